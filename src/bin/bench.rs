@@ -0,0 +1,441 @@
+//! ブラウザなしで動くネイティブベンチマーク
+//!
+//! マーカーのアニメーション・投影・空間インデックス・クラスタリングを
+//! N個×Mティック実行し、ブラウザ版と同じ `BenchmarkRun` のJSON/CSVを出力する。
+//! 1ティックはアニメーションの1フレーム。`--scenario` を指定するとカオスシナリオを
+//! アプリ時間に沿って再生し、マーカー数と表示範囲を変えながら計測する。
+//! `cargo run --release --bin bench --target x86_64-unknown-linux-gnu -- --markers 10000 --ticks 600`
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use leaflet_webgl_hybrid_poc::sim::cluster::grid_cluster;
+use leaflet_webgl_hybrid_poc::sim::markers::{MarkerSim, DEFAULT_SPEED_RANGE};
+use leaflet_webgl_hybrid_poc::sim::projection::Viewport;
+use leaflet_webgl_hybrid_poc::sim::rng::SimRng;
+use leaflet_webgl_hybrid_poc::sim::spatial::{Rect, SpatialGrid};
+use leaflet_webgl_hybrid_poc::utils::alloc_tracker::{self, FrameAllocCounter};
+use leaflet_webgl_hybrid_poc::utils::benchmark_export::{compare_with_baseline, BenchmarkRun, RegressionThresholds, BENCHMARK_SCHEMA_VERSION};
+use leaflet_webgl_hybrid_poc::utils::chaos_intensity::{ChaosEventType, ChaosIntensity};
+use leaflet_webgl_hybrid_poc::utils::chaos_scenario::{ChaosScenario, ScenarioAction, ScenarioRunner};
+use leaflet_webgl_hybrid_poc::utils::frame_stats::FrameTimeHistogram;
+
+/// 空間インデックスのセルサイズ（px）
+const GRID_CELL_PX: f64 = 64.0;
+/// クラスタリングのセルサイズ（px）
+const CLUSTER_CELL_PX: f64 = 60.0;
+/// 1ティックあたりの矩形検索回数（ホバー判定などを想定）
+const QUERIES_PER_TICK: usize = 32;
+const QUERY_SIZE_PX: f64 = 256.0;
+/// 1ティックで進めるアプリ時間（ms）
+const FRAME_MS: f64 = 1000.0 / 60.0;
+/// 深刻度100%で追加・削除するマーカー数（`MapChaosPanel` と同じ）
+const MAX_BURST: f64 = 500.0;
+/// 追加でこの数を超えないようにする（`MapChaosPanel` と同じ）
+const MAX_OBJECTS: usize = 20_000;
+
+const PHASES: [&str; 6] = ["scenario", "animate", "project", "index", "query", "cluster"];
+
+/// 回帰とみなす中央値ティック時間の増加率（%）。同じランナーでも数%〜10%程度は揺れるため余裕を持たせる
+const DEFAULT_MAX_REGRESSION_PCT: f64 = 25.0;
+
+struct Options {
+    markers: usize,
+    ticks: usize,
+    warmup: usize,
+    seed: u64,
+    width: f64,
+    height: f64,
+    scenario: Option<String>,
+    /// 計測を繰り返す回数（結果はティック時間の中央値が真ん中の回）
+    runs: usize,
+    csv: bool,
+    out: Option<String>,
+    baseline: Option<String>,
+    max_regression_pct: f64,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, String> {
+        let mut options = Self {
+            markers: 10_000,
+            ticks: 600,
+            warmup: 60,
+            seed: 42,
+            width: 1280.0,
+            height: 720.0,
+            scenario: None,
+            runs: 1,
+            csv: false,
+            out: None,
+            baseline: None,
+            max_regression_pct: DEFAULT_MAX_REGRESSION_PCT,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} requires a value", arg));
+            match arg.as_str() {
+                "--markers" => options.markers = parse(&value()?)?,
+                "--ticks" => options.ticks = parse(&value()?)?,
+                "--warmup" => options.warmup = parse(&value()?)?,
+                "--seed" => options.seed = parse(&value()?)?,
+                "--width" => options.width = parse(&value()?)?,
+                "--height" => options.height = parse(&value()?)?,
+                "--scenario" => options.scenario = Some(value()?),
+                "--runs" => options.runs = parse::<usize>(&value()?)?.max(1),
+                "--csv" => options.csv = true,
+                "--out" => options.out = Some(value()?),
+                "--baseline" => options.baseline = Some(value()?),
+                "--max-regression" => options.max_regression_pct = parse(&value()?)?,
+                "-h" | "--help" => {
+                    println!(
+                        "Usage: bench [--markers N] [--ticks M] [--warmup W] [--seed S] \
+                         [--width PX] [--height PX] [--scenario NAME|FILE] [--runs R] [--csv] [--out FILE] \
+                         [--baseline FILE] [--max-regression PCT]"
+                    );
+                    std::process::exit(0);
+                }
+                other => return Err(format!("Unknown argument: {}", other)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid value: {}", value))
+}
+
+/// 組み込みシナリオ名、またはシナリオJSONのパス
+fn load_scenario(name: &str) -> Result<ChaosScenario, String> {
+    if let Some(scenario) = ChaosScenario::builtin(name) {
+        return Ok(scenario);
+    }
+    let json = std::fs::read_to_string(name).map_err(|e| format!("{}: {}", name, e))?;
+    ChaosScenario::from_json(&json)
+}
+
+/// シナリオの再生状態（マップのカオスモードと同じようにマーカー数と表示範囲を変える）
+struct ScenarioState {
+    runner: ScenarioRunner,
+    intensity: ChaosIntensity,
+    /// ランダムなパン・ズーム用
+    rng: SimRng,
+    now_ms: f64,
+    events: usize,
+}
+
+/// 1ティック分の処理。各フェーズの所要時間（ms）を返す
+struct Pipeline {
+    viewport: Viewport,
+    sim: MarkerSim,
+    /// 追加するマーカーの配置用
+    spawn_rng: SimRng,
+    grid: SpatialGrid,
+    points: Vec<(f64, f64)>,
+    query_rng: SimRng,
+    hits: Vec<u32>,
+    visible: usize,
+    clusters: usize,
+    scenario: Option<ScenarioState>,
+}
+
+impl Pipeline {
+    fn new(options: &Options, scenario: Option<ChaosScenario>) -> Self {
+        let viewport = Viewport::tokyo(options.width, options.height);
+        let mut rng = SimRng::new(options.seed);
+        Self {
+            viewport,
+            sim: MarkerSim::new(options.markers, viewport.bounds(), DEFAULT_SPEED_RANGE, &mut rng),
+            spawn_rng: rng,
+            grid: SpatialGrid::new(options.width, options.height, GRID_CELL_PX),
+            points: Vec::with_capacity(options.markers),
+            query_rng: SimRng::new(options.seed.wrapping_add(1)),
+            hits: Vec::new(),
+            visible: 0,
+            clusters: 0,
+            scenario: scenario.map(|scenario| ScenarioState {
+                runner: ScenarioRunner::repeating(scenario, options.seed, 0.0),
+                intensity: ChaosIntensity::default(),
+                rng: SimRng::new(options.seed.wrapping_add(2)),
+                now_ms: 0.0,
+                events: 0,
+            }),
+        }
+    }
+
+    /// 時刻表のアクションをシーンに反映する（描画モードとJSヒープへの操作はネイティブでは対象外）
+    fn apply_scenario(&mut self) {
+        let Some(state) = self.scenario.as_mut() else {
+            return;
+        };
+        state.now_ms += FRAME_MS;
+        for action in state.runner.due(state.now_ms) {
+            let burst = (MAX_BURST * state.intensity.profile().severity).round() as usize;
+            let count = self.sim.markers().len();
+            match action {
+                ScenarioAction::SetIntensity(level) => state.intensity = ChaosIntensity::saturating(level),
+                ScenarioAction::SetObjectCount(count) => {
+                    self.sim.set_count((count.max(0) as usize).min(MAX_OBJECTS), &mut self.spawn_rng);
+                }
+                ScenarioAction::SetViewport(point) => {
+                    self.viewport.center_lat = point.lat;
+                    self.viewport.center_lng = point.lng;
+                    self.viewport.zoom = point.zoom.round();
+                }
+                ScenarioAction::Fire(event_type) => {
+                    state.events += 1;
+                    match event_type {
+                        ChaosEventType::ObjectSpawn => {
+                            self.sim.set_count((count + burst).min(MAX_OBJECTS), &mut self.spawn_rng);
+                        }
+                        ChaosEventType::ObjectRemove => {
+                            self.sim.set_count(count.saturating_sub(burst), &mut self.spawn_rng);
+                        }
+                        // `MapChaosPanel` のランダムなパン・ズームと同じ範囲
+                        ChaosEventType::ViewportChange => {
+                            let tokyo = Viewport::tokyo(self.viewport.width, self.viewport.height);
+                            self.viewport.center_lat = tokyo.center_lat + state.rng.range(-0.05, 0.05);
+                            self.viewport.center_lng = tokyo.center_lng + state.rng.range(-0.05, 0.05);
+                            self.viewport.zoom = 11.0 + (state.rng.next_f64() * 6.0).floor();
+                        }
+                        ChaosEventType::ModeSwitch | ChaosEventType::MemoryPressure => {}
+                    }
+                }
+                ScenarioAction::PhaseStarted { .. } | ScenarioAction::Finished => {}
+            }
+        }
+    }
+
+    fn tick(&mut self) -> [f64; 6] {
+        let mut timings = [0.0; 6];
+        let mut lap = Instant::now();
+        let mut mark = |phase: usize| {
+            timings[phase] = lap.elapsed().as_secs_f64() * 1000.0;
+            lap = Instant::now();
+        };
+
+        self.apply_scenario();
+        mark(0);
+
+        self.sim.tick();
+        mark(1);
+
+        let bounds = self.viewport.bounds();
+        let viewport = self.viewport;
+        self.points.clear();
+        self.points.extend(
+            self.sim
+                .markers()
+                .iter()
+                .filter(|m| bounds.contains(m.lat, m.lng))
+                .map(|m| viewport.to_container_point(m.lat, m.lng)),
+        );
+        self.visible = self.points.len();
+        mark(2);
+
+        self.grid.rebuild(self.points.iter().copied());
+        mark(3);
+
+        self.hits.clear();
+        for _ in 0..QUERIES_PER_TICK {
+            let min_x = self.query_rng.range(0.0, (self.viewport.width - QUERY_SIZE_PX).max(0.0));
+            let min_y = self.query_rng.range(0.0, (self.viewport.height - QUERY_SIZE_PX).max(0.0));
+            let rect = Rect { min_x, min_y, max_x: min_x + QUERY_SIZE_PX, max_y: min_y + QUERY_SIZE_PX };
+            self.grid.query(&rect, &mut self.hits);
+        }
+        mark(4);
+
+        self.clusters = grid_cluster(&self.points, CLUSTER_CELL_PX).len();
+        mark(5);
+
+        timings
+    }
+}
+
+/// `Date.toISOString()` と同じ形式の現在時刻
+fn iso8601_now() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = now.as_secs() as i64;
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // days since 1970-01-01 → 年月日（Howard Hinnantのcivil_from_days）
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60,
+        now.subsec_millis()
+    )
+}
+
+/// 1回分の計測結果
+struct Measurement {
+    run: BenchmarkRun,
+    pipeline: Pipeline,
+    phase_totals: [f64; 6],
+}
+
+/// 同じシードの新しいシーンでウォームアップしてから `ticks` 回計測する
+fn measure(options: &Options, scenario: Option<ChaosScenario>, mode: &str) -> Measurement {
+    let mut pipeline = Pipeline::new(options, scenario);
+    for _ in 0..options.warmup {
+        pipeline.tick();
+    }
+
+    let mut histogram = FrameTimeHistogram::new();
+    let mut allocs = FrameAllocCounter::default();
+    let mut phase_totals = [0.0; 6];
+    let mut min_tick_ms = f64::MAX;
+    alloc_tracker::reset_peak();
+
+    let started = Instant::now();
+    for _ in 0..options.ticks {
+        let timings = pipeline.tick();
+        let tick_ms: f64 = timings.iter().sum();
+
+        histogram.record(tick_ms, started.elapsed().as_secs_f64() * 1000.0);
+        min_tick_ms = min_tick_ms.min(tick_ms);
+        for (total, ms) in phase_totals.iter_mut().zip(timings) {
+            *total += ms;
+        }
+        if let Some(stats) = alloc_tracker::current_stats() {
+            allocs.on_frame(&stats);
+        }
+    }
+    let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let frame_stats = histogram.stats();
+    let fps = |ms: f64| if ms > 0.0 { 1000.0 / ms } else { 0.0 };
+    let allocations = alloc_tracker::current_stats().map(|_| allocs.stats());
+    let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+    let tracking = if cfg!(feature = "alloc-tracking") { "+alloc-tracking" } else { "" };
+
+    let run = BenchmarkRun {
+        schema_version: BENCHMARK_SCHEMA_VERSION,
+        mode: mode.to_string(),
+        object_count: options.markers as i32,
+        seed: Some(options.seed),
+        recorded_at: iso8601_now(),
+        duration_ms,
+        min_fps: fps(frame_stats.max_ms),
+        max_fps: fps(min_tick_ms),
+        avg_fps: fps(frame_stats.avg_ms),
+        frame_stats,
+        memory_mb: allocations.map(|a| a.peak_bytes as f64 / 1024.0 / 1024.0),
+        allocations,
+        user_agent: format!("native ({}; {})", std::env::consts::OS, std::env::consts::ARCH),
+        build_profile: format!("{}+system{}", profile, tracking),
+    };
+    Measurement { run, pipeline, phase_totals }
+}
+
+fn main() {
+    let options = match Options::from_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    let scenario = match options.scenario.as_deref().map(load_scenario).transpose() {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("Failed to load scenario: {}", e);
+            std::process::exit(2);
+        }
+    };
+    let mode = match &scenario {
+        Some(scenario) => format!("native/{}", scenario.name),
+        None => "native".to_string(),
+    };
+
+    // 1回ごとの揺らぎ（他プロセス・CPUクロック）を避けるため、中央値ティック時間が真ん中の回を結果にする
+    let mut measurements: Vec<Measurement> = (0..options.runs)
+        .map(|_| measure(&options, scenario.clone(), &mode))
+        .collect();
+    measurements.sort_by(|a, b| a.run.frame_stats.p50_ms.total_cmp(&b.run.frame_stats.p50_ms));
+    if options.runs > 1 {
+        let p50s: Vec<String> = measurements.iter().map(|m| format!("{:.3}", m.run.frame_stats.p50_ms)).collect();
+        eprintln!("{} runs, p50 {} ms (using the median run)", options.runs, p50s.join(" / "));
+    }
+    let Measurement { run, pipeline, phase_totals } = measurements.swap_remove(options.runs / 2);
+
+    // フェーズ別の内訳は標準エラーに出す（標準出力はエクスポート形式のみ）
+    eprintln!(
+        "{} × {} ticks (seed {}), markers {}, visible {}, clusters {}, last query hits {}",
+        run.mode,
+        options.ticks,
+        options.seed,
+        pipeline.sim.markers().len(),
+        pipeline.visible,
+        pipeline.clusters,
+        pipeline.hits.len()
+    );
+    if let Some(state) = &pipeline.scenario {
+        eprintln!("  scenario events {} (intensity {})", state.events, state.intensity);
+    }
+    for (name, total) in PHASES.iter().zip(phase_totals) {
+        eprintln!("  {:<8} {:>8.3} ms/tick", name, total / options.ticks.max(1) as f64);
+    }
+    eprintln!(
+        "  p50 {:.2}ms / p95 {:.2}ms / p99 {:.2}ms / max {:.2}ms",
+        run.frame_stats.p50_ms, run.frame_stats.p95_ms, run.frame_stats.p99_ms, run.frame_stats.max_ms
+    );
+
+    let output = if options.csv { run.to_csv() } else { run.to_json() };
+    match &options.out {
+        Some(path) => std::fs::write(path, output).unwrap_or_else(|e| panic!("Failed to write {}: {}", path, e)),
+        None => println!("{}", output),
+    }
+
+    if let Some(path) = &options.baseline {
+        let baseline = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| BenchmarkRun::from_json(&json))
+            .unwrap_or_else(|e| panic!("Failed to load baseline {}: {}", path, e));
+
+        // 裾の指標（P95・P99など）はマイクロ秒単位の揺らぎが大きいため参考表示にとどめる
+        let comparison = compare_with_baseline(&baseline, &run, &RegressionThresholds::default());
+        for warning in &comparison.warnings {
+            eprintln!("warning: {}", warning);
+        }
+        for delta in &comparison.deltas {
+            eprintln!(
+                "  info {}: {:.2} → {:.2} {} ({:+.1}%)",
+                delta.name,
+                delta.baseline,
+                delta.current,
+                delta.unit,
+                delta.delta_pct()
+            );
+        }
+
+        let ceiling = baseline.frame_stats.p50_ms * (1.0 + options.max_regression_pct / 100.0);
+        let passed = run.frame_stats.p50_ms <= ceiling;
+        eprintln!(
+            "  {} 中央値ティック時間: {:.3} → {:.3} ms (上限 {:.3} ms, +{:.0}%)",
+            if passed { "PASS" } else { "FAIL" },
+            baseline.frame_stats.p50_ms,
+            run.frame_stats.p50_ms,
+            ceiling,
+            options.max_regression_pct
+        );
+        if !passed {
+            std::process::exit(1);
+        }
+    }
+}
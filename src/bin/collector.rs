@@ -0,0 +1,204 @@
+//! テレメトリ受信用のローカルコレクター
+//!
+//! ブラウザから送られたレコードをJSONL形式で追記する。
+//! `cargo run --bin collector --target x86_64-unknown-linux-gnu -- --addr 127.0.0.1:8787 --out telemetry.jsonl`
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 1リクエストあたりの最大ボディサイズ
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+struct Config {
+    addr: String,
+    out: String,
+}
+
+impl Config {
+    fn from_args() -> Result<Self, String> {
+        let mut config = Self {
+            addr: "127.0.0.1:8787".to_string(),
+            out: "telemetry.jsonl".to_string(),
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--addr" => config.addr = args.next().ok_or("--addr requires a value")?,
+                "--out" => config.out = args.next().ok_or("--out requires a value")?,
+                "-h" | "--help" => {
+                    println!("Usage: collector [--addr HOST:PORT] [--out FILE]");
+                    std::process::exit(0);
+                }
+                other => return Err(format!("Unknown argument: {}", other)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request, String> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(|e| e.to_string())?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or("Empty request")?.to_string();
+    let path = parts.next().ok_or("Missing request path")?.to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| "Invalid Content-Length")?;
+            }
+        }
+    }
+
+    if content_length > MAX_BODY_BYTES {
+        return Err(format!("Body too large: {} bytes", content_length));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+
+    Ok(Request { method, path, body })
+}
+
+/// ボディ（レコード1件または配列）を受信時刻付きのJSON行に変換
+fn records_to_lines(body: &[u8], received_at_ms: u128) -> Result<Vec<String>, String> {
+    let value: serde_json::Value =
+        serde_json::from_slice(body).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let records = match value {
+        serde_json::Value::Array(records) => records,
+        record => vec![record],
+    };
+
+    records
+        .into_iter()
+        .map(|mut record| {
+            let object = record.as_object_mut().ok_or("Record must be a JSON object")?;
+            object.insert("received_at".to_string(), serde_json::json!(received_at_ms as u64));
+            Ok(record.to_string())
+        })
+        .collect()
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Methods: POST, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Content-Type\r\n\
+         Content-Type: text/plain\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+fn handle_connection(mut stream: TcpStream, out: &Mutex<File>) {
+    let request = match read_request(&mut BufReader::new(&mut stream)) {
+        Ok(request) => request,
+        Err(e) => {
+            respond(&mut stream, "400 Bad Request", &e);
+            return;
+        }
+    };
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("OPTIONS", _) => respond(&mut stream, "204 No Content", ""),
+        ("GET", "/health") => respond(&mut stream, "200 OK", "ok"),
+        ("POST", "/collect") => {
+            let received_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            match records_to_lines(&request.body, received_at) {
+                Ok(lines) => {
+                    let mut file = out.lock().unwrap_or_else(|e| e.into_inner());
+                    for line in &lines {
+                        if let Err(e) = writeln!(file, "{}", line) {
+                            respond(&mut stream, "500 Internal Server Error", &e.to_string());
+                            return;
+                        }
+                    }
+                    println!("Stored {} record(s)", lines.len());
+                    respond(&mut stream, "204 No Content", "");
+                }
+                Err(e) => respond(&mut stream, "400 Bad Request", &e),
+            }
+        }
+        _ => respond(&mut stream, "404 Not Found", "not found"),
+    }
+}
+
+fn main() {
+    let config = match Config::from_args() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&config.out)
+        .unwrap_or_else(|e| panic!("Failed to open {}: {}", config.out, e));
+    let out = Arc::new(Mutex::new(file));
+
+    let listener = TcpListener::bind(&config.addr)
+        .unwrap_or_else(|e| panic!("Failed to bind {}: {}", config.addr, e));
+    println!("Collecting telemetry on http://{}/collect -> {}", config.addr, config.out);
+
+    for stream in listener.incoming().flatten() {
+        let out = out.clone();
+        std::thread::spawn(move || handle_connection(stream, &out));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_post_request_with_body() {
+        let raw = b"POST /collect HTTP/1.1\r\nHost: localhost\r\nContent-Type: text/plain\r\ncontent-length: 14\r\n\r\n[{\"kind\":\"x\"}]";
+        let request = read_request(&mut &raw[..]).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/collect");
+        assert_eq!(request.body, b"[{\"kind\":\"x\"}]".to_vec());
+    }
+
+    #[test]
+    fn converts_batches_to_stamped_lines() {
+        let lines = records_to_lines(br#"[{"kind":"load"},{"kind":"latency"}]"#, 42).unwrap();
+        assert_eq!(lines, vec![
+            r#"{"kind":"load","received_at":42}"#.to_string(),
+            r#"{"kind":"latency","received_at":42}"#.to_string(),
+        ]);
+
+        assert!(records_to_lines(b"[1, 2]", 0).is_err());
+        assert!(records_to_lines(b"not json", 0).is_err());
+    }
+}
@@ -1,101 +1,123 @@
 use dioxus::prelude::*;
 use wasm_bindgen::closure::Closure;
-use crate::utils::fps_counter::{startFPSCounter, stopFPSCounter};
+use crate::utils::fps_counter::{startFPSCounter, stopFPSCounter, startFrameTimer, stopFrameTimer};
+use crate::utils::frame_stats::{FrameTimeHistogram, FrameTimeStats};
+use crate::utils::benchmark_export::BenchmarkRun;
+use crate::utils::benchmark_metrics::{DeviceClass, ScoringConfig};
+use crate::utils::clock::now_ms;
+use crate::utils::memory::MemorySample;
+use crate::utils::alloc_tracker::{self, FrameAllocCounter, FrameAllocStats};
+use crate::hooks::{use_benchmark, use_latency_probe, BenchmarkHandle};
+use crate::utils::telemetry::{Telemetry, TelemetryPayload};
+use crate::utils::long_tasks::{self, LongTaskEntry, LongTaskKind, LongTaskMonitor, PhaseScope, PipelinePhase};
+use crate::utils::profiler::profile_scope;
+use crate::types::PerformanceMetrics;
+use crate::components::benchmark_export::BenchmarkExportPanel;
+use crate::components::sparkline::Sparkline;
+use crate::components::latency_breakdown::LatencyBreakdown;
+use crate::utils::input_latency::{InteractionKind, LatencyTag};
 use std::rc::Rc;
 use std::cell::RefCell;
 
-#[derive(Clone, Debug, Default)]
-pub struct BenchmarkMetrics {
-    pub fps: f64,
-    pub min_fps: f64,
-    pub max_fps: f64,
-    pub avg_fps: f64,
-    pub frame_count: u32,
-}
-
-impl BenchmarkMetrics {
-    #[inline]
-    fn update_fps(&mut self, current_fps: f64) {
-        self.frame_count += 1;
-        self.fps = current_fps;
-        
-        if self.min_fps == 0.0 || current_fps < self.min_fps {
-            self.min_fps = current_fps;
-        }
-        if current_fps > self.max_fps {
-            self.max_fps = current_fps;
-        }
-        
-        // 移動平均でavg_fpsを計算
-        self.avg_fps = (self.avg_fps * (self.frame_count - 1) as f64 + current_fps) / self.frame_count as f64;
-    }
-    
-    #[inline]
-    fn get_performance_color(&self) -> &'static str {
-        if self.avg_fps >= 55.0 {
-            "#4CAF50"
-        } else if self.avg_fps >= 30.0 {
-            "#FF9800"
-        } else {
-            "#f44336"
-        }
-    }
-    
-    #[inline]
-    fn get_performance_text(&self) -> &'static str {
-        if self.avg_fps >= 55.0 {
-            "良好"
-        } else if self.avg_fps >= 30.0 {
-            "可"
-        } else {
-            "要改善"
-        }
-    }
-}
-
 #[component]
 pub fn BenchmarkPanel(
     mut object_count: Signal<i32>,
     render_mode: String,
+    /// マーカー配置のシード（記録に残す）
+    scene_seed: ReadOnlySignal<Option<u64>>,
 ) -> Element {
+    let _render_scope = PhaseScope::new(PipelinePhase::Render);
+    profile_scope!("render:BenchmarkPanel");
+    
     let mut fps = use_signal(|| 0.0);
-    let mut metrics = use_signal(BenchmarkMetrics::default);
-    let mut is_recording = use_signal(|| false);
+    let mut benchmark = use_benchmark();
+    let mut metrics = benchmark.metrics;
+    let is_recording = benchmark.is_recording;
+    let mut recording_started_at = use_signal(|| 0.0);
+    let mut last_run = use_signal(|| None::<BenchmarkRun>);
+    let telemetry = use_context::<Telemetry>();
+    let latency = use_latency_probe();
+    let mut recording_ended_at = use_signal(|| 0.0);
+    
+    // 記録中に発生した長時間タスク（発生頻度が低いためSignalで直接保持）
+    let mut long_task_entries = use_signal(Vec::<LongTaskEntry>::new);
+    // 監視は記録中のみ行う（`window.__benchPhase` も記録中だけ設定される）
+    let mut long_task_monitor = use_signal(|| None::<LongTaskMonitor>);
+    // 直近2分間のメモリ推移（記録中かどうかに関わらず表示する）
+    let mut memory_history = use_signal(Vec::<MemorySample>::new);
+    
+    // フレーム時間ヒストグラム（毎フレーム更新するためSignalの外で保持）
+    let frame_histogram = use_hook(|| Rc::new(RefCell::new(FrameTimeHistogram::new())));
+    // フレームごとの割り当て回数（alloc-tracking有効時のみ集計される）
+    let frame_allocs = use_hook(|| Rc::new(RefCell::new(FrameAllocCounter::default())));
     
     // FPSコールバックを保持するためのSignal
     let mut callback_holder = use_signal(|| None::<Rc<RefCell<Closure<dyn FnMut(f64)>>>>);
+    let mut frame_callback_holder = use_signal(|| None::<Rc<RefCell<Closure<dyn FnMut(f64, f64)>>>>);
     
     // FPS計測を開始（初回のみ実行）
+    let timer_histogram = frame_histogram.clone();
+    let timer_allocs = frame_allocs.clone();
     use_effect(move || {
         // 既にコールバックが設定されている場合は何もしない
         if callback_holder.read().is_some() {
             return;
         }
         
+        let histogram = timer_histogram.clone();
+        let allocs = timer_allocs.clone();
         let callback = Closure::new(move |current_fps: f64| {
+            profile_scope!("fps_sample");
             fps.set(current_fps);
             
-            // メトリクスを更新
+            // メモリも1秒ごとにサンプリング
+            let memory = MemorySample::capture();
+            memory_history.with_mut(|history| {
+                history.push(memory);
+                if history.len() > 120 {
+                    history.remove(0);
+                }
+            });
+            
+            // メトリクスを更新（フレーム時間の集計もここで1秒ごとに反映）
             if is_recording() {
-                metrics.with_mut(|m| m.update_fps(current_fps));
+                let frame_stats = histogram.borrow().stats();
+                let allocations = alloc_tracker::current_stats().map(|_| allocs.borrow().stats());
+                metrics.with_mut(|m| {
+                    m.update_fps(current_fps);
+                    m.frame_stats = frame_stats;
+                    m.allocations = allocations;
+                    m.record_memory(memory.total_mb());
+                });
+            }
+        });
+        
+        // 毎フレームのフレーム時間をヒストグラムに記録
+        let histogram = timer_histogram.clone();
+        let allocs = timer_allocs.clone();
+        let frame_callback = Closure::new(move |frame_ms: f64, timestamp: f64| {
+            profile_scope!("frame_sample");
+            if *is_recording.peek() {
+                histogram.borrow_mut().record(frame_ms, timestamp);
+                if let Some(stats) = alloc_tracker::current_stats() {
+                    allocs.borrow_mut().on_frame(&stats);
+                }
             }
         });
         
         startFPSCounter(&callback);
+        startFrameTimer(&frame_callback);
         
         // コールバックを保持
         callback_holder.set(Some(Rc::new(RefCell::new(callback))));
+        frame_callback_holder.set(Some(Rc::new(RefCell::new(frame_callback))));
     });
     
     // コンポーネントがアンマウントされる時にFPSカウンターを停止
     use_drop(move || {
         stopFPSCounter();
-    });
-    
-    // パフォーマンス評価をメモ化（metricsが変更されたときのみ再計算）
-    let performance_evaluation = use_memo(move || {
-        let m = metrics.read();
-        (m.get_performance_color(), m.get_performance_text())
+        stopFrameTimer();
+        long_task_monitor.write().take();
     });
     
     // ボタンの状態をメモ化
@@ -107,15 +129,17 @@ pub fn BenchmarkPanel(
         }
     });
     
-    // FPSカテゴリをメモ化
-    let fps_category = use_memo(move || {
-        match fps() {
-            f if f >= 55.0 => "🔥 優秀",
-            f if f >= 30.0 => "✅ 良好",
-            f if f >= 15.0 => "⚠️ 警告",
-            _ => "🆘 危険",
-        }
-    });
+    // 現在のFPSの評価（記録結果と同じ段階・端末クラスで判定）
+    let live_score = benchmark.score_fps(fps());
+    
+    let mode_for_export = render_mode.clone();
+    
+    // タイムラインの右端（記録中は現在時刻、FPS更新ごとの再描画で伸びる）
+    let timeline_end = if is_recording() {
+        now_ms()
+    } else {
+        recording_ended_at()
+    };
     
     rsx! {
         div {
@@ -134,7 +158,7 @@ pub fn BenchmarkPanel(
                     oninput: move |evt| {
                         if let Ok(val) = evt.value().parse::<i32>() {
                             web_sys::console::log_1(&format!("[BenchmarkPanel] Slider changed to: {}", val).into());
-                            object_count.set(val);
+                            latency.measure(LatencyTag::new(InteractionKind::Slider, "object-count"), || object_count.set(val));
                             web_sys::console::log_1(&format!("[BenchmarkPanel] object_count Signal updated to: {}", object_count()).into());
                         }
                     }
@@ -156,7 +180,7 @@ pub fn BenchmarkPanel(
                     {format!("現在のFPS: {:.1} ", fps())},
                     span {
                         class: "fps-category",
-                        {fps_category()}
+                        {format!("{} {}", live_score.icon(), live_score.text())}
                     }
                 }
                 
@@ -164,12 +188,42 @@ pub fn BenchmarkPanel(
                     class: "recording-controls",
                     button {
                         onclick: move |_| {
+                            let now = now_ms();
+                            
                             if is_recording() {
-                                is_recording.set(false);
+                                benchmark.stop_recording();
+                                long_task_monitor.set(None);
+                                recording_ended_at.set(now);
+                                
+                                // 最終的なフレーム時間を反映して記録を確定
+                                let frame_stats = frame_histogram.borrow().stats();
+                                metrics.with_mut(|m| m.frame_stats = frame_stats);
+                                if metrics.read().frame_count > 0 {
+                                    let run = metrics.read().to_run(
+                                        &mode_for_export,
+                                        object_count(),
+                                        *scene_seed.peek(),
+                                        now - recording_started_at(),
+                                    );
+                                    telemetry.report(TelemetryPayload::Performance(PerformanceMetrics {
+                                        fps: run.avg_fps,
+                                        memory_usage: run.memory_mb,
+                                        dropped_frames: run.frame_stats.over_16_7ms,
+                                        timestamp: js_sys::Date::now(),
+                                    }));
+                                    last_run.set(Some(run));
+                                }
                             } else {
                                 // 記録開始時にメトリクスをリセット
-                                metrics.set(BenchmarkMetrics::default());
-                                is_recording.set(true);
+                                frame_histogram.borrow_mut().reset();
+                                frame_allocs.borrow_mut().reset();
+                                alloc_tracker::reset_peak();
+                                long_task_entries.write().clear();
+                                long_task_monitor.set(Some(LongTaskMonitor::start(move |entry| {
+                                    long_task_entries.write().push(entry);
+                                })));
+                                recording_started_at.set(now);
+                                benchmark.start_recording();
                             }
                         },
                         style: format!("background: {};", button_state.read().0),
@@ -185,15 +239,290 @@ pub fn BenchmarkPanel(
                         p { {format!("最大FPS: {:.1}", metrics().max_fps)} }
                         p { {format!("平均FPS: {:.1}", metrics().avg_fps)} }
                         p { "フレーム数: {metrics().frame_count}" }
+                        if let Some(peak) = metrics().peak_memory_mb {
+                            p { {format!("ピークメモリ: {:.1}MB", peak)} }
+                        }
+                        if let Some(allocations) = metrics().allocations {
+                            AllocationReport { stats: allocations }
+                        }
+                        
+                        FrameTimeReport { stats: metrics().frame_stats }
                         p {
                             class: "performance-score",
-                            style: format!("font-weight: bold; color: {};", performance_evaluation.read().0),
-                            {"パフォーマンス: "},
-                            {performance_evaluation.read().1}
+                            style: format!("font-weight: bold; color: {};", benchmark.performance_score.read().color()),
+                            {format!("パフォーマンス: {} (目標 {:.0}fps / {})", benchmark.performance_score.read().text(), benchmark.scoring.read().target_fps(), benchmark.scoring.read().device_class.label())}
+                        }
+                        ul {
+                            class: "recommendations",
+                            for (i, rec) in benchmark.recommendations.read().iter().enumerate() {
+                                li { key: "{i}", "{rec}" }
+                            }
+                        }
+                    }
+                }
+                
+                if is_recording() || metrics().frame_count > 0 {
+                    LongTaskTimeline {
+                        entries: long_task_entries(),
+                        start_ms: recording_started_at(),
+                        end_ms: timeline_end,
+                    }
+                }
+                
+                MemoryChart { samples: memory_history() }
+                
+                // スライダーとマップへの入力の遅延（FPSの更新ごとに再描画される）
+                if !latency.tagged_stats().is_empty() {
+                    h4 { "入力遅延" }
+                    LatencyBreakdown {
+                        stats: latency.tagged_stats(),
+                        on_target_change: move |(tag, target_ms)| latency.set_target(tag, target_ms),
+                    }
+                }
+                
+                if cfg!(feature = "benchmarking") {
+                    ScoringSettings { benchmark }
+                }
+                
+                if !is_recording() {
+                    BenchmarkExportPanel { run: last_run() }
+                }
+            }
+        }
+    }
+}
+
+/// 端末クラス・目標FPS・評価段階の設定（benchmarking有効時のみ表示）
+#[component]
+fn ScoringSettings(benchmark: BenchmarkHandle) -> Element {
+    let config = benchmark.scoring.read().clone();
+    let [excellent, good, fair] = config.thresholds();
+    let avg_fps = benchmark.metrics.read().avg_fps;
+    
+    let update = move |f: &dyn Fn(&mut ScoringConfig)| {
+        let mut handle = benchmark;
+        let mut next = handle.scoring.peek().clone();
+        f(&mut next);
+        handle.set_scoring(next);
+    };
+    
+    rsx! {
+        div {
+            class: "scoring-settings",
+            h4 { "評価基準" }
+            div {
+                class: "control-group",
+                label { "端末クラス" }
+                select {
+                    value: config.device_class.name(),
+                    onchange: move |evt| {
+                        if let Some(class) = DeviceClass::from_name(&evt.value()) {
+                            update(&|c| {
+                                c.device_class = class;
+                                c.target_fps = None;
+                            });
+                        }
+                    },
+                    for class in DeviceClass::ALL {
+                        option {
+                            key: "{class.name()}",
+                            value: class.name(),
+                            selected: class == config.device_class,
+                            {format!("{} ({:.0}fps)", class.label(), class.target_fps())}
                         }
                     }
                 }
             }
+            div {
+                class: "control-group",
+                label { {format!("目標FPS: {:.0}", config.target_fps())} }
+                input {
+                    r#type: "number",
+                    min: "10",
+                    max: "240",
+                    value: "{config.target_fps()}",
+                    onchange: move |evt| {
+                        if let Ok(target) = evt.value().parse::<f64>() {
+                            update(&|c| c.target_fps = Some(target.clamp(10.0, 240.0)));
+                        }
+                    }
+                }
+            }
+            table {
+                style: "font-size: 0.8rem;",
+                tr { th { "段階" } th { "下限FPS" } th { "目標比" } }
+                for (label, fps, ratio) in [("優秀", excellent, config.tiers.excellent), ("良好", good, config.tiers.good), ("可", fair, config.tiers.fair)] {
+                    tr {
+                        key: "{label}",
+                        td { "{label}" }
+                        td { {format!("{:.1}", fps)} }
+                        td {
+                            input {
+                                r#type: "number",
+                                min: "0",
+                                max: "100",
+                                step: "5",
+                                style: "width: 4em;",
+                                value: "{(ratio * 100.0).round()}",
+                                onchange: move |evt| {
+                                    if let Ok(percent) = evt.value().parse::<f64>() {
+                                        let ratio = (percent / 100.0).clamp(0.0, 1.0);
+                                        update(&|c| match label {
+                                            "優秀" => c.tiers.excellent = ratio,
+                                            "良好" => c.tiers.good = ratio,
+                                            _ => c.tiers.fair = ratio,
+                                        });
+                                    }
+                                }
+                            }
+                            "%"
+                        }
+                    }
+                }
+            }
+            if avg_fps > 0.0 {
+                p {
+                    style: "font-size: 0.8rem;",
+                    {format!("平均FPS {:.1} は目標の {:.0}%", avg_fps, avg_fps / config.target_fps() * 100.0)}
+                }
+            }
+        }
+    }
+}
+
+/// フレーム時間のパーセンタイルとカクつき回数の表示
+#[component]
+fn FrameTimeReport(stats: FrameTimeStats) -> Element {
+    if stats.frame_count == 0 {
+        return rsx! {};
+    }
+    
+    let p99_color = if stats.p99_ms > 33.0 {
+        "#f44336"
+    } else if stats.p99_ms > 16.7 {
+        "#FF9800"
+    } else {
+        "#4CAF50"
+    };
+    
+    rsx! {
+        div {
+            class: "frame-time-report",
+            h4 { "フレーム時間" }
+            p { {format!("P50: {:.1}ms / P95: {:.1}ms", stats.p50_ms, stats.p95_ms)} }
+            p {
+                style: format!("color: {};", p99_color),
+                {format!("P99: {:.1}ms", stats.p99_ms)}
+            }
+            p { {format!(">16.7ms: {} ({:.1}%)", stats.over_16_7ms, stats.jank_ratio())} }
+            p { {format!(">33ms: {} / >50ms: {}", stats.over_33ms, stats.over_50ms)} }
+            p { {format!("最長ストール: {:.1}ms (t={:.1}s)", stats.longest_stall_ms, stats.longest_stall_at_ms / 1000.0)} }
         }
     }
-}
\ No newline at end of file
+}
+
+/// 長時間タスク・長時間フレームのタイムライン（区分ごとに色分け）
+#[component]
+fn LongTaskTimeline(entries: Vec<LongTaskEntry>, start_ms: f64, end_ms: f64) -> Element {
+    const WIDTH: f64 = 260.0;
+    const ROW_HEIGHT: f64 = 14.0;
+    
+    let span_ms = (end_ms - start_ms).max(1.0);
+    // 同じ処理が両方の種類で報告されるため、種類ごとに別々に集計する
+    let summaries: Vec<(LongTaskKind, Vec<long_tasks::PhaseSummary>)> = LongTaskKind::ALL
+        .iter()
+        .map(|&kind| (kind, long_tasks::summarize(&entries, kind)))
+        .collect();
+    
+    rsx! {
+        div {
+            class: "long-task-timeline",
+            h4 { "長時間タスク" }
+            svg {
+                width: "{WIDTH}",
+                height: "{ROW_HEIGHT * 2.0 + 4.0}",
+                style: "background: #fff; border: 1px solid #ddd;",
+                for (i, entry) in entries.iter().enumerate() {
+                    rect {
+                        key: "{i}",
+                        x: "{((entry.start_ms - start_ms) / span_ms * WIDTH).clamp(0.0, WIDTH)}",
+                        y: if entry.kind == LongTaskKind::Task { "2" } else { "{ROW_HEIGHT + 2.0}" },
+                        width: "{(entry.duration_ms / span_ms * WIDTH).max(1.5)}",
+                        height: "{ROW_HEIGHT - 2.0}",
+                        fill: entry.phase.color(),
+                        title { {format!("{} {:.0}ms (t={:.1}s)", entry.phase.label(), entry.duration_ms, (entry.start_ms - start_ms) / 1000.0)} }
+                    }
+                }
+            }
+            p { style: "font-size: 0.7rem; color: #999;", "上段: Long Task / 下段: Long Animation Frame" }
+            for (kind, summary) in summaries {
+                p {
+                    key: "{kind.label()}",
+                    style: "font-size: 0.8rem; font-weight: bold;",
+                    {format!(
+                        "{}: {}件 (ブロック {:.0}ms)",
+                        kind.label(),
+                        summary.iter().map(|s| s.count).sum::<u32>(),
+                        summary.iter().map(|s| s.blocking_ms).sum::<f64>(),
+                    )}
+                }
+                for s in summary {
+                    p {
+                        key: "{kind.label()}-{s.phase.name()}",
+                        style: "font-size: 0.8rem;",
+                        span { style: format!("color: {};", s.phase.color()), "■ " }
+                        {format!("{}: {}件 / ブロック {:.0}ms / 最長 {:.0}ms", s.phase.label(), s.count, s.blocking_ms, s.longest_ms)}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// JSヒープ・WASMメモリ・アロケータ使用量の推移
+#[component]
+fn MemoryChart(samples: Vec<MemorySample>) -> Element {
+    if samples.is_empty() {
+        return rsx! {};
+    }
+    
+    let js_heap: Vec<f64> = samples.iter().filter_map(|s| s.js_heap_mb).collect();
+    let wasm: Vec<f64> = samples.iter().map(|s| s.wasm_memory_mb).collect();
+    let allocator: Vec<f64> = samples.iter().filter_map(|s| s.allocator_mb).collect();
+    
+    rsx! {
+        div {
+            class: "memory-chart",
+            h4 { "メモリ推移" }
+            if js_heap.is_empty() {
+                p { style: "font-size: 0.8rem; color: #999;", "JSヒープ: 非対応ブラウザ" }
+            } else {
+                Sparkline { label: "JSヒープ", values: js_heap, color: "#FF9800" }
+            }
+            Sparkline { label: "WASMメモリ", values: wasm, color: "#2196F3" }
+            if !allocator.is_empty() {
+                Sparkline { label: "アロケータ", values: allocator, color: "#9C27B0" }
+            }
+        }
+    }
+}
+
+/// フレームごとの割り当て回数の表示（alloc-tracking有効時のみ）
+#[component]
+fn AllocationReport(stats: FrameAllocStats) -> Element {
+    let live = alloc_tracker::current_stats().unwrap_or_default();
+    
+    rsx! {
+        div {
+            class: "allocation-report",
+            h4 { {format!("割り当て ({})", alloc_tracker::allocator_name())} }
+            p { {format!("使用中: {:.1}KB / ピーク: {:.1}KB", live.live_bytes as f64 / 1024.0, stats.peak_bytes as f64 / 1024.0)} }
+            p { {format!("累計割り当て: {}回", live.allocations)} }
+            p { {format!("フレーム平均: {:.2}回 / 最大: {}回", stats.avg_allocations_per_frame(), stats.max_allocations_per_frame)} }
+            p {
+                style: if stats.is_allocation_free() { "color: #4CAF50;" } else { "color: #FF9800;" },
+                {format!("割り当てありフレーム: {} / {}", stats.frames_with_allocations, stats.frames)}
+            }
+        }
+    }
+}
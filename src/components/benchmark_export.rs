@@ -0,0 +1,203 @@
+use dioxus::prelude::*;
+use crate::utils::benchmark_export::{compare_with_baseline, BenchmarkRun, RegressionThresholds};
+use crate::utils::storage::{download_text, load_item, remove_item, save_item};
+
+const BASELINE_STORAGE_KEY: &str = "benchmark-baseline";
+
+#[inline]
+fn export_file_name(run: &BenchmarkRun, extension: &str) -> String {
+    format!(
+        "benchmark-{}-{}-{}.{}",
+        run.mode.to_lowercase(),
+        run.object_count,
+        run.recorded_at.replace([':', '.'], "-"),
+        extension
+    )
+}
+
+#[inline]
+fn load_saved_baseline() -> Option<BenchmarkRun> {
+    load_item(BASELINE_STORAGE_KEY).and_then(|json| BenchmarkRun::from_json(&json).ok())
+}
+
+/// 記録結果のエクスポートとベースライン比較
+#[component]
+pub fn BenchmarkExportPanel(run: Option<BenchmarkRun>) -> Element {
+    let mut baseline = use_signal(load_saved_baseline);
+    let mut thresholds = use_signal(RegressionThresholds::default);
+    let mut load_error = use_signal(|| None::<String>);
+
+    // ベースラインとの比較（記録とベースラインが揃った場合のみ）
+    let comparison = match (&run, baseline.read().as_ref()) {
+        (Some(current), Some(base)) => Some(compare_with_baseline(base, current, &thresholds.read())),
+        _ => None,
+    };
+
+    rsx! {
+        div {
+            class: "benchmark-export",
+            h4 { "エクスポート / ベースライン比較" }
+
+            if let Some(current) = run.clone() {
+                div {
+                    class: "export-buttons",
+                    button {
+                        onclick: {
+                            let current = current.clone();
+                            move |_| download_text(&export_file_name(&current, "json"), "application/json", &current.to_json())
+                        },
+                        "JSON"
+                    }
+                    button {
+                        onclick: {
+                            let current = current.clone();
+                            move |_| download_text(&export_file_name(&current, "csv"), "text/csv", &current.to_csv())
+                        },
+                        "CSV"
+                    }
+                    button {
+                        onclick: move |_| {
+                            save_item(BASELINE_STORAGE_KEY, &current.to_json());
+                            baseline.set(Some(current.clone()));
+                            load_error.set(None);
+                        },
+                        "ベースラインに設定"
+                    }
+                }
+            }
+
+            div {
+                class: "control-group",
+                label { "ベースライン読込 (JSON)" }
+                input {
+                    r#type: "file",
+                    accept: ".json,application/json",
+                    onchange: move |evt| async move {
+                        let Some(engine) = evt.files() else {
+                            return;
+                        };
+                        for name in engine.files() {
+                            match engine.read_file_to_string(&name).await {
+                                Some(json) => match BenchmarkRun::from_json(&json) {
+                                    Ok(loaded) => {
+                                        save_item(BASELINE_STORAGE_KEY, &loaded.to_json());
+                                        baseline.set(Some(loaded));
+                                        load_error.set(None);
+                                    }
+                                    Err(e) => load_error.set(Some(e)),
+                                },
+                                None => load_error.set(Some(format!("Failed to read {}", name))),
+                            }
+                        }
+                    }
+                }
+                if let Some(e) = load_error() {
+                    p { style: "color: #f44336;", "{e}" }
+                }
+            }
+
+            if let Some(base) = baseline() {
+                div {
+                    class: "baseline-info",
+                    p {
+                        {format!("ベースライン: {} / {}個 / {:.1}fps", base.mode, base.object_count, base.avg_fps)}
+                    }
+                    p { style: "font-size: 0.8rem; color: #999;", {base.recorded_at.clone()} }
+                    button {
+                        onclick: move |_| {
+                            remove_item(BASELINE_STORAGE_KEY);
+                            baseline.set(None);
+                        },
+                        "ベースラインを解除"
+                    }
+                }
+
+                details {
+                    summary { "回帰しきい値" }
+                    ThresholdInput {
+                        label: "平均FPS低下 (%)",
+                        value: thresholds.read().avg_fps_drop_pct,
+                        onchange: move |v| thresholds.write().avg_fps_drop_pct = v,
+                    }
+                    ThresholdInput {
+                        label: "P95増加 (%)",
+                        value: thresholds.read().p95_increase_pct,
+                        onchange: move |v| thresholds.write().p95_increase_pct = v,
+                    }
+                    ThresholdInput {
+                        label: "P99増加 (%)",
+                        value: thresholds.read().p99_increase_pct,
+                        onchange: move |v| thresholds.write().p99_increase_pct = v,
+                    }
+                    ThresholdInput {
+                        label: ">16.7ms割合増加 (pt)",
+                        value: thresholds.read().jank_increase_points,
+                        onchange: move |v| thresholds.write().jank_increase_points = v,
+                    }
+                    ThresholdInput {
+                        label: "メモリ増加 (%)",
+                        value: thresholds.read().memory_increase_pct,
+                        onchange: move |v| thresholds.write().memory_increase_pct = v,
+                    }
+                }
+            }
+
+            if let Some(result) = comparison {
+                div {
+                    class: "baseline-comparison",
+                    for warning in result.warnings.iter() {
+                        p { style: "color: #FF9800; font-size: 0.8rem;", "⚠️ {warning}" }
+                    }
+                    table {
+                        style: "width: 100%; font-size: 0.8rem;",
+                        tr {
+                            th { "指標" }
+                            th { "基準" }
+                            th { "今回" }
+                            th { "差分" }
+                            th { "" }
+                        }
+                        for delta in result.deltas.iter() {
+                            tr {
+                                key: "{delta.name}",
+                                td { "{delta.name}" }
+                                td { {format!("{:.1}{}", delta.baseline, delta.unit)} }
+                                td { {format!("{:.1}{}", delta.current, delta.unit)} }
+                                td { {format!("{:+.1} ({:+.1}%)", delta.delta(), delta.delta_pct())} }
+                                td { if delta.passed { "✅" } else { "❌" } }
+                            }
+                        }
+                    }
+                    p {
+                        style: format!(
+                            "font-weight: bold; color: {};",
+                            if result.passed() { "#4CAF50" } else { "#f44336" }
+                        ),
+                        if result.passed() { "PASS: 回帰なし" } else { "FAIL: 回帰を検出" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ThresholdInput(label: &'static str, value: f64, onchange: EventHandler<f64>) -> Element {
+    rsx! {
+        div {
+            class: "control-group",
+            label { "{label}" }
+            input {
+                r#type: "number",
+                min: "0",
+                step: "0.5",
+                value: "{value}",
+                oninput: move |evt| {
+                    if let Ok(v) = evt.value().parse::<f64>() {
+                        onchange.call(v.max(0.0));
+                    }
+                }
+            }
+        }
+    }
+}
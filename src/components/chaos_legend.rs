@@ -0,0 +1,33 @@
+use dioxus::prelude::*;
+use crate::utils::chaos_intensity::ChaosIntensity;
+
+/// 強度0〜10の発生率・深刻度・イベント構成の一覧
+#[component]
+pub fn ChaosIntensityLegend(current: Option<ChaosIntensity>) -> Element {
+    rsx! {
+        details {
+            class: "chaos-legend",
+            summary { "強度の目安" }
+            table {
+                tr {
+                    th { "強度" } th { "段階" } th { "発生率" } th { "深刻度" } th { "イベント" }
+                }
+                for profile in ChaosIntensity::all().map(|i| i.profile()) {
+                    tr {
+                        key: "{profile.intensity}",
+                        class: if Some(profile.intensity) == current { "current" } else { "" },
+                        td { "{profile.intensity}" }
+                        td { {profile.label} }
+                        td { {format!("{}件/秒", profile.events_per_second)} }
+                        td { {format!("{:.0}%", profile.severity * 100.0)} }
+                        td { {profile.mix_description()} }
+                    }
+                }
+            }
+            p {
+                style: "font-size: 0.8rem;",
+                "深刻度はマーカーの追加・削除数（最大500）、一時確保量（最大80MB）、UI破壊の強さ、入力遅延（最大500ms）、時間倍率（最大x3）にかかります。"
+            }
+        }
+    }
+}
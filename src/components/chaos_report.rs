@@ -0,0 +1,120 @@
+use dioxus::prelude::*;
+use crate::utils::chaos_report::ChaosRunReport;
+use crate::utils::storage::download_text;
+
+/// 表に出すバーストの数（回復の遅い順）
+const BURST_ROWS: usize = 10;
+
+fn format_ms(ms: Option<f64>) -> String {
+    ms.map(|ms| format!("{:.0}ms", ms)).unwrap_or_else(|| "未回復".to_string())
+}
+
+/// カオス実行後のレポート（種別ごとの影響とバーストからの回復）
+///
+/// レポートはシグナルのまま受け取り（親の再描画では複製しない）、JSON/HTMLはダウンロード時に作る。
+/// レポートがなければ何も表示しない
+#[component]
+pub fn ChaosReportView(report: ReadOnlySignal<Option<ChaosRunReport>>, file_prefix: String) -> Element {
+    let source = report;
+    let guard = report.read();
+    let Some(report) = guard.as_ref() else {
+        return rsx! {};
+    };
+    let mut bursts: Vec<_> = report.bursts.iter().collect();
+    // 回復しなかったものを先頭に、あとは回復の遅い順
+    bursts.sort_by(|a, b| {
+        b.recovery_ms
+            .unwrap_or(f64::INFINITY)
+            .total_cmp(&a.recovery_ms.unwrap_or(f64::INFINITY))
+    });
+    let json_name = format!("{}-report.json", file_prefix);
+    let html_name = format!("{}-report.html", file_prefix);
+
+    rsx! {
+        div {
+            class: "chaos-report",
+            h3 { {report.title.clone()} }
+            p {
+                {format!(
+                    "{:.1}秒 / イベント{}件 / フレーム落ち{}回（>{:.0}ms） / 遅延スパイク{}回（>{:.0}ms） / バースト{}回",
+                    report.duration_ms / 1000.0,
+                    report.total_events,
+                    report.frame_drops,
+                    report.config.drop_threshold_ms,
+                    report.latency_spikes,
+                    report.config.latency_spike_ms,
+                    report.bursts.len()
+                )}
+            }
+            if report.event_rate.count > 0 {
+                p {
+                    style: "font-size: 0.8rem;",
+                    {format!(
+                        "イベント発生率 P50 {:.1}件/秒 / P95 {:.1}件/秒 / 最大 {:.1}件/秒",
+                        report.event_rate.p50,
+                        report.event_rate.p95,
+                        report.event_rate.max
+                    )}
+                }
+            }
+
+            if !report.by_type.is_empty() {
+                table {
+                    style: "font-size: 0.8rem;",
+                    tr {
+                        th { "種別" } th { "件数" } th { "直後の落ち" } th { "直後のスパイク" } th { "平均回復" }
+                    }
+                    for impact in report.by_type.iter() {
+                        tr {
+                            key: "{impact.kind}",
+                            td { {impact.kind.clone()} }
+                            td { "{impact.events}" }
+                            td { "{impact.preceded_drops}" }
+                            td { "{impact.preceded_spikes}" }
+                            td { {format_ms(impact.avg_recovery_ms)} }
+                        }
+                    }
+                }
+            }
+
+            if !bursts.is_empty() {
+                table {
+                    style: "font-size: 0.8rem; margin-top: 8px;",
+                    tr {
+                        th { "開始" } th { "長さ" } th { "件数" } th { "最長フレーム" } th { "回復" }
+                    }
+                    for (i, burst) in bursts.iter().take(BURST_ROWS).enumerate() {
+                        tr {
+                            key: "{i}",
+                            td { {format!("{:.1}s", burst.start_ms / 1000.0)} }
+                            td { {format!("{:.0}ms", burst.end_ms - burst.start_ms)} }
+                            td { "{burst.event_count()}" }
+                            td { {format!("{:.1}ms", burst.worst_frame_ms)} }
+                            td {
+                                style: if burst.recovery_ms.is_none() { "color: #f44336;" } else { "" },
+                                {format_ms(burst.recovery_ms)}
+                            }
+                        }
+                    }
+                }
+            }
+
+            button {
+                onclick: move |_| {
+                    if let Some(report) = source.peek().as_ref() {
+                        download_text(&json_name, "application/json", &report.to_json());
+                    }
+                },
+                "レポート(JSON)"
+            }
+            button {
+                onclick: move |_| {
+                    if let Some(report) = source.peek().as_ref() {
+                        download_text(&html_name, "text/html", &report.to_html());
+                    }
+                },
+                "レポート(HTML)"
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use dioxus::prelude::*;
+use crate::utils::event_timing::InteractionTracker;
+
+/// Event Timing API による部品ごとの入力遅延と内訳
+#[component]
+pub fn EventTimingReport(tracker: InteractionTracker, target_ms: f64) -> Element {
+    let inp = tracker.inp();
+    let reports = tracker.by_component();
+
+    rsx! {
+        div {
+            class: "event-timing-report",
+            p {
+                style: if inp > target_ms { "color: #f00;" } else { "color: #0f0;" },
+                {format!("INP {:.0}ms（16ms以上のインタラクション{}件 / 目標 {:.0}ms）", inp, tracker.count(), target_ms)}
+            }
+            if reports.is_empty() {
+                p { style: "color: #aaa;", "まだインタラクションがありません（16ms未満のものは記録されません）" }
+            } else {
+                p { style: "font-size: 0.8rem; color: #aaa;", "表は直近1000件の16ms以上のインタラクションの統計です" }
+                table {
+                    style: "font-size: 0.8rem;",
+                    tr {
+                        th { "部品" } th { "件数" } th { "P50" } th { "P95" } th { "P99" }
+                        th { "入力遅延 P95" } th { "処理 P95" } th { "表示遅延 P95" }
+                    }
+                    for report in reports.iter() {
+                        tr {
+                            key: "{report.component}",
+                            td { {report.component.clone()} }
+                            td { "{report.total.count}" }
+                            td { {format!("{:.0}ms", report.total.p50)} }
+                            td {
+                                style: if report.total.meets_target(target_ms) { "" } else { "color: #f00;" },
+                                {format!("{:.0}ms", report.total.p95)}
+                            }
+                            td { {format!("{:.0}ms", report.total.p99)} }
+                            td { {format!("{:.0}ms", report.input_delay.p95)} }
+                            td { {format!("{:.0}ms", report.processing.p95)} }
+                            td { {format!("{:.0}ms", report.presentation_delay.p95)} }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use dioxus::prelude::*;
+use crate::utils::input_latency::{LatencyTag, TaggedLatencyStats};
+
+/// 操作の種類と対象ごとの入力遅延（それぞれの目標で判定。目標は表から変更できる）
+#[component]
+pub fn LatencyBreakdown(stats: Vec<TaggedLatencyStats>, on_target_change: EventHandler<(LatencyTag, f64)>) -> Element {
+    if stats.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        table {
+            class: "latency-breakdown",
+            style: "font-size: 0.8rem; margin-bottom: 8px;",
+            tr {
+                th { "操作" } th { "対象" } th { "件数" } th { "P50" } th { "P95" } th { "P99" } th { "目標" } th { "" }
+            }
+            for tagged in stats.iter() {
+                tr {
+                    key: "{tagged.tag}",
+                    td { {tagged.tag.kind.label()} }
+                    td { {tagged.tag.target.clone()} }
+                    td { "{tagged.stats.count}" }
+                    td { {format!("{:.0}ms", tagged.stats.p50)} }
+                    td { {format!("{:.0}ms", tagged.stats.p95)} }
+                    td { {format!("{:.0}ms", tagged.stats.p99)} }
+                    td {
+                        input {
+                            r#type: "number",
+                            min: "1",
+                            style: "width: 4em;",
+                            value: "{tagged.target_ms}",
+                            onchange: {
+                                let tag = tagged.tag.clone();
+                                move |evt: FormEvent| {
+                                    if let Ok(v) = evt.value().parse::<f64>() {
+                                        on_target_change.call((tag.clone(), v.max(1.0)));
+                                    }
+                                }
+                            },
+                        }
+                        "ms"
+                    }
+                    td {
+                        style: if tagged.meets_target() { "color: #0f0;" } else { "color: #f00;" },
+                        if tagged.meets_target() { "✅" } else { "❌" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,17 @@
 use dioxus::prelude::*;
+use crate::utils::profiler::profile_scope;
+use crate::utils::seeded_random::random_source_js;
 
 // マーカー更新コードを生成するヘルパー関数
-fn generate_marker_update_code(object_count: i32) -> String {
+fn generate_marker_update_code(object_count: i32, seed: Option<u64>) -> String {
     format!(r#"
         console.log('[JS] Marker update code executing for {} markers');
         setTimeout(() => {{
             console.log('[JS] Inside setTimeout, checking for map instance');
             if (window.theMapInstance) {{
                 console.log('[JS] Map instance found, clearing existing markers');
+                const rebuildStart = performance.now();
+                
                 // 既存のマーカーをクリア
                 if (window.theMapMarkers) {{
                     console.log('[JS] Removing', window.theMapMarkers.length, 'existing markers');
@@ -23,45 +27,66 @@ fn generate_marker_update_code(object_count: i32) -> String {
                 
                 console.log('[JS] Adding {} new markers in DOM mode');
                 
+                // シード指定時は毎回同じ配置・速度になる
+                {}
+                
                 for (let i = 0; i < {}; i++) {{
-                    const lat = sw.lat + Math.random() * (ne.lat - sw.lat);
-                    const lng = sw.lng + Math.random() * (ne.lng - sw.lng);
+                    const lat = sw.lat + rand() * (ne.lat - sw.lat);
+                    const lng = sw.lng + rand() * (ne.lng - sw.lng);
                     
                     let marker = L.marker([lat, lng]).addTo(map);
                     
                     // アニメーション用の速度を設定
                     marker._velocity = {{
-                        lat: (Math.random() - 0.5) * 0.00005,
-                        lng: (Math.random() - 0.5) * 0.00005
+                        lat: (rand() - 0.5) * 0.00005,
+                        lng: (rand() - 0.5) * 0.00005
                     }};
                     
                     window.theMapMarkers.push(marker);
                 }}
                 
+                if (window.__benchPhase) {{
+                    window.__benchPhase(1 /* marker-rebuild */, rebuildStart, performance.now());
+                }}
+                
                 // アニメーションを開始
                 if (window.animationId) {{
                     cancelAnimationFrame(window.animationId);
                 }}
                 
+                let frameIndex = 0;
+                
                 function animate() {{
-                    window.theMapMarkers.forEach(marker => {{
+                    const frameStart = performance.now();
+                    // 詳細度に応じてN個に1個だけ更新し、移動量をN倍にする
+                    const stride = window.__mapUpdateStride || 1;
+                    // カオスモードの時間歪曲中はシミュレーション時間を伸縮する
+                    const step = stride * (window.__chaosTimeScale ?? 1);
+                    frameIndex = (frameIndex + 1) % stride;
+                    
+                    window.theMapMarkers.forEach((marker, i) => {{
+                        if (i % stride !== frameIndex) return;
                         const pos = marker.getLatLng();
-                        let newLat = pos.lat + marker._velocity.lat;
-                        let newLng = pos.lng + marker._velocity.lng;
+                        let newLat = pos.lat + marker._velocity.lat * step;
+                        let newLng = pos.lng + marker._velocity.lng * step;
                         
                         // 境界でバウンス
                         if (newLat <= sw.lat || newLat >= ne.lat) {{
                             marker._velocity.lat *= -1;
-                            newLat = pos.lat + marker._velocity.lat;
+                            newLat = pos.lat + marker._velocity.lat * step;
                         }}
                         if (newLng <= sw.lng || newLng >= ne.lng) {{
                             marker._velocity.lng *= -1;
-                            newLng = pos.lng + marker._velocity.lng;
+                            newLng = pos.lng + marker._velocity.lng * step;
                         }}
                         
                         marker.setLatLng([newLat, newLng]);
                     }});
                     
+                    if (window.__benchPhase) {{
+                        window.__benchPhase(0 /* animate */, frameStart, performance.now());
+                    }}
+                    
                     window.animationId = requestAnimationFrame(animate);
                 }}
                 
@@ -72,11 +97,11 @@ fn generate_marker_update_code(object_count: i32) -> String {
                 console.log('[JS] Map instance not found, skipping marker update');
             }}
         }}, 200);
-    "#, object_count, object_count, object_count, object_count)
+    "#, object_count, object_count, random_source_js(seed), object_count, object_count)
 }
 
 #[component]
-pub fn Map(object_count: i32) -> Element {
+pub fn Map(object_count: i32, seed: Option<u64>) -> Element {
     web_sys::console::log_1(&format!("[Map] Component called with object_count: {}", object_count).into());
     // ユニークなコンテナ ID を生成（一度だけ計算）
     let container_id = use_memo(|| {
@@ -148,10 +173,21 @@ pub fn Map(object_count: i32) -> Element {
                 // 新しいマップインスタンスを作成
                 try {{
                     const map = L.map('{}').setView([35.6762, 139.6503], 13);
-                    L.tileLayer('https://{{s}}.tile.openstreetmap.org/{{z}}/{{x}}/{{y}}.png', {{
+                    // 障害注入が有効ならそのタイルレイヤーを使う（utils::tile_faults）
+                    const tiles = (window.__tileLayerFactory || L.tileLayer)('https://{{s}}.tile.openstreetmap.org/{{z}}/{{x}}/{{y}}.png', {{
                         attribution: '© OpenStreetMap contributors'
                     }}).addTo(map);
                     
+                    // ベンチマーク中はタイル読み込み区間を記録（長時間タスクの原因推定用）
+                    let tileLoadStart = null;
+                    tiles.on('loading', () => {{ tileLoadStart = performance.now(); }});
+                    tiles.on('load', () => {{
+                        if (tileLoadStart !== null && window.__benchPhase) {{
+                            window.__benchPhase(2 /* tile-load */, tileLoadStart, performance.now());
+                        }}
+                        tileLoadStart = null;
+                    }});
+                    
                     window.theMapInstance = map;
                     window.theMapMarkers = [];
                     console.log('Map initialized successfully');
@@ -167,6 +203,7 @@ pub fn Map(object_count: i32) -> Element {
     // 地図の初期化（コンポーネントがマウントされるたびに実行）
     use_effect(move || {
         web_sys::console::log_1(&"[Map] Initializing map".into());
+        profile_scope!("effect:map_init");
         let _ = js_sys::eval(&init_code());
     });
     
@@ -178,9 +215,17 @@ pub fn Map(object_count: i32) -> Element {
         count_signal.set(object_count);
     }
     
+    // 配置のシード（未指定ならランダム）。変更時はマーカーを作り直す
+    let mut seed_signal = use_signal(|| seed);
+    if seed_signal() != seed {
+        seed_signal.set(seed);
+    }
+    
     // シグナルの変更時にマーカーを更新
     use_effect(move || {
+        profile_scope!("effect:marker_update");
         let current_count = count_signal();
+        let current_seed = seed_signal();
         web_sys::console::log_1(&format!("[Map] Marker update effect triggered with count: {}", current_count).into());
         
         // 地図の初期化を待つ
@@ -194,7 +239,7 @@ pub fn Map(object_count: i32) -> Element {
                     setTimeout(arguments.callee, 200);
                 }}
             }}, 300);
-        "#, current_count, generate_marker_update_code(current_count));
+        "#, current_count, generate_marker_update_code(current_count, current_seed));
         
         let _ = js_sys::eval(&update_markers_with_delay);
     });
@@ -0,0 +1,450 @@
+use dioxus::prelude::*;
+use crate::hooks::{use_chaos_engine, use_latency_probe, use_soak_test, ChaosEventType, RenderMode};
+use crate::utils::input_latency::{InteractionKind, LatencyTag};
+use crate::sim::rng::SimRng;
+use crate::components::chaos_report::ChaosReportView;
+use crate::components::chaos_legend::ChaosIntensityLegend;
+use crate::utils::chaos_intensity::ChaosIntensity;
+use crate::utils::clock::now_ms;
+use crate::utils::chaos_scenario::{ChaosScenario, ScenarioAction, ScenarioRunner, BUILTIN_SCENARIOS};
+use crate::utils::soak::SoakConfig;
+
+/// 深刻度100%で追加・削除するマーカー数
+const MAX_BURST: f64 = 500.0;
+/// 深刻度100%で一時確保する量（MB）
+const MAX_PRESSURE_MB: f64 = 80.0;
+/// 追加でこの数を超えないようにする
+const MAX_OBJECTS: i32 = 20_000;
+/// 一時確保を保持する時間（ms）
+const PRESSURE_HOLD_MS: u32 = 2000;
+
+const MODES: [RenderMode; 3] = [RenderMode::DOM, RenderMode::Canvas, RenderMode::WebGL];
+
+/// ソークテストの既定の実行時間（分）
+const DEFAULT_SOAK_MINUTES: f64 = 60.0;
+
+/// 読み込んだファイルのシナリオを表す選択肢
+const FILE_SOURCE: &str = "file";
+/// ランダム実行を表す選択肢
+const RANDOM_SOURCE: &str = "random";
+
+/// 表示中の地図を指定位置へ移動する
+fn set_view(lat: f64, lng: f64, zoom: f64) {
+    let zoom = zoom.round();
+    let _ = js_sys::eval(&format!(
+        "if (window.theMapInstance) {{ window.theMapInstance.setView([{lat}, {lng}], {zoom}, {{ animate: false }}); }}"
+    ));
+}
+
+/// 表示中の地図をランダムにパン・ズームする
+fn random_viewport(rng: &mut SimRng) -> String {
+    let lat = 35.6762 + rng.range(-0.05, 0.05);
+    let lng = 139.6503 + rng.range(-0.05, 0.05);
+    let zoom = 11.0 + (rng.next_f64() * 6.0).floor();
+    set_view(lat, lng, zoom);
+    format!("ビュー移動 ({:.4}, {:.4}) z{}", lat, lng, zoom)
+}
+
+/// JSヒープに大きな配列とオブジェクト群を確保し、一定時間後に解放する
+fn allocate_pressure(megabytes: u32) -> String {
+    let _ = js_sys::eval(&format!(
+        r#"
+        (() => {{
+            window.__chaosPressure = window.__chaosPressure || [];
+            const block = {{
+                buffer: new Float64Array({megabytes} * 131072).fill(1),
+                objects: Array.from({{ length: {megabytes} * 10000 }}, (_, i) => ({{ i, label: 'chaos-' + i }})),
+            }};
+            window.__chaosPressure.push(block);
+            setTimeout(() => {{
+                window.__chaosPressure = window.__chaosPressure.filter(b => b !== block);
+            }}, {PRESSURE_HOLD_MS});
+        }})();
+        "#
+    ));
+    format!("一時確保 {}MB（{}ms保持）", megabytes, PRESSURE_HOLD_MS)
+}
+
+/// 表示中のマップにカオスイベントを発生させ、種別ごとのFPS・遅延の劣化を表示する
+#[component]
+pub fn MapChaosPanel(
+    mut object_count: Signal<i32>,
+    render_mode: RenderMode,
+    on_select_mode: EventHandler<RenderMode>,
+) -> Element {
+    let mut chaos = use_chaos_engine(3);
+    let latency = use_latency_probe();
+    let mut soak = use_soak_test();
+    let mut soak_enabled = use_signal(|| false);
+    let mut soak_minutes = use_signal(|| DEFAULT_SOAK_MINUTES);
+    // ランダム実行か、同梱/読み込んだシナリオか
+    let mut source = use_signal(|| RANDOM_SOURCE.to_string());
+    let mut loaded_scenario = use_signal(|| None::<ChaosScenario>);
+    // 未指定ならシナリオのシード
+    let mut seed_override = use_signal(|| None::<u64>);
+    let mut phase_name = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+    // 停止時に戻す元の構成
+    let mut original = use_signal(|| None::<(i32, RenderMode)>);
+    let mut current_mode = use_signal(|| render_mode.clone());
+    if *current_mode.peek() != render_mode {
+        current_mode.set(render_mode);
+    }
+
+    let is_running = chaos.engine.read().is_running;
+    let intensity = chaos.engine.read().intensity;
+    let stats = (chaos.event_stats)();
+    let baseline = chaos.baseline_fps();
+
+    let selected_scenario = move || -> Option<ChaosScenario> {
+        match source.read().as_str() {
+            RANDOM_SOURCE => None,
+            FILE_SOURCE => loaded_scenario.read().clone(),
+            name => ChaosScenario::builtin(name),
+        }
+    };
+    let scenario_info = selected_scenario();
+
+    // 停止・完了時に元の構成へ戻す
+    let mut restore = move || {
+        phase_name.set(None);
+        if let Some((count, mode)) = original.take() {
+            object_count.set(count);
+            on_select_mode.call(mode);
+        }
+    };
+
+    let toggle = move |_| {
+        if chaos.engine.peek().is_running {
+            soak.cancel();
+            chaos.stop();
+            restore();
+            return;
+        }
+
+        let scenario = selected_scenario();
+        if *source.peek() != RANDOM_SOURCE && scenario.is_none() {
+            error.set(Some("シナリオファイルを読み込んでください".to_string()));
+            return;
+        }
+        error.set(None);
+        let phase_names: Vec<String> = scenario.iter().flat_map(|s| s.phases.iter().map(|p| p.name.clone())).collect();
+        // ソークテストではシナリオを繰り返し、予定時間で止める
+        let soaking = *soak_enabled.peek();
+        let runner = scenario.map(|s| {
+            let seed = seed_override().unwrap_or(s.seed);
+            if soaking {
+                ScenarioRunner::repeating(s, seed, now_ms())
+            } else {
+                ScenarioRunner::new(s, seed, now_ms())
+            }
+        });
+
+        original.set(Some((*object_count.peek(), current_mode.peek().clone())));
+        chaos.start(runner, move |action, rng| {
+            let event_type = match action {
+                ScenarioAction::PhaseStarted { index } => {
+                    phase_name.set(phase_names.get(*index).cloned());
+                    return format!("フェーズ: {}", phase_names.get(*index).map(String::as_str).unwrap_or("?"));
+                }
+                ScenarioAction::SetIntensity(intensity) => {
+                    chaos.set_intensity(*intensity);
+                    return format!("強度 → {}", intensity);
+                }
+                ScenarioAction::SetObjectCount(count) => {
+                    let count = (*count).min(MAX_OBJECTS);
+                    object_count.set(count);
+                    return format!("オブジェクト数 → {}", count);
+                }
+                ScenarioAction::SetViewport(point) => {
+                    set_view(point.lat, point.lng, point.zoom);
+                    return format!("ビュー ({:.4}, {:.4}) z{:.0}", point.lat, point.lng, point.zoom.round());
+                }
+                ScenarioAction::Finished => {
+                    restore();
+                    return "シナリオ完了".to_string();
+                }
+                ScenarioAction::Fire(event_type) => event_type,
+            };
+            let severity = chaos.engine.peek().intensity.profile().severity;
+            let burst = (MAX_BURST * severity).round() as i32;
+            match event_type {
+                ChaosEventType::ObjectSpawn => {
+                    let count = (*object_count.peek() + burst).min(MAX_OBJECTS);
+                    object_count.set(count);
+                    format!("マーカー +{} → {}", burst, count)
+                }
+                ChaosEventType::ObjectRemove => {
+                    let count = (*object_count.peek() - burst).max(0);
+                    object_count.set(count);
+                    format!("マーカー -{} → {}", burst, count)
+                }
+                ChaosEventType::ViewportChange => random_viewport(rng),
+                ChaosEventType::ModeSwitch => {
+                    let candidates: Vec<_> = MODES.iter().filter(|m| **m != *current_mode.peek()).collect();
+                    let index = (rng.next_f64() * candidates.len() as f64) as usize;
+                    let mode = candidates[index].clone();
+                    on_select_mode.call(mode.clone());
+                    format!("モード切替 → {}", mode.as_str())
+                }
+                ChaosEventType::MemoryPressure => allocate_pressure((MAX_PRESSURE_MB * severity).round() as u32),
+            }
+        });
+        if soaking {
+            soak.start(SoakConfig::with_duration_ms(*soak_minutes.peek() * 60_000.0), move |_| {
+                chaos.stop();
+                restore();
+            });
+        }
+    };
+
+    rsx! {
+        div {
+            class: "map-chaos-panel",
+            style: "margin-top: 16px;",
+            h3 { "カオスモード" }
+
+            div {
+                class: "control-group",
+                label { "実行内容" }
+                select {
+                    disabled: is_running,
+                    value: "{source}",
+                    onchange: move |evt| source.set(evt.value()),
+                    option { value: RANDOM_SOURCE, "ランダム（強度指定）" }
+                    for (name, _) in BUILTIN_SCENARIOS {
+                        option { key: "{name}", value: name, "シナリオ: {name}" }
+                    }
+                    option { value: FILE_SOURCE, "シナリオ: ファイルから読込" }
+                }
+            }
+
+            if source() == FILE_SOURCE {
+                div {
+                    class: "control-group",
+                    input {
+                        r#type: "file",
+                        accept: ".json,application/json",
+                        disabled: is_running,
+                        onchange: move |evt| async move {
+                            let Some(engine) = evt.files() else {
+                                return;
+                            };
+                            for name in engine.files() {
+                                match engine.read_file_to_string(&name).await {
+                                    Some(json) => match ChaosScenario::from_json(&json) {
+                                        Ok(scenario) => {
+                                            loaded_scenario.set(Some(scenario));
+                                            error.set(None);
+                                        }
+                                        Err(e) => error.set(Some(e)),
+                                    },
+                                    None => error.set(Some(format!("Failed to read {}", name))),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(scenario) = scenario_info.as_ref() {
+                div {
+                    class: "control-group",
+                    p {
+                        style: "font-size: 0.8rem; color: #666;",
+                        {format!(
+                            "{}: {}（{}フェーズ / {:.0}秒）",
+                            scenario.name,
+                            scenario.description,
+                            scenario.phases.len(),
+                            scenario.duration_ms() / 1000.0
+                        )}
+                    }
+                    label { "シード" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        style: "width: 6em;",
+                        disabled: is_running,
+                        value: "{seed_override().unwrap_or(scenario.seed)}",
+                        onchange: move |evt| seed_override.set(evt.value().parse().ok()),
+                    }
+                }
+            } else {
+                div {
+                    class: "control-group",
+                    label { {format!("強度: {}（{}）", intensity, intensity.profile().label)} }
+                    input {
+                        r#type: "range",
+                        min: "0",
+                        max: "{ChaosIntensity::MAX}",
+                        value: "{intensity}",
+                        oninput: move |evt| {
+                            if let Ok(v) = evt.value().parse() {
+                                latency.measure(LatencyTag::new(InteractionKind::Slider, "chaos-intensity"), || chaos.set_intensity(v));
+                            }
+                        },
+                    }
+                }
+                ChaosIntensityLegend { current: Some(intensity) }
+            }
+            div {
+                class: "control-group",
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: soak_enabled(),
+                        disabled: is_running,
+                        onchange: move |evt| soak_enabled.set(evt.checked()),
+                    }
+                    " ソークテスト"
+                }
+                if soak_enabled() {
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        style: "width: 5em;",
+                        disabled: is_running,
+                        value: "{soak_minutes}",
+                        onchange: move |evt| {
+                            if let Ok(v) = evt.value().parse::<f64>() {
+                                soak_minutes.set(v.max(1.0));
+                            }
+                        },
+                    }
+                    " 分"
+                }
+            }
+            button {
+                onclick: toggle,
+                if is_running { "カオス停止" } else { "カオス開始" }
+            }
+
+            if let Some((elapsed, duration, sample)) = (soak.progress)() {
+                p {
+                    style: "font-size: 0.8rem;",
+                    {format!(
+                        "ソーク {:.1}/{:.0}分 — メモリ {:.1}MB / DOM {} / interval {} / frame loop {} / 未実行クロージャ {}",
+                        elapsed / 60_000.0,
+                        duration / 60_000.0,
+                        sample.memory_mb,
+                        sample.dom_nodes,
+                        sample.handles.intervals,
+                        sample.handles.frame_loops,
+                        sample.handles.pending_closures
+                    )}
+                }
+            }
+
+            if let (false, Some(verdict)) = (is_running, (soak.verdict)()) {
+                div {
+                    p {
+                        style: if verdict.passed { "color: #4caf50;" } else { "color: #f44336;" },
+                        {verdict.summary()}
+                    }
+                    table {
+                        style: "font-size: 0.8rem;",
+                        tr {
+                            th { "指標" } th { "開始" } th { "終了" } th { "増加/時" } th { "判定" }
+                        }
+                        for trend in verdict.trends.iter() {
+                            tr {
+                                key: "{trend.metric}",
+                                td { {trend.metric.clone()} }
+                                td { {format!("{:.1}", trend.first)} }
+                                td { {format!("{:.1}", trend.last)} }
+                                td { {format!("{:+.2}", trend.slope_per_hour)} }
+                                td {
+                                    style: if trend.leaking { "color: #f44336;" } else { "" },
+                                    if trend.leaking { "リーク疑い" } else if trend.monotonic { "増加（許容内）" } else { "OK" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(message) = error() {
+                p { style: "color: #f44336;", "{message}" }
+            }
+
+            if let (true, Some(progress)) = (is_running, (chaos.scenario_progress)()) {
+                p {
+                    style: "font-size: 0.8rem;",
+                    {format!("{} — {:.0}%", phase_name().unwrap_or_default(), progress * 100.0)}
+                }
+            }
+
+            if stats.total > 0 {
+                p {
+                    style: "font-size: 0.8rem;",
+                    {format!(
+                        "イベント: {}件（{:.1}件/秒） / ベースライン: {}",
+                        chaos.engine.read().total_events,
+                        stats.events_per_second,
+                        baseline.map(|fps| format!("{:.1}fps", fps)).unwrap_or_else(|| "-".to_string()),
+                    )}
+                }
+            }
+
+            if is_running || stats.total > 0 {
+                p {
+                    style: "font-size: 0.8rem;",
+                    {
+                        let report = chaos.performance.report.read();
+                        format!(
+                            "FPS {:.0}（平均 {:.1}） / 落ちたフレーム {} / メモリ {:.1}MB（最大 {:.1}MB）",
+                            report.fps_current,
+                            report.fps_average,
+                            report.dropped_frames,
+                            report.memory_usage,
+                            report.peak_memory_mb
+                        )
+                    }
+                }
+            }
+
+            if !chaos.impact.read().is_empty() {
+                table {
+                    style: "font-size: 0.8rem; margin-top: 8px;",
+                    tr {
+                        th { "種別" } th { "件数" } th { "FPS" } th { "低下率" } th { "遅延P95" }
+                    }
+                    for impact in chaos.impact.read().iter() {
+                        tr {
+                            key: "{impact.event_type.label()}",
+                            td { {impact.event_type.label()} }
+                            td { "{impact.events}" }
+                            td { {impact.fps.map(|fps| format!("{:.1}", fps)).unwrap_or_else(|| "-".to_string())} }
+                            td {
+                                style: if impact.fps_drop_percent.is_some_and(|d| d > 20.0) { "color: #f44336;" } else { "" },
+                                {impact.fps_drop_percent.map(|d| format!("{:.0}%", d)).unwrap_or_else(|| "-".to_string())}
+                            }
+                            td { {format!("{:.0}ms", impact.latency.p95)} }
+                        }
+                    }
+                }
+            }
+
+            if !is_running {
+                ChaosReportView { report: chaos.run_report, file_prefix: "map-chaos".to_string() }
+            }
+
+            if is_running {
+                div {
+                    style: "font-size: 0.75rem; color: #666; margin-top: 8px;",
+                    for (i, event) in chaos.engine.read().events.iter().enumerate().rev().take(5) {
+                        div {
+                            key: "{i}",
+                            {format!(
+                                "{:.1}s {}",
+                                (event.timestamp - chaos.engine.read().start_time.unwrap_or(event.timestamp)) / 1000.0,
+                                event.description
+                            )}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
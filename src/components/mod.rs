@@ -1,4 +1,17 @@
 pub mod map;
 pub mod benchmark;
+pub mod benchmark_export;
+pub mod multi_run;
+pub mod sparkline;
+pub mod chaos_report;
+pub mod chaos_legend;
+pub mod event_timing;
+pub mod latency_breakdown;
 pub mod webgl_map;
-pub mod canvas_map;
\ No newline at end of file
+pub mod canvas_map;
+#[cfg(feature = "profiling")]
+pub mod profiler_overlay;
+#[cfg(feature = "chaos-testing")]
+pub mod map_chaos;
+#[cfg(feature = "chaos-testing")]
+pub mod tile_faults;
@@ -0,0 +1,308 @@
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::hooks::RenderMode;
+use crate::utils::benchmark_export::BenchmarkRun;
+use crate::utils::benchmark_metrics::ScoringConfig;
+use crate::utils::fps_counter::FrameLoop;
+use crate::utils::clock::now_ms;
+use crate::utils::interval::Interval;
+use crate::utils::memory::MemorySample;
+use crate::utils::multi_run::{MultiRunResults, MultiRunner, RunConfig, RunPlan, RunnerEvent, RunnerPhase, ALPHA};
+use crate::utils::storage::download_text;
+
+/// フェーズ切り替えを判定する間隔（ms）
+const TICK_MS: u32 = 250;
+
+const MODES: [RenderMode; 3] = [RenderMode::DOM, RenderMode::Canvas, RenderMode::WebGL];
+
+/// 構成ごとにシードを変えて繰り返し計測し、平均・信頼区間・有意差を表示する
+#[component]
+pub fn MultiRunPanel(
+    mut object_count: Signal<i32>,
+    mut scene_seed: Signal<Option<u64>>,
+    on_select_mode: EventHandler<RenderMode>,
+) -> Element {
+    let mut selected_modes = use_signal(|| MODES.to_vec());
+    let mut counts_text = use_signal(|| "1000".to_string());
+    let mut runs_per_config = use_signal(|| 5u32);
+    let mut measure_sec = use_signal(|| 10.0f64);
+    let mut warmup_sec = use_signal(|| 3.0f64);
+    let mut base_seed = use_signal(|| 42u64);
+    let mut error = use_signal(|| None::<String>);
+
+    // 実行中の状態（毎フレーム更新するためSignalの外で保持）
+    let runner = use_hook(|| Rc::new(RefCell::new(None::<MultiRunner>)));
+    let mut progress = use_signal(|| None::<(usize, usize, String, &'static str)>);
+    let mut results = use_signal(|| None::<MultiRunResults>);
+    let mut recorded_runs = use_signal(Vec::<BenchmarkRun>::new);
+    let mut ticker = use_signal(|| None::<Interval>);
+
+    let frame_runner = runner.clone();
+    use_hook(move || {
+        Rc::new(FrameLoop::start(move |frame_ms, timestamp| {
+            if let Some(r) = frame_runner.borrow_mut().as_mut() {
+                r.on_frame(frame_ms, timestamp);
+            }
+        }))
+    });
+
+    use_drop(move || {
+        if let Some(mut interval) = ticker.write().take() {
+            interval.stop();
+        }
+    });
+
+    let start_runner = runner.clone();
+    let start = move |_| {
+        let counts: Result<Vec<i32>, _> = counts_text
+            .read()
+            .split(',')
+            .map(|c| c.trim().parse::<i32>())
+            .collect();
+        let counts = match counts {
+            Ok(counts) if !counts.is_empty() => counts,
+            _ => {
+                error.set(Some("オブジェクト数はカンマ区切りの整数で指定してください".to_string()));
+                return;
+            }
+        };
+        let configs: Vec<RunConfig> = selected_modes
+            .read()
+            .iter()
+            .flat_map(|mode| counts.iter().map(move |&object_count| RunConfig { mode: mode.clone(), object_count }))
+            .collect();
+        if configs.is_empty() {
+            error.set(Some("レンダリングモードを1つ以上選択してください".to_string()));
+            return;
+        }
+        error.set(None);
+
+        let plan = RunPlan {
+            configs,
+            runs_per_config: runs_per_config().max(2),
+            base_seed: base_seed(),
+            warmup_ms: warmup_sec() * 1000.0,
+            measure_ms: measure_sec() * 1000.0,
+        };
+        let mut new_runner = MultiRunner::new(plan);
+        let first = new_runner.start(now_ms());
+        *start_runner.borrow_mut() = Some(new_runner);
+        results.set(None);
+        recorded_runs.write().clear();
+
+        let tick_runner = start_runner.clone();
+        let mut apply = move |event: RunnerEvent| {
+            let Some((plan, total)) = tick_runner
+                .borrow()
+                .as_ref()
+                .map(|r| (r.results.plan.clone(), r.total_runs()))
+            else {
+                return;
+            };
+            match event {
+                RunnerEvent::Apply(run) => {
+                    let config = &plan.configs[run.config_index];
+                    on_select_mode.call(config.mode.clone());
+                    object_count.set(config.object_count);
+                    scene_seed.set(Some(run.seed));
+                    let completed = tick_runner.borrow().as_ref().map(|r| r.results.completed_runs()).unwrap_or(0);
+                    progress.set(Some((completed, total, format!("{} (seed {})", config.label(), run.seed), "ウォームアップ")));
+                }
+                RunnerEvent::Completed(run, metrics) => {
+                    let config = &plan.configs[run.config_index];
+                    let record = metrics.to_run(&config.mode.as_str().to_lowercase(), config.object_count, Some(run.seed), plan.measure_ms);
+                    recorded_runs.write().push(record);
+                    results.set(tick_runner.borrow().as_ref().map(|r| r.results.clone()));
+                }
+                RunnerEvent::Finished => {
+                    progress.set(None);
+                    scene_seed.set(None);
+                    *tick_runner.borrow_mut() = None;
+                    Interval::stop_in_callback(&mut ticker.write());
+                }
+            }
+        };
+        apply(first);
+
+        let interval_runner = start_runner.clone();
+        ticker.set(Some(Interval::new(TICK_MS, move || {
+            let (events, measuring) = match interval_runner.borrow_mut().as_mut() {
+                Some(r) => {
+                    r.record_memory(MemorySample::capture().total_mb());
+                    let events = r.tick(now_ms());
+                    (events, matches!(r.phase(), RunnerPhase::Measuring { .. }))
+                }
+                None => return,
+            };
+            if measuring {
+                progress.with_mut(|p| {
+                    if let Some(p) = p {
+                        p.3 = "計測中";
+                    }
+                });
+            }
+            // 借用を解放してから画面に反映する
+            for event in events {
+                apply(event);
+            }
+        })));
+    };
+
+    let cancel_runner = runner.clone();
+    let cancel = move |_| {
+        *cancel_runner.borrow_mut() = None;
+        if let Some(mut interval) = ticker.write().take() {
+            interval.stop();
+        }
+        progress.set(None);
+        scene_seed.set(None);
+    };
+
+    let scoring = ScoringConfig::load();
+    let is_running = progress.read().is_some();
+    let summaries = results.read().as_ref().map(|r| r.summaries()).unwrap_or_default();
+    let comparisons = results.read().as_ref().map(|r| r.comparisons()).unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "multi-run-panel",
+            style: "margin-top: 16px;",
+            h3 { "繰り返し計測" }
+
+            div {
+                class: "control-group",
+                label { "モード" }
+                for mode in MODES {
+                    label {
+                        key: "{mode.as_str()}",
+                        style: "margin-right: 8px;",
+                        input {
+                            r#type: "checkbox",
+                            disabled: is_running,
+                            checked: selected_modes.read().contains(&mode),
+                            onchange: {
+                                let mode = mode.clone();
+                                move |evt: FormEvent| {
+                                    selected_modes.with_mut(|modes| {
+                                        modes.retain(|m| *m != mode);
+                                        if evt.checked() {
+                                            modes.push(mode.clone());
+                                        }
+                                        modes.sort_by_key(|m| MODES.iter().position(|x| x == m));
+                                    });
+                                }
+                            }
+                        }
+                        " {mode.as_str()}"
+                    }
+                }
+            }
+            div {
+                class: "control-group",
+                label { "オブジェクト数（カンマ区切り）" }
+                input {
+                    r#type: "text",
+                    disabled: is_running,
+                    value: "{counts_text}",
+                    oninput: move |evt| counts_text.set(evt.value()),
+                }
+            }
+            div {
+                class: "control-group",
+                label { "回数 / 計測秒 / ウォームアップ秒 / シード" }
+                input {
+                    r#type: "number", min: "2", max: "50", style: "width: 4em;", disabled: is_running,
+                    value: "{runs_per_config}",
+                    onchange: move |evt| if let Ok(v) = evt.value().parse() { runs_per_config.set(v) },
+                }
+                input {
+                    r#type: "number", min: "1", max: "120", style: "width: 4em;", disabled: is_running,
+                    value: "{measure_sec}",
+                    onchange: move |evt| if let Ok(v) = evt.value().parse() { measure_sec.set(v) },
+                }
+                input {
+                    r#type: "number", min: "0", max: "60", style: "width: 4em;", disabled: is_running,
+                    value: "{warmup_sec}",
+                    onchange: move |evt| if let Ok(v) = evt.value().parse() { warmup_sec.set(v) },
+                }
+                input {
+                    r#type: "number", min: "0", style: "width: 5em;", disabled: is_running,
+                    value: "{base_seed}",
+                    onchange: move |evt| if let Ok(v) = evt.value().parse() { base_seed.set(v) },
+                }
+            }
+
+            if let Some(message) = error() {
+                p { style: "color: #f44336;", "{message}" }
+            }
+
+            if let Some((completed, total, label, phase)) = progress() {
+                p { {format!("{}/{}: {} — {}", completed + 1, total, label, phase)} }
+                button { onclick: cancel, "中止" }
+            } else {
+                button { onclick: start, "開始" }
+            }
+
+            if !summaries.is_empty() {
+                table {
+                    style: "font-size: 0.8rem; margin-top: 8px;",
+                    tr {
+                        th { "構成" } th { "n" } th { "平均FPS" } th { "±SD" } th { "95%CI" } th { "CV" } th { "評価" }
+                    }
+                    for (i, s) in summaries.iter().enumerate() {
+                        tr {
+                            key: "{i}",
+                            td { {s.config.label()} }
+                            td { "{s.fps.n}" }
+                            td { {format!("{:.1}", s.fps.mean)} }
+                            td { {format!("{:.2}", s.fps.stddev)} }
+                            td { {format!("{:.1}–{:.1}", s.fps.ci_low, s.fps.ci_high)} }
+                            td { {format!("{:.1}%", s.fps.cv_percent())} }
+                            td {
+                                style: format!("color: {};", scoring.score(s.fps.mean).color()),
+                                {scoring.score(s.fps.mean).text()}
+                            }
+                        }
+                    }
+                }
+                for c in comparisons.iter() {
+                    p {
+                        key: "{c.candidate}",
+                        style: "font-size: 0.8rem;",
+                        {match c.fps {
+                            Some(test) => format!(
+                                "{} vs {}: {:+.1}fps (t={:.2}, df={:.1}, p={:.4}) {}",
+                                summaries[c.candidate].config.label(),
+                                summaries[c.baseline].config.label(),
+                                test.mean_diff,
+                                test.t,
+                                test.df,
+                                test.p_value,
+                                if test.is_significant(ALPHA) { "有意差あり" } else { "有意差なし" }
+                            ),
+                            None => format!("{}: 標本不足", summaries[c.candidate].config.label()),
+                        }}
+                    }
+                }
+                if !is_running {
+                    button {
+                        onclick: move |_| {
+                            if let Some(r) = results.read().as_ref() {
+                                download_text("multi-run-report.md", "text/markdown", &r.to_markdown(&ScoringConfig::load()));
+                            }
+                        },
+                        "レポート(Markdown)"
+                    }
+                    button {
+                        onclick: move |_| {
+                            let json = serde_json::to_string_pretty(&*recorded_runs.read()).unwrap_or_default();
+                            download_text("multi-run.json", "application/json", &json);
+                        },
+                        "全記録(JSON)"
+                    }
+                }
+            }
+        }
+    }
+}
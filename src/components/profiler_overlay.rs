@@ -0,0 +1,129 @@
+use dioxus::prelude::*;
+use std::rc::Rc;
+use crate::utils::interval::Interval;
+use crate::utils::profiler::{self, ProfileSpan, ProfilerSession};
+use crate::utils::storage::download_text;
+
+/// 表示するフレーム数
+const VISIBLE_FRAMES: usize = 3;
+const WIDTH: f64 = 360.0;
+const ROW_HEIGHT: f64 = 14.0;
+
+/// 直近数フレームのフレームタイムライン（`profiling` フィーチャー有効時のみ）
+#[component]
+pub fn ProfilerOverlay() -> Element {
+    use_hook(|| Rc::new(ProfilerSession::start()));
+
+    let mut expanded = use_signal(|| true);
+    let mut paused = use_signal(|| false);
+    let mut snapshot = use_signal(|| (Vec::<ProfileSpan>::new(), Vec::<f64>::new()));
+
+    // 毎フレーム描画すると計測対象に影響するため0.5秒ごとに更新
+    let mut refresher = use_signal(|| None::<Interval>);
+    use_effect(move || {
+        refresher.set(Some(Interval::new(500, move || {
+            if !*paused.peek() && *expanded.peek() {
+                snapshot.set(profiler::snapshot());
+            }
+        })));
+    });
+    use_drop(move || {
+        if let Some(mut interval) = refresher.write().take() {
+            interval.stop();
+        }
+    });
+
+    let (spans, frame_starts) = snapshot();
+    let window_start = frame_starts
+        .len()
+        .checked_sub(VISIBLE_FRAMES + 1)
+        .map(|i| frame_starts[i])
+        .unwrap_or(0.0);
+    let window_end = frame_starts.last().copied().unwrap_or(0.0);
+    let span_ms = (window_end - window_start).max(1.0);
+    let visible: Vec<ProfileSpan> = spans
+        .iter()
+        .filter(|s| s.end_ms > window_start && s.start_ms < window_end)
+        .copied()
+        .collect();
+    let max_depth = visible.iter().map(|s| s.depth).max().unwrap_or(0) as f64;
+    let x_of = move |t: f64| ((t - window_start) / span_ms * WIDTH).clamp(0.0, WIDTH);
+
+    // 区間名ごとの平均時間（バッファ全体）
+    let mut totals: Vec<(&'static str, f64, u32)> = Vec::new();
+    for span in &spans {
+        match totals.iter_mut().find(|(name, _, _)| *name == span.name) {
+            Some((_, total, count)) => {
+                *total += span.duration_ms();
+                *count += 1;
+            }
+            None => totals.push((span.name, span.duration_ms(), 1)),
+        }
+    }
+    totals.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    rsx! {
+        div {
+            style: "position: fixed; left: 8px; bottom: 8px; z-index: 10000; background: rgba(20,20,20,0.9); color: #eee; font: 11px monospace; padding: 6px; border-radius: 4px;",
+            div {
+                style: "display: flex; gap: 6px; align-items: center;",
+                button { onclick: move |_| expanded.set(!expanded()), if expanded() { "▼ Profiler" } else { "▶ Profiler" } }
+                if expanded() {
+                    button { onclick: move |_| paused.set(!paused()), if paused() { "再開" } else { "一時停止" } }
+                    button {
+                        onclick: move |_| download_text("profile-trace.json", "application/json", &profiler::export_chrome_trace()),
+                        "Chrome trace"
+                    }
+                    button { onclick: move |_| profiler::clear(), "クリア" }
+                }
+            }
+            if expanded() {
+                svg {
+                    width: "{WIDTH}",
+                    height: "{(max_depth + 1.0) * ROW_HEIGHT + 4.0}",
+                    style: "display: block; margin-top: 4px; background: #111;",
+                    // フレーム境界
+                    for (i, start) in frame_starts.iter().filter(|&&t| t >= window_start).enumerate() {
+                        line {
+                            key: "f{i}",
+                            x1: "{x_of(*start)}",
+                            x2: "{x_of(*start)}",
+                            y1: "0",
+                            y2: "{(max_depth + 1.0) * ROW_HEIGHT + 4.0}",
+                            stroke: "#555",
+                            stroke_dasharray: "2,2",
+                        }
+                    }
+                    for (i, span) in visible.iter().enumerate() {
+                        g {
+                            key: "s{i}",
+                            rect {
+                                x: "{x_of(span.start_ms)}",
+                                y: "{span.depth as f64 * ROW_HEIGHT + 2.0}",
+                                width: "{(x_of(span.end_ms) - x_of(span.start_ms)).max(1.0)}",
+                                height: "{ROW_HEIGHT - 1.0}",
+                                fill: if span.depth == 0 { "#2196F3" } else { "#FF9800" },
+                                title { {format!("{} {:.2}ms", span.name, span.duration_ms())} }
+                            }
+                            if x_of(span.end_ms) - x_of(span.start_ms) > 40.0 {
+                                text {
+                                    x: "{x_of(span.start_ms) + 2.0}",
+                                    y: "{span.depth as f64 * ROW_HEIGHT + 12.0}",
+                                    fill: "#000",
+                                    font_size: "9",
+                                    {span.name}
+                                }
+                            }
+                        }
+                    }
+                }
+                for (name, total, count) in totals.into_iter().take(8) {
+                    div {
+                        key: "{name}",
+                        {format!("{:<24} avg {:>6.2}ms  n={}", name, total / count as f64, count)}
+                    }
+                }
+            }
+        }
+    }
+}
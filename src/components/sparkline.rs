@@ -0,0 +1,56 @@
+use dioxus::prelude::*;
+
+/// 時系列データを折れ線で表示する小さなSVGチャート
+#[component]
+pub fn Sparkline(
+    label: String,
+    values: Vec<f64>,
+    #[props(default = "#2196F3")] color: &'static str,
+    #[props(default = "MB")] unit: &'static str,
+) -> Element {
+    const WIDTH: f64 = 240.0;
+    const HEIGHT: f64 = 48.0;
+
+    if values.is_empty() {
+        return rsx! {};
+    }
+
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = (max - min).max(f64::EPSILON);
+    let step = if values.len() > 1 { WIDTH / (values.len() - 1) as f64 } else { 0.0 };
+
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let x = i as f64 * step;
+            let y = HEIGHT - (v - min) / range * (HEIGHT - 4.0) - 2.0;
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let latest = values[values.len() - 1];
+
+    rsx! {
+        div {
+            class: "sparkline",
+            p {
+                style: "font-size: 0.8rem; margin: 4px 0;",
+                {format!("{}: {:.1}{} (min {:.1} / max {:.1})", label, latest, unit, min, max)}
+            }
+            svg {
+                width: "{WIDTH}",
+                height: "{HEIGHT}",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                style: "background: #fff; border: 1px solid #ddd;",
+                polyline {
+                    points: "{points}",
+                    fill: "none",
+                    stroke: "{color}",
+                    stroke_width: "1.5",
+                }
+            }
+        }
+    }
+}
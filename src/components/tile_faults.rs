@@ -0,0 +1,218 @@
+use dioxus::prelude::*;
+use std::rc::Rc;
+use crate::utils::interval::Interval;
+use crate::utils::input_latency::{InteractionKind, LatencyTag};
+use crate::hooks::use_latency_probe;
+use crate::utils::tile_faults::{TileFaultConfig, TileFaultInjector, TileFaultStats, TileProvider};
+
+/// 集計を画面へ反映する間隔（ms）
+const STATS_INTERVAL_MS: u32 = 1000;
+
+#[component]
+fn RateSlider(name: &'static str, label: &'static str, value: f64, onchange: EventHandler<f64>) -> Element {
+    let latency = use_latency_probe();
+    rsx! {
+        div {
+            class: "control-group",
+            label { {format!("{}: {:.0}%", label, value * 100.0)} }
+            input {
+                r#type: "range",
+                min: "0",
+                max: "100",
+                value: "{(value * 100.0).round()}",
+                oninput: move |evt| {
+                    if let Ok(v) = evt.value().parse::<f64>() {
+                        let tag = LatencyTag::new(InteractionKind::Slider, format!("tile-fault/{}", name));
+                        latency.measure(tag, || onchange.call(v / 100.0));
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// タイル読み込みへの障害注入の設定と、再試行・代替タイルの集計
+#[component]
+pub fn TileFaultPanel() -> Element {
+    // マップの初期化より先にタイルレイヤーを差し替える
+    let injector = use_hook(|| Rc::new(TileFaultInjector::new()));
+    let config = use_signal(TileFaultConfig::default);
+    let mut stats = use_signal(TileFaultStats::default);
+    let error = use_signal(|| None::<String>);
+
+    use_hook({
+        let injector = injector.clone();
+        move || Rc::new(Interval::new(STATS_INTERVAL_MS, move || stats.set(injector.stats())))
+    });
+
+    let apply = {
+        let injector = injector.clone();
+        move |update: &dyn Fn(&mut TileFaultConfig)| {
+            let (mut config, mut error) = (config, error);
+            let mut next = config.peek().clone();
+            update(&mut next);
+            match injector.apply(&next) {
+                Ok(()) => {
+                    error.set(None);
+                    config.set(next);
+                }
+                Err(e) => error.set(Some(e)),
+            }
+        }
+    };
+    let current = config();
+    let s = stats();
+
+    rsx! {
+        div {
+            class: "tile-fault-panel",
+            style: "margin-top: 16px;",
+            h3 { "タイル障害注入" }
+
+            div {
+                class: "control-group",
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: current.enabled,
+                        onchange: {
+                            let apply = apply.clone();
+                            move |evt: FormEvent| apply(&|c| c.enabled = evt.checked())
+                        },
+                    }
+                    " 有効"
+                }
+                select {
+                    value: if current.provider == TileProvider::Local { "local" } else { "remote" },
+                    onchange: {
+                        let apply = apply.clone();
+                        move |evt: FormEvent| {
+                            let provider = if evt.value() == "local" { TileProvider::Local } else { TileProvider::Remote };
+                            apply(&|c| c.provider = provider)
+                        }
+                    },
+                    option { value: "remote", "OpenStreetMap" }
+                    option { value: "local", "ローカル生成" }
+                }
+            }
+
+            RateSlider {
+                name: "error",
+                label: "通信エラー",
+                value: current.error_rate,
+                onchange: { let apply = apply.clone(); move |v| apply(&|c| c.error_rate = v) },
+            }
+            RateSlider {
+                name: "429",
+                label: "429",
+                value: current.too_many_requests_rate,
+                onchange: { let apply = apply.clone(); move |v| apply(&|c| c.too_many_requests_rate = v) },
+            }
+            RateSlider {
+                name: "503",
+                label: "503",
+                value: current.unavailable_rate,
+                onchange: { let apply = apply.clone(); move |v| apply(&|c| c.unavailable_rate = v) },
+            }
+            RateSlider {
+                name: "truncate",
+                label: "画像の切り捨て",
+                value: current.truncate_rate,
+                onchange: { let apply = apply.clone(); move |v| apply(&|c| c.truncate_rate = v) },
+            }
+            RateSlider {
+                name: "corrupt",
+                label: "画像の破損",
+                value: current.corrupt_rate,
+                onchange: { let apply = apply.clone(); move |v| apply(&|c| c.corrupt_rate = v) },
+            }
+
+            div {
+                class: "control-group",
+                label { "遅延 (ms)" }
+                input {
+                    r#type: "number",
+                    min: "0",
+                    style: "width: 5em;",
+                    value: "{current.latency_ms}",
+                    onchange: {
+                        let apply = apply.clone();
+                        move |evt: FormEvent| {
+                            if let Ok(v) = evt.value().parse::<f64>() {
+                                apply(&|c| c.latency_ms = v)
+                            }
+                        }
+                    },
+                }
+                label { " ±" }
+                input {
+                    r#type: "number",
+                    min: "0",
+                    style: "width: 5em;",
+                    value: "{current.latency_jitter_ms}",
+                    onchange: {
+                        let apply = apply.clone();
+                        move |evt: FormEvent| {
+                            if let Ok(v) = evt.value().parse::<f64>() {
+                                apply(&|c| c.latency_jitter_ms = v)
+                            }
+                        }
+                    },
+                }
+                label { " 再試行" }
+                input {
+                    r#type: "number",
+                    min: "0",
+                    max: "8",
+                    style: "width: 3em;",
+                    value: "{current.max_retries}",
+                    onchange: {
+                        let apply = apply.clone();
+                        move |evt: FormEvent| {
+                            if let Ok(v) = evt.value().parse::<u32>() {
+                                apply(&|c| c.max_retries = v.min(8))
+                            }
+                        }
+                    },
+                }
+            }
+
+            if let Some(message) = error() {
+                p { style: "color: #f44336;", "{message}" }
+            }
+
+            if s.requests > 0 {
+                p {
+                    style: "font-size: 0.8rem;",
+                    {format!(
+                        "要求 {} / 成功 {} / 再試行 {} / 代替タイル {} / 平均 {:.0}ms",
+                        s.requests,
+                        s.loaded,
+                        s.retries,
+                        s.fallbacks,
+                        s.avg_latency_ms()
+                    )}
+                }
+                p {
+                    style: "font-size: 0.8rem; color: #666;",
+                    {format!(
+                        "注入: 通信エラー {} / 429 {} / 503 {} / 切り捨て {} / 破損 {}（デコード失敗 {}）",
+                        s.injected_errors,
+                        s.injected_429,
+                        s.injected_503,
+                        s.truncated,
+                        s.corrupted,
+                        s.decode_errors
+                    )}
+                }
+                button {
+                    onclick: move |_| {
+                        injector.reset_stats();
+                        stats.set(TileFaultStats::default());
+                    },
+                    "集計をリセット"
+                }
+            }
+        }
+    }
+}
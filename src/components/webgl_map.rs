@@ -1,7 +1,9 @@
 use dioxus::prelude::*;
+use crate::utils::profiler::profile_scope;
+use crate::utils::seeded_random::random_source_js;
 
 #[component]
-pub fn WebGLMap(object_count: i32) -> Element {
+pub fn WebGLMap(object_count: i32, seed: Option<u64>) -> Element {
     // ユニークなコンテナ ID を生成（一度だけ計算）
     let container_id = use_memo(|| {
         format!("webgl-map-container-{}", js_sys::Math::random().to_string().replace(".", ""))
@@ -92,10 +94,21 @@ pub fn WebGLMap(object_count: i32) -> Element {
                 // 新しいマップインスタンスを作成
                 try {{
                     const map = L.map('{}').setView([35.6762, 139.6503], 13);
-                    L.tileLayer('https://{{s}}.tile.openstreetmap.org/{{z}}/{{x}}/{{y}}.png', {{
+                    // 障害注入が有効ならそのタイルレイヤーを使う（utils::tile_faults）
+                    const tiles = (window.__tileLayerFactory || L.tileLayer)('https://{{s}}.tile.openstreetmap.org/{{z}}/{{x}}/{{y}}.png', {{
                         attribution: '© OpenStreetMap contributors'
                     }}).addTo(map);
                     
+                    // ベンチマーク中はタイル読み込み区間を記録（長時間タスクの原因推定用）
+                    let tileLoadStart = null;
+                    tiles.on('loading', () => {{ tileLoadStart = performance.now(); }});
+                    tiles.on('load', () => {{
+                        if (tileLoadStart !== null && window.__benchPhase) {{
+                            window.__benchPhase(2 /* tile-load */, tileLoadStart, performance.now());
+                        }}
+                        tileLoadStart = null;
+                    }});
+                    
                     window.theMapInstance = map;
                     window.theMapMarkers = [];
                     console.log('WebGL Map initialized successfully');
@@ -110,6 +123,7 @@ pub fn WebGLMap(object_count: i32) -> Element {
     
     // 地図の初期化（一度だけ実行）
     use_effect(move || {
+        profile_scope!("effect:map_init");
         let _ = js_sys::eval(&init_code());
     });
     
@@ -121,9 +135,17 @@ pub fn WebGLMap(object_count: i32) -> Element {
         count_signal.set(object_count);
     }
     
+    // 配置のシード（未指定ならランダム）。変更時はマーカーを作り直す
+    let mut seed_signal = use_signal(|| seed);
+    if seed_signal() != seed {
+        seed_signal.set(seed);
+    }
+    
     // シグナルの変更時にマーカーを更新
     use_effect(move || {
+        profile_scope!("effect:marker_update");
         let current_count = count_signal();
+        let current_seed = seed_signal();
         web_sys::console::log_1(&format!("[WebGLMap] Marker update effect triggered with count: {}", current_count).into());
         
         let container_id_str = container_id();
@@ -138,6 +160,8 @@ pub fn WebGLMap(object_count: i32) -> Element {
                     const ne = bounds.getNorthEast();
                     
                     const targetCount = {};
+                    // シード指定時は毎回同じ配置・速度になる
+                    {}
                     console.log('[WebGL JS] Adding ' + targetCount + ' WebGL markers');
                     
                     // WebGLモードの初期化
@@ -186,6 +210,8 @@ pub fn WebGLMap(object_count: i32) -> Element {
                         }});
                     }}
                     
+                    const rebuildStart = performance.now();
+                    
                     // 既存のマーカーとPixiマーカーをクリア
                     if (window.theMapMarkers) {{
                         console.log('[WebGL JS] Removing existing markers...');
@@ -203,8 +229,8 @@ pub fn WebGLMap(object_count: i32) -> Element {
                     // 新しいWebGLマーカーを追加
                     window.theMapMarkers = [];
                     for (let i = 0; i < targetCount; i++) {{
-                        const lat = sw.lat + Math.random() * (ne.lat - sw.lat);
-                        const lng = sw.lng + Math.random() * (ne.lng - sw.lng);
+                        const lat = sw.lat + rand() * (ne.lat - sw.lat);
+                        const lng = sw.lng + rand() * (ne.lng - sw.lng);
                         
                         // WebGLマーカー用の位置情報を保存
                         const latlng = {{ lat, lng }};
@@ -226,14 +252,14 @@ pub fn WebGLMap(object_count: i32) -> Element {
                         
                         // アニメーション用の速度を設定（より大きな値に）
                         graphics._velocity = {{
-                            lat: (Math.random() - 0.5) * 0.0002,
-                            lng: (Math.random() - 0.5) * 0.0002
+                            lat: (rand() - 0.5) * 0.0002,
+                            lng: (rand() - 0.5) * 0.0002
                         }};
                         
                         // アニメーション用の速度を設定
                         marker._velocity = {{
-                            lat: (Math.random() - 0.5) * 0.00005,
-                            lng: (Math.random() - 0.5) * 0.00005
+                            lat: (rand() - 0.5) * 0.00005,
+                            lng: (rand() - 0.5) * 0.00005
                         }};
                         
                         window.theMapMarkers.push(marker);
@@ -241,27 +267,41 @@ pub fn WebGLMap(object_count: i32) -> Element {
                     
                     console.log('[WebGL JS] Actually created markers:', window.theMapMarkers.length);
                     
+                    if (window.__benchPhase) {{
+                        window.__benchPhase(1 /* marker-rebuild */, rebuildStart, performance.now());
+                    }}
+                    
                     // アニメーションを開始
                     if (window.animationId) {{
                         cancelAnimationFrame(window.animationId);
                     }}
                     
+                    let frameIndex = 0;
+                    
                     function animate() {{
+                        const frameStart = performance.now();
+                        // 詳細度に応じてN個に1個だけ更新し、移動量をN倍にする
+                        const stride = window.__mapUpdateStride || 1;
+                        // カオスモードの時間歪曲中はシミュレーション時間を伸縮する
+                        const step = stride * (window.__chaosTimeScale ?? 1);
+                        frameIndex = (frameIndex + 1) % stride;
+                        
                         // WebGLモードのアニメーション
                         window.theMapMarkers.forEach((marker, index) => {{
+                            if (index % stride !== frameIndex) return;
                             const pixiMarker = window.pixiMarkers[index];
                             if (pixiMarker && pixiMarker._velocity) {{
-                                let newLat = marker._latlng.lat + pixiMarker._velocity.lat;
-                                let newLng = marker._latlng.lng + pixiMarker._velocity.lng;
+                                let newLat = marker._latlng.lat + pixiMarker._velocity.lat * step;
+                                let newLng = marker._latlng.lng + pixiMarker._velocity.lng * step;
                                 
                                 // 境界でバウンス
                                 if (newLat <= sw.lat || newLat >= ne.lat) {{
                                     pixiMarker._velocity.lat *= -1;
-                                    newLat = marker._latlng.lat + pixiMarker._velocity.lat;
+                                    newLat = marker._latlng.lat + pixiMarker._velocity.lat * step;
                                 }}
                                 if (newLng <= sw.lng || newLng >= ne.lng) {{
                                     pixiMarker._velocity.lng *= -1;
-                                    newLng = marker._latlng.lng + pixiMarker._velocity.lng;
+                                    newLng = marker._latlng.lng + pixiMarker._velocity.lng * step;
                                 }}
                                 
                                 marker._latlng.lat = newLat;
@@ -273,6 +313,10 @@ pub fn WebGLMap(object_count: i32) -> Element {
                             }}
                         }});
                         
+                        if (window.__benchPhase) {{
+                            window.__benchPhase(0 /* animate */, frameStart, performance.now());
+                        }}
+                        
                         window.animationId = requestAnimationFrame(animate);
                     }}
                     
@@ -281,7 +325,7 @@ pub fn WebGLMap(object_count: i32) -> Element {
                     }}
                 }}
             }}, 200);
-        "#, current_count, container_id_str);
+        "#, current_count, random_source_js(current_seed), container_id_str);
         
         let _ = js_sys::eval(&update_code);
     });
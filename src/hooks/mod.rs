@@ -1,12 +1,22 @@
 pub mod use_map_config;
-// TODO: Feature-gate these unused modules to reduce WASM size
+pub mod use_adaptive_render;
+pub mod use_benchmark;
+pub mod use_performance_monitor;
+pub mod use_input_fuzzer;
+pub mod use_latency_probe;
+// マップ上のカオスモード（`chaos-testing` フィーチャー有効時のみ）
 #[cfg(feature = "chaos-testing")]
 pub mod use_chaos_engine;
-#[cfg(feature = "benchmarking")]
-pub mod use_benchmark;
+#[cfg(feature = "chaos-testing")]
+pub mod use_soak_test;
 
 pub use use_map_config::*;
+pub use use_adaptive_render::*;
+pub use use_benchmark::*;
+pub use use_performance_monitor::*;
+pub use use_input_fuzzer::*;
+pub use use_latency_probe::*;
 #[cfg(feature = "chaos-testing")]
 pub use use_chaos_engine::*;
-#[cfg(feature = "benchmarking")]
-pub use use_benchmark::*;
\ No newline at end of file
+#[cfg(feature = "chaos-testing")]
+pub use use_soak_test::*;
\ No newline at end of file
@@ -0,0 +1,97 @@
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::utils::adaptive_render::{AdaptiveConfig, AdaptiveController, AdaptiveDecision, DetailLevel, RenderStep};
+use crate::utils::clock::now_ms;
+use crate::utils::fps_counter::FrameLoop;
+use crate::utils::frame_stats::FrameTimeHistogram;
+
+/// 判定に使う区間の長さ（ms）
+const WINDOW_MS: f64 = 1000.0;
+/// 保持する切り替えログの件数
+const DECISION_LOG_LIMIT: usize = 50;
+
+/// マップのJSコードが参照する更新間隔
+fn apply_detail(detail: DetailLevel) {
+    if let Some(window) = web_sys::window() {
+        let _ = js_sys::Reflect::set(&window, &"__mapUpdateStride".into(), &detail.update_stride().into());
+    }
+}
+
+/// フレーム時間の計測ループ。ドロップ時に詳細度を戻す
+struct FrameSampler {
+    _frames: FrameLoop,
+}
+
+impl Drop for FrameSampler {
+    fn drop(&mut self) {
+        apply_detail(DetailLevel::Full);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct AdaptiveRender {
+    /// 自動調整が有効か
+    pub enabled: Signal<bool>,
+    /// 現在の段階（無効時も手動選択に追従する）
+    pub step: Signal<RenderStep>,
+    /// 新しい順の切り替え履歴
+    pub decisions: Signal<Vec<AdaptiveDecision>>,
+    controller: Signal<Rc<RefCell<AdaptiveController>>>,
+}
+
+impl AdaptiveRender {
+    /// 手動でモードが選ばれた場合に現在位置を合わせる
+    pub fn reset_to(&mut self, step: RenderStep) {
+        let now = now_ms();
+        self.controller.peek().borrow_mut().reset_to(step.clone(), now);
+        apply_detail(step.detail);
+        self.step.set(step);
+    }
+}
+
+/// カスタムフック: 実測フレーム時間に応じたレンダリング方式の自動調整
+pub fn use_adaptive_render(initial: RenderStep) -> AdaptiveRender {
+    let enabled = use_signal(|| false);
+    let mut step = use_signal(|| initial.clone());
+    let mut decisions = use_signal(Vec::<AdaptiveDecision>::new);
+    let controller = use_signal(|| Rc::new(RefCell::new(AdaptiveController::new(AdaptiveConfig::default(), initial))));
+
+    use_hook(move || {
+        let controller = controller.peek().clone();
+        let mut histogram = FrameTimeHistogram::new();
+        let mut window_start: Option<f64> = None;
+
+        let frames = FrameLoop::start(move |frame_ms: f64, timestamp: f64| {
+            histogram.record(frame_ms, timestamp);
+            let start = *window_start.get_or_insert(timestamp);
+            if timestamp - start < WINDOW_MS {
+                return;
+            }
+
+            let stats = histogram.stats();
+            histogram.reset();
+            window_start = Some(timestamp);
+            if !*enabled.peek() {
+                return;
+            }
+
+            let decision = controller.borrow_mut().observe(&stats, timestamp);
+            if let Some(decision) = decision {
+                web_sys::console::log_1(
+                    &format!("[Adaptive] {} → {}: {}", decision.from.label(), decision.to.label(), decision.reason).into(),
+                );
+                apply_detail(decision.to.detail);
+                step.set(decision.to.clone());
+                decisions.with_mut(|log| {
+                    log.insert(0, decision);
+                    log.truncate(DECISION_LOG_LIMIT);
+                });
+            }
+        });
+
+        Rc::new(FrameSampler { _frames: frames })
+    });
+
+    AdaptiveRender { enabled, step, decisions, controller }
+}
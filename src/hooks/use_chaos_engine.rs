@@ -1,36 +1,47 @@
 use dioxus::prelude::*;
+use crate::utils::profiler::profile_scope;
+use crate::utils::clock::now_ms;
 use crate::utils::interval::Interval;
-use std::time::Instant;
+use crate::utils::fps_counter::FrameLoop;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::sim::rng::SimRng;
+use crate::utils::chaos_scenario::{pick_event, ScenarioAction, ScenarioRunner};
+use crate::utils::chaos_intensity::ChaosIntensity;
+use crate::utils::chaos_report::{ChaosRunRecorder, ChaosRunReport, ReportConfig};
+use crate::hooks::{use_performance_monitor, PerformanceMonitorHandle};
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub enum ChaosEventType {
-    ObjectSpawn,
-    ObjectRemove,
-    ViewportChange,
-    ModeSwitch,
-    MemoryPressure,
-}
+pub use crate::utils::chaos_intensity::ChaosEventType;
+pub use crate::utils::chaos_impact::{ChaosImpactTracker, EventImpact};
+
+/// イベント発生を判定する間隔（ms）
+const TICK_MS: u32 = 50;
+/// イベント直後のフレームをその影響とみなす期間（ms）
+const IMPACT_WINDOW_MS: f64 = 1000.0;
+/// 影響の集計を画面に反映するティック間隔
+const REPORT_EVERY_TICKS: u32 = 20;
 
 #[derive(Clone, Debug)]
 pub struct ChaosEvent {
     pub event_type: ChaosEventType,
-    pub timestamp: Instant,
+    /// 発生時刻（`performance.now()`, ms）
+    pub timestamp: f64,
     pub description: String,
 }
 
 #[derive(Clone, Debug)]
 pub struct ChaosEngine {
-    pub intensity: u8,
+    pub intensity: ChaosIntensity,
     pub events: Vec<ChaosEvent>,
     pub is_running: bool,
     pub total_events: usize,
-    pub start_time: Option<Instant>,
+    pub start_time: Option<f64>,
 }
 
 impl ChaosEngine {
     pub fn new(intensity: u8) -> Self {
         Self {
-            intensity: intensity.min(10),
+            intensity: ChaosIntensity::saturating(intensity),
             events: Vec::new(),
             is_running: false,
             total_events: 0,
@@ -41,7 +52,7 @@ impl ChaosEngine {
     pub fn add_event(&mut self, event_type: ChaosEventType, description: String) {
         self.events.push(ChaosEvent {
             event_type,
-            timestamp: Instant::now(),
+            timestamp: now_ms(),
             description,
         });
         self.total_events += 1;
@@ -60,28 +71,21 @@ pub struct EventStats {
     pub events_per_second: f64,
 }
 
+#[derive(Clone, Copy)]
 pub struct ChaosEngineHandle {
     pub engine: Signal<ChaosEngine>,
     pub interval: Signal<Option<Interval>>,
     pub event_stats: Memo<EventStats>,
-    pub performance_monitor: Resource<Result<PerformanceReport, String>>,
-}
-
-#[derive(Clone, Debug)]
-pub struct PerformanceReport {
-    pub memory_usage: f64,
-    pub fps_average: f64,
-    pub dropped_frames: usize,
-}
-
-/// カオスパフォーマンスを監視する非同期関数
-async fn monitor_chaos_performance(_engine: Signal<ChaosEngine>) -> Result<PerformanceReport, String> {
-    // 実際の実装では、JavaScript側からパフォーマンスメトリクスを取得
-    Ok(PerformanceReport {
-        memory_usage: 0.0,
-        fps_average: 60.0,
-        dropped_frames: 0,
-    })
+    /// 実測のFPS・落ちたフレーム数・メモリ（開始時にリセット）
+    pub performance: PerformanceMonitorHandle,
+    /// イベント種別ごとのFPS・遅延への影響
+    pub impact: Signal<Vec<EventImpact>>,
+    /// シナリオ実行中の進捗（0〜1）
+    pub scenario_progress: Signal<Option<f64>>,
+    /// 直近の実行のレポート（停止・完了時に作る）
+    pub run_report: Signal<Option<ChaosRunReport>>,
+    tracker: Signal<Rc<RefCell<ChaosImpactTracker>>>,
+    recorder: Signal<Rc<RefCell<ChaosRunRecorder>>>,
 }
 
 /// イベント統計を計算するヘルパー関数
@@ -94,7 +98,7 @@ fn categorize_events(events: &[ChaosEvent]) -> Vec<(ChaosEventType, usize)> {
     }
     
     let mut result: Vec<_> = counts.into_iter().collect();
-    result.sort_by(|a, b| b.1.cmp(&a.1)); // 降順ソート
+    result.sort_by_key(|(_, count)| std::cmp::Reverse(*count)); // 降順ソート
     result
 }
 
@@ -102,12 +106,34 @@ fn categorize_events(events: &[ChaosEvent]) -> Vec<(ChaosEventType, usize)> {
 pub fn use_chaos_engine(initial_intensity: u8) -> ChaosEngineHandle {
     let engine = use_signal(|| ChaosEngine::new(initial_intensity));
     let mut interval = use_signal(|| None::<Interval>);
+    let impact = use_signal(Vec::<EventImpact>::new);
+    let scenario_progress = use_signal(|| None::<f64>);
+    let tracker = use_signal(|| Rc::new(RefCell::new(ChaosImpactTracker::new(IMPACT_WINDOW_MS))));
+    let run_report = use_signal(|| None::<ChaosRunReport>);
+    let recorder = use_signal(|| Rc::new(RefCell::new(ChaosRunRecorder::new())));
+    
+    // 実行中のフレーム時間をイベント種別ごとに振り分け、レポート用に記録する
+    use_hook(move || {
+        let tracker = tracker.peek().clone();
+        let recorder = recorder.peek().clone();
+        Rc::new(FrameLoop::start(move |frame_ms, timestamp| {
+            if engine.try_peek().is_ok_and(|e| e.is_running) {
+                let resolved = tracker.borrow_mut().on_frame(frame_ms, timestamp);
+                let mut recorder = recorder.borrow_mut();
+                recorder.record_frame(frame_ms, timestamp);
+                for (at_ms, latency) in resolved {
+                    recorder.record_latency(at_ms, latency);
+                }
+            }
+        }))
+    });
     
     // イベント統計をメモ化
     let event_stats = use_memo(move || {
+        profile_scope!("chaos_event_stats");
         let e = engine.read();
         let elapsed = e.start_time
-            .map(|start| start.elapsed().as_secs_f64())
+            .map(|start| ((now_ms() - start) / 1000.0).max(0.001))
             .unwrap_or(1.0);
         
         EventStats {
@@ -117,10 +143,7 @@ pub fn use_chaos_engine(initial_intensity: u8) -> ChaosEngineHandle {
         }
     });
     
-    // パフォーマンス監視をリソースとして管理
-    let performance_monitor = use_resource(move || async move {
-        monitor_chaos_performance(engine).await
-    });
+    let performance = use_performance_monitor();
     
     // クリーンアップ: コンポーネントのアンマウント時にインターバルを停止
     use_drop(move || {
@@ -133,15 +156,117 @@ pub fn use_chaos_engine(initial_intensity: u8) -> ChaosEngineHandle {
         engine,
         interval,
         event_stats,
-        performance_monitor,
+        performance,
+        impact,
+        scenario_progress,
+        run_report,
+        tracker,
+        recorder,
     }
 }
 
+/// 記録からレポートを作る（種別の表記は `ChaosEventType::label`）
+fn build_run_report(engine: &ChaosEngine, recorder: &ChaosRunRecorder) -> Option<ChaosRunReport> {
+    if recorder.is_empty() {
+        return None;
+    }
+    let title = format!("マップのカオス実行（強度 {} / {}イベント）", engine.intensity, engine.total_events);
+    Some(recorder.build_report(&title, &ReportConfig::default()))
+}
+
 impl ChaosEngineHandle {
-    /// カオスエンジンを開始
-    pub fn start(&mut self) {
-        self.engine.write().is_running = true;
-        self.engine.write().start_time = Some(Instant::now());
+    /// カオスエンジンを開始する
+    ///
+    /// `scenario` を渡すとその時刻表どおりに、`None` なら強度に応じてランダムにイベントを発生させる。
+    /// 各アクションは `execute` で適用し、イベントなら返した説明を記録する。
+    /// 適用時の乱数には `SimRng` を渡すので、シナリオ実行時は同じシードで同じ結果になる。
+    pub fn start(
+        &mut self,
+        mut scenario: Option<ScenarioRunner>,
+        mut execute: impl FnMut(&ScenarioAction, &mut SimRng) -> String + 'static,
+    ) {
+        if let Some(mut int) = self.interval.write().take() {
+            int.stop();
+        }
+        self.engine.with_mut(|e| {
+            e.is_running = true;
+            e.start_time = Some(now_ms());
+            e.events.clear();
+            e.total_events = 0;
+        });
+        self.tracker.peek().borrow_mut().reset();
+        self.recorder.peek().borrow_mut().start(now_ms());
+        self.performance.reset();
+        self.impact.set(Vec::new());
+        self.run_report.set(None);
+        self.scenario_progress.set(scenario.as_ref().map(|_| 0.0));
+        
+        let mut rng = match &scenario {
+            Some(runner) => SimRng::new(runner.seed.wrapping_add(1)),
+            None => SimRng::new((js_sys::Math::random() * u32::MAX as f64) as u64),
+        };
+        let mut engine = self.engine;
+        let mut impact = self.impact;
+        let mut progress = self.scenario_progress;
+        let mut interval = self.interval;
+        let tracker = self.tracker.peek().clone();
+        let recorder = self.recorder.peek().clone();
+        let mut run_report = self.run_report;
+        let mut ticks = 0u32;
+        // 1ティックに満たない端数のイベントを持ち越す（発生率がティック数で頭打ちにならないように）
+        let mut pending = 0.0;
+        let mut last_tick = now_ms();
+        self.interval.set(Some(Interval::new(TICK_MS, move || {
+            profile_scope!("map_chaos_tick");
+            let now = now_ms();
+            let elapsed = now - last_tick;
+            last_tick = now;
+            let actions = match scenario.as_mut() {
+                Some(runner) => runner.due(now),
+                None => {
+                    // 強度の発生率と構成比に従う
+                    let profile = engine.peek().intensity.profile();
+                    let mix = profile.map_mix();
+                    if mix.is_empty() {
+                        Vec::new()
+                    } else {
+                        pending += profile.events_per_second * elapsed / 1000.0;
+                        let due = pending.floor();
+                        pending -= due;
+                        (0..due as usize).map(|_| ScenarioAction::Fire(pick_event(&mut rng, &mix))).collect()
+                    }
+                }
+            };
+            
+            let mut finished = false;
+            for action in actions {
+                let at_ms = now_ms();
+                let description = execute(&action, &mut rng);
+                match action {
+                    ScenarioAction::Fire(event_type) => {
+                        tracker.borrow_mut().on_event(event_type.clone(), at_ms);
+                        recorder.borrow_mut().record_event(at_ms, event_type.label(), &description);
+                        engine.write().add_event(event_type, description);
+                    }
+                    ScenarioAction::Finished => finished = true,
+                    _ => {}
+                }
+            }
+            
+            ticks += 1;
+            if finished {
+                engine.write().is_running = false;
+                impact.set(tracker.borrow().report());
+                run_report.set(build_run_report(&engine.peek(), &recorder.borrow()));
+                progress.set(Some(1.0));
+                Interval::stop_in_callback(&mut interval.write());
+            } else if ticks.is_multiple_of(REPORT_EVERY_TICKS) {
+                impact.set(tracker.borrow().report());
+                if let Some(runner) = &scenario {
+                    progress.set(Some(runner.progress(now)));
+                }
+            }
+        })));
     }
     
     /// カオスエンジンを停止
@@ -150,15 +275,18 @@ impl ChaosEngineHandle {
         if let Some(mut int) = self.interval.write().take() {
             int.stop();
         }
+        self.impact.set(self.tracker.peek().borrow().report());
+        let report = build_run_report(&self.engine.peek(), &self.recorder.peek().borrow());
+        self.run_report.set(report);
     }
     
-    /// 強度を変更
-    pub fn set_intensity(&mut self, intensity: u8) {
-        self.engine.write().intensity = intensity.min(10);
+    /// イベントの影響を受けていないフレームの平均FPS
+    pub fn baseline_fps(&self) -> Option<f64> {
+        self.tracker.peek().borrow().baseline_fps()
     }
     
-    /// イベントを追加
-    pub fn add_event(&mut self, event_type: ChaosEventType, description: String) {
-        self.engine.write().add_event(event_type, description);
+    /// 強度を変更（10を超える値は10に丸める）
+    pub fn set_intensity(&mut self, intensity: u8) {
+        self.engine.write().intensity = ChaosIntensity::saturating(intensity);
     }
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+use dioxus::prelude::*;
+use std::rc::Rc;
+use crate::utils::input_fuzzer::{FuzzConfig, FuzzTarget, InputFuzzer};
+use crate::utils::input_latency::{InputLatencyMeasurer, LatencyTag};
+use crate::utils::clock::now_ms;
+use crate::utils::interval::Interval;
+
+/// 合成入力を送る間隔（ms）
+const FUZZ_TICK_MS: u32 = 16;
+
+/// 1件の測定ごとに呼ぶコールバック（タグ, 遅延ms）
+type MeasuredCallback = Rc<dyn Fn(&LatencyTag, f64)>;
+
+#[derive(Clone, Copy)]
+pub struct InputFuzzerHandle {
+    pub running: Signal<bool>,
+    /// 送ったイベント数（対象の要素がなかったものは含まない）
+    pub dispatched: Signal<usize>,
+    pub error: Signal<Option<String>>,
+    /// 今回のファジングの測定（ルートの測定とは別に持ち、開始時にリセットする）
+    pub latency: Signal<InputLatencyMeasurer>,
+    interval: Signal<Option<Interval>>,
+}
+
+/// カスタムフック: 合成入力を送り、各イベントの処理時間を専用の測定器に記録する
+pub fn use_input_fuzzer() -> InputFuzzerHandle {
+    let running = use_signal(|| false);
+    let dispatched = use_signal(|| 0usize);
+    let error = use_signal(|| None::<String>);
+    let latency = use_signal(InputLatencyMeasurer::new);
+    let mut interval = use_signal(|| None::<Interval>);
+
+    use_drop(move || {
+        if let Some(mut int) = interval.write().take() {
+            int.stop();
+        }
+    });
+
+    InputFuzzerHandle { running, dispatched, error, latency, interval }
+}
+
+impl InputFuzzerHandle {
+    /// 専用の測定をリセットして送信を開始する。
+    /// 各イベントの送信から次フレームまでを操作の種類と送り先のタグ付きで測り `on_measured` に渡し、予定時間に達したら `on_finish` を呼ぶ
+    pub fn start(
+        &mut self,
+        config: FuzzConfig,
+        targets: Vec<FuzzTarget>,
+        on_measured: impl Fn(&LatencyTag, f64) + 'static,
+        on_finish: impl FnOnce() + 'static,
+    ) {
+        self.stop();
+        let mut fuzzer = match InputFuzzer::new(config, targets) {
+            Ok(fuzzer) => fuzzer,
+            Err(e) => {
+                self.error.set(Some(e));
+                return;
+            }
+        };
+        self.error.set(None);
+        self.dispatched.set(0);
+        self.running.set(true);
+        self.latency.peek().reset();
+
+        let started_at = now_ms();
+        let mut last_tick = started_at;
+        let duration_ms = fuzzer.config().duration_ms;
+        let on_measured: MeasuredCallback = Rc::new(on_measured);
+        let mut on_finish = Some(on_finish);
+        let latency = self.latency;
+        let mut dispatched = self.dispatched;
+        let mut running = self.running;
+        let mut interval = self.interval;
+
+        self.interval.set(Some(Interval::new(FUZZ_TICK_MS, move || {
+            // タイマーは遅れることがあるため、実際の経過時間分を送る（1回の上限は `InputFuzzer::due` で制限）
+            let now = now_ms();
+            let elapsed = now - last_tick;
+            last_tick = now;
+            let mut sent = 0;
+            for _ in 0..fuzzer.due(elapsed) {
+                let action = fuzzer.next_action();
+                // 送信直前から計測し、送れたら次フレームで完了させる
+                let on_measured = on_measured.clone();
+                let tag = fuzzer.tag(&action);
+                let finish = latency.peek().measure_with_raf(tag.clone(), move |ms| on_measured(&tag, ms));
+                if fuzzer.dispatch(&action) {
+                    finish();
+                    sent += 1;
+                }
+            }
+            if sent > 0 {
+                *dispatched.write() += sent;
+            }
+
+            if now - started_at < duration_ms {
+                return;
+            }
+            running.set(false);
+            if let Some(on_finish) = on_finish.take() {
+                on_finish();
+            }
+            Interval::stop_in_callback(&mut interval.write());
+        })));
+    }
+
+    /// 判定せずに止める
+    pub fn stop(&mut self) {
+        if let Some(mut int) = self.interval.write().take() {
+            int.stop();
+        }
+        self.running.set(false);
+    }
+}
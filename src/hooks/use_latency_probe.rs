@@ -0,0 +1,38 @@
+use dioxus::prelude::*;
+use crate::utils::input_latency::{InputLatencyMeasurer, LatencyTag, TaggedLatencyStats};
+
+/// ルートが共有する入力遅延の測定（`use_context_provider` で渡す）への窓口
+#[derive(Clone, Copy)]
+pub struct LatencyProbe {
+    measurer: Option<Signal<InputLatencyMeasurer>>,
+}
+
+impl LatencyProbe {
+    /// `action` の実行から次のフレームまでを `tag` 付きで記録する（測定がなければ実行だけ行う）
+    pub fn measure(&self, tag: LatencyTag, action: impl FnOnce()) {
+        match self.measurer {
+            Some(measurer) => {
+                let finish = measurer.peek().measure_with_raf(tag, |_| {});
+                action();
+                finish();
+            }
+            None => action(),
+        }
+    }
+
+    /// タグごとの統計（測定がなければ空）
+    pub fn tagged_stats(&self) -> Vec<TaggedLatencyStats> {
+        self.measurer.map(|m| m.peek().get_tagged_stats()).unwrap_or_default()
+    }
+
+    pub fn set_target(&self, tag: LatencyTag, target_ms: f64) {
+        if let Some(measurer) = self.measurer {
+            measurer.peek().set_target(tag, target_ms);
+        }
+    }
+}
+
+/// カスタムフック: 祖先のルートが共有する入力遅延の測定を取得する
+pub fn use_latency_probe() -> LatencyProbe {
+    LatencyProbe { measurer: try_use_context::<Signal<InputLatencyMeasurer>>() }
+}
@@ -0,0 +1,47 @@
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::utils::fps_counter::FrameLoop;
+use crate::utils::memory::MemorySample;
+use crate::utils::performance_monitor::{PerformanceMonitor, PerformanceReport};
+
+/// 画面へ反映する間隔（ms）
+const PUBLISH_INTERVAL_MS: f64 = 500.0;
+
+#[derive(Clone, Copy)]
+pub struct PerformanceMonitorHandle {
+    /// 実測値（PUBLISH_INTERVAL_MSごとに更新）
+    pub report: Signal<PerformanceReport>,
+    monitor: Signal<Rc<RefCell<PerformanceMonitor>>>,
+}
+
+impl PerformanceMonitorHandle {
+    /// 計測をやり直す（カオス開始時など）
+    pub fn reset(&mut self) {
+        self.monitor.peek().borrow_mut().reset();
+        self.report.set(PerformanceReport::default());
+    }
+}
+
+/// カスタムフック: 実フレーム時間とメモリによるパフォーマンス集計
+pub fn use_performance_monitor() -> PerformanceMonitorHandle {
+    let mut report = use_signal(PerformanceReport::default);
+    let monitor = use_signal(|| Rc::new(RefCell::new(PerformanceMonitor::new())));
+
+    use_hook(move || {
+        let monitor = monitor.peek().clone();
+        let mut last_publish = 0.0;
+        Rc::new(FrameLoop::start(move |frame_ms, timestamp| {
+            let mut monitor = monitor.borrow_mut();
+            monitor.on_frame(frame_ms, timestamp);
+            if timestamp - last_publish < PUBLISH_INTERVAL_MS {
+                return;
+            }
+            last_publish = timestamp;
+            monitor.record_memory(MemorySample::capture().total_mb());
+            report.set(monitor.report());
+        }))
+    });
+
+    PerformanceMonitorHandle { report, monitor }
+}
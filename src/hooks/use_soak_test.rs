@@ -0,0 +1,88 @@
+use dioxus::prelude::*;
+use crate::utils::clock::now_ms;
+use crate::utils::interval::Interval;
+use crate::utils::live_handles;
+use crate::utils::memory::MemorySample;
+use crate::utils::soak::{SoakConfig, SoakMonitor, SoakSample, SoakVerdict};
+
+/// ページ内の全DOMノード数
+fn dom_node_count() -> usize {
+    js_sys::eval("document.getElementsByTagName('*').length")
+        .ok()
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0) as usize
+}
+
+#[derive(Clone, Copy)]
+pub struct SoakTestHandle {
+    /// 実行中の (経過時間ms, 予定時間ms, 直近のサンプル)
+    pub progress: Signal<Option<(f64, f64, SoakSample)>>,
+    /// 完了時の判定
+    pub verdict: Signal<Option<SoakVerdict>>,
+    interval: Signal<Option<Interval>>,
+}
+
+/// カスタムフック: 一定時間サンプリングしてリークを判定するソークテスト
+pub fn use_soak_test() -> SoakTestHandle {
+    let progress = use_signal(|| None::<(f64, f64, SoakSample)>);
+    let verdict = use_signal(|| None::<SoakVerdict>);
+    let mut interval = use_signal(|| None::<Interval>);
+
+    use_drop(move || {
+        if let Some(mut int) = interval.write().take() {
+            int.stop();
+        }
+    });
+
+    SoakTestHandle { progress, verdict, interval }
+}
+
+impl SoakTestHandle {
+    /// サンプリングを開始し、予定時間に達したら判定して `on_finish` を呼ぶ
+    pub fn start(&mut self, config: SoakConfig, on_finish: impl FnOnce(&SoakVerdict) + 'static) {
+        self.cancel();
+        self.verdict.set(None);
+
+        let started_at = now_ms();
+        let sample_interval_ms = config.sample_interval_ms;
+        let duration_ms = config.duration_ms;
+        let mut monitor = SoakMonitor::new(config);
+        let mut on_finish = Some(on_finish);
+        let mut progress = self.progress;
+        let mut verdict = self.verdict;
+        let mut interval = self.interval;
+
+        let mut sample = move || {
+            let sample = SoakSample {
+                at_ms: now_ms() - started_at,
+                memory_mb: MemorySample::capture().total_mb(),
+                dom_nodes: dom_node_count(),
+                handles: live_handles::snapshot(),
+            };
+            monitor.record(sample);
+            progress.set(Some((sample.at_ms, duration_ms, sample)));
+            if !monitor.is_finished() {
+                return;
+            }
+
+            let result = monitor.verdict();
+            web_sys::console::log_1(&format!("Soak test {}", result.summary()).into());
+            progress.set(None);
+            if let Some(on_finish) = on_finish.take() {
+                on_finish(&result);
+            }
+            verdict.set(Some(result));
+            Interval::stop_in_callback(&mut interval.write());
+        };
+        sample();
+        self.interval.set(Some(Interval::new(sample_interval_ms as u32, sample)));
+    }
+
+    /// 判定せずに中止する
+    pub fn cancel(&mut self) {
+        if let Some(mut int) = self.interval.write().take() {
+            int.stop();
+        }
+        self.progress.set(None);
+    }
+}
@@ -0,0 +1,26 @@
+//! ブラウザに依存しない計算部分
+//!
+//! アプリ（`main.rs`）とネイティブベンチマーク（`src/bin/bench.rs`）で共有する。
+//! アプリからは `crate::sim`・`crate::utils` の同じパスで使える。
+
+pub mod sim;
+
+pub mod utils {
+    pub mod alloc_tracker;
+    pub mod benchmark_export;
+    pub mod chaos_intensity;
+    pub mod chaos_scenario;
+    pub mod frame_stats;
+    pub mod histogram;
+}
+
+// 割り当て計測用: 選択中のアロケータを計測ラッパーで包む（アプリとネイティブベンチマークの両方に効く）
+#[cfg(all(feature = "alloc-tracking", feature = "wee_alloc", target_arch = "wasm32"))]
+#[global_allocator]
+static ALLOC: utils::alloc_tracker::CountingAllocator<wee_alloc::WeeAlloc> =
+    utils::alloc_tracker::CountingAllocator::new(wee_alloc::WeeAlloc::INIT);
+
+#[cfg(all(feature = "alloc-tracking", not(all(feature = "wee_alloc", target_arch = "wasm32"))))]
+#[global_allocator]
+static ALLOC: utils::alloc_tracker::CountingAllocator<std::alloc::System> =
+    utils::alloc_tracker::CountingAllocator::new(std::alloc::System);
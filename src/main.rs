@@ -1,5 +1,5 @@
-// wee_allocを使用してWASMバイナリサイズを削減
-#[cfg(feature = "wee_alloc")]
+// wee_allocを使用してWASMバイナリサイズを削減（`alloc-tracking` 有効時はライブラリ側の計測用アロケータを使う）
+#[cfg(all(feature = "wee_alloc", not(feature = "alloc-tracking")))]
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
@@ -12,7 +12,14 @@ mod routes;
 mod hooks;
 mod types;
 
+// カオスシナリオの乱数はネイティブベンチマークと同じ実装を使う
+use leaflet_webgl_hybrid_poc::sim;
+
 use routes::{home::Home, map::MapRoute, chaos::ChaosRoute};
+use utils::performance_metrics::{save_to_history, LoadMetrics, WebVitalsObserver};
+use utils::telemetry::{Telemetry, TelemetryPayload};
+use std::cell::Cell;
+use std::rc::Rc;
 
 #[derive(Clone, Routable, Debug, PartialEq)]
 enum Route {
@@ -21,15 +28,25 @@ enum Route {
     #[route("/map/:mode")]
     MapRoute { mode: String },
     #[route("/chaos/:intensity")]
-    ChaosRoute { intensity: u8 },
+    ChaosRoute { intensity: String },
 }
 
 fn main() {
     // WASM初期化時刻を記録
+    let load_metrics = capture_load_metrics();
+    
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::log_1(&"Launching Dioxus app...".into());
+    
+    // ロードメトリクスはコンテキスト経由で各ルートに渡す
+    dioxus::LaunchBuilder::new()
+        .with_context(load_metrics)
+        .launch(app);
+}
+
+fn capture_load_metrics() -> Option<LoadMetrics> {
     #[cfg(target_arch = "wasm32")]
     {
-        use crate::utils::performance_metrics::{LoadMetrics, saveLoadMetrics};
-        
         // デバッグ情報を追加
         web_sys::console::log_1(&"Starting WASM application...".into());
         
@@ -39,21 +56,56 @@ fn main() {
             }
         }
         
-        if let Some(metrics) = LoadMetrics::new() {
+        let metrics = LoadMetrics::new();
+        if let Some(metrics) = &metrics {
             web_sys::console::log_1(&format!("Initial load metrics captured: {:.0}ms", metrics.total_load_time).into());
-            // メトリクスを保存
-            let js_value = serde_wasm_bindgen::to_value(&metrics).unwrap_or(wasm_bindgen::JsValue::NULL);
-            saveLoadMetrics(js_value);
+            save_to_history(metrics);
         }
+        metrics
     }
     
-    #[cfg(target_arch = "wasm32")]
-    web_sys::console::log_1(&"Launching Dioxus app...".into());
-    
-    dioxus::launch(app);
+    #[cfg(not(target_arch = "wasm32"))]
+    None
 }
 
 fn app() -> Element {
+    // mainで取得したロードメトリクスを共有し、後から届くWeb Vitalsで更新する
+    let initial_metrics = use_context::<Option<LoadMetrics>>();
+    let mut load_metrics = use_context_provider(|| Signal::new(initial_metrics.clone()));
+    use_hook(move || {
+        initial_metrics.map(|initial| {
+            // 履歴への保存はページ非表示時にまとめて行う（エントリごとにlocalStorageへ書かない）
+            Rc::new(WebVitalsObserver::start(initial, move |updated| {
+                load_metrics.set(Some(updated));
+            }))
+        })
+    });
+    
+    // テレメトリ（オプトイン時のみ送信）。ページ非表示時に最終的なロードメトリクスを履歴に保存し、1回だけ送る
+    let telemetry = use_context_provider(Telemetry::new);
+    use_hook(move || {
+        let load_reported = Cell::new(false);
+        let reporter = telemetry.clone();
+        Rc::new(telemetry.start(move || {
+            if let Some(metrics) = &*load_metrics.peek() {
+                save_to_history(metrics);
+            }
+            if load_reported.get() {
+                return;
+            }
+            if let Some(metrics) = load_metrics.peek().clone() {
+                reporter.report(TelemetryPayload::Load(metrics));
+                load_reported.set(true);
+            }
+        }))
+    });
+    
+    // `profiling` フィーチャー有効時のみフレームタイムラインを重ねて表示
+    #[cfg(feature = "profiling")]
+    let profiler_overlay = rsx! { components::profiler_overlay::ProfilerOverlay {} };
+    #[cfg(not(feature = "profiling"))]
+    let profiler_overlay = rsx! {};
+    
     rsx! {
         // Tailwind CSS
         Link { rel: "stylesheet", href: "/assets/tailwind.css" }
@@ -64,5 +116,6 @@ fn app() -> Element {
         Script { src: "https://cdn.jsdelivr.net/npm/pixi.js@8.6.5/dist/pixi.min.js" }
         
         Router::<Route> {}
+        {profiler_overlay}
     }
 }
@@ -1,18 +1,110 @@
 use dioxus::prelude::*;
 use web_sys::window;
 use smallvec::SmallVec;
-use crate::utils::input_latency::InputLatencyMeasurer;
+use crate::utils::input_latency::{self, InputLatencyMeasurer, InteractionKind, LatencyStats, LatencyTag};
+use crate::utils::input_fuzzer::{FuzzConfig, FuzzKind, FuzzTarget};
+use crate::utils::map_input::MapInputMonitor;
+use crate::utils::clock::now_ms;
+use crate::utils::histogram::LogHistogram;
+use crate::utils::event_timing::{EventTimingMonitor, InteractionTracker};
+use crate::utils::telemetry::{Telemetry, TelemetryPayload};
+use crate::utils::profiler::profile_scope;
 use wasm_bindgen::JsCast;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::components::map::Map;
+use crate::hooks::{use_input_fuzzer, use_performance_monitor};
+use crate::utils::chaos_effects::{ChaosEffectExecutor, ChaosEffectState, ChaosEvent, Distortion, EffectConfig};
+use crate::utils::chaos_report::{ChaosRunRecorder, ChaosRunReport, ReportConfig};
+use crate::utils::fps_counter::FrameLoop;
+use crate::components::chaos_report::ChaosReportView;
+use crate::utils::live_handles::{self, HandleKind};
+use crate::utils::chaos_intensity::{ChaosIntensity, UI_EVENT_KINDS};
+use crate::components::chaos_legend::ChaosIntensityLegend;
+use crate::components::event_timing::EventTimingReport;
+use crate::components::latency_breakdown::LatencyBreakdown;
 
-#[derive(Clone)]
-pub enum ChaosEvent {
-    UIGlitch { element_id: String, severity: f32 },
-    InputCorruption { delay_ms: u32, noise: f32 },
-    VisualDistortion { distortion_type: String },
-    TimeWarp { speed_multiplier: f32 },
+/// カオス中に描画するマーカー数
+const SCENE_OBJECT_COUNT: i32 = 300;
+/// イベント生成の間隔（ms）
+const TICK_MS: u32 = 16;
+/// 強度10での入力遅延の上限（ms）
+const MAX_INPUT_DELAY_MS: f64 = 500.0;
+/// UIグリッチの対象にする実要素
+const GLITCH_TARGETS: &str = ".chaos-scene .leaflet-marker-icon, .chaos-scene .leaflet-tile, .chaos-stats .stat-box, .chaos-event";
+/// 入力遅延の目標（P95, ms）
+const LATENCY_TARGET_MS: f64 = 200.0;
+
+/// 合成入力の送り先。マップには全操作、ズームボタンと凡例にはクリックだけを送る
+fn fuzz_targets() -> Vec<FuzzTarget> {
+    vec![
+        FuzzTarget { name: "map", selector: ".chaos-scene .leaflet-container", weight: 8.0, kinds: &FuzzKind::ALL },
+        FuzzTarget { name: "zoom-control", selector: ".chaos-scene .leaflet-control-zoom a", weight: 1.5, kinds: &[FuzzKind::Click] },
+        FuzzTarget { name: "legend", selector: ".chaos-legend summary", weight: 0.5, kinds: &[FuzzKind::Click] },
+    ]
+}
+
+/// 入力処理を（入力破壊中なら遅らせて）実行し、次フレームまでの遅延を `tag` 付きで記録する
+///
+/// 遅延は処理を始めた時点から測り、注入した遅延は `injected_delay` に別に記録する。
+fn handle_input(
+    delay_ms: f64,
+    tag: LatencyTag,
+    latency_measurer: Signal<InputLatencyMeasurer>,
+    mut input_latency: Signal<f64>,
+    mut injected_delay: Signal<LogHistogram>,
+    recorder: Rc<RefCell<ChaosRunRecorder>>,
+    action: impl FnOnce() + 'static,
+) {
+    let requested_at = now_ms();
+    let run = move || {
+        let start_time = now_ms();
+        if delay_ms > 0.0 {
+            injected_delay.write().record(start_time - requested_at);
+        }
+        action();
+
+        // requestAnimationFrameを使用して次のフレームで測定完了（呼ばれた時点でクロージャは解放される）
+        let closure = wasm_bindgen::closure::Closure::once_into_js(move || {
+            live_handles::release(HandleKind::PendingClosure);
+            let latency = now_ms() - start_time;
+            latency_measurer.with(|m| m.add_tagged_measurement(&tag, latency));
+            recorder.borrow_mut().record_latency(start_time, latency);
+            let stats = latency_measurer.with(|m| m.get_stats());
+            input_latency.set(stats.p95);
+        });
+        window()
+            .unwrap()
+            .request_animation_frame(closure.unchecked_ref())
+            .expect("Failed to request animation frame");
+        live_handles::acquire(HandleKind::PendingClosure);
+    };
+
+    if delay_ms <= 0.0 {
+        run();
+        return;
+    }
+    let closure = wasm_bindgen::closure::Closure::once_into_js(move || {
+        live_handles::release(HandleKind::PendingClosure);
+        run();
+    });
+    let _ = window()
+        .unwrap()
+        .set_timeout_with_callback_and_timeout_and_arguments_0(closure.unchecked_ref(), delay_ms as i32);
+    live_handles::acquire(HandleKind::PendingClosure);
 }
 
 impl ChaosEvent {
+    /// レポートで集計する種別名
+    fn kind(&self) -> &'static str {
+        match self {
+            ChaosEvent::UIGlitch { .. } => UI_EVENT_KINDS[0],
+            ChaosEvent::InputCorruption { .. } => UI_EVENT_KINDS[1],
+            ChaosEvent::VisualDistortion { .. } => UI_EVENT_KINDS[2],
+            ChaosEvent::TimeWarp { .. } => UI_EVENT_KINDS[3],
+        }
+    }
+
     #[inline]
     fn get_display_string(&self) -> String {
         match self {
@@ -34,71 +126,168 @@ impl ChaosEvent {
 
 #[derive(Clone)]
 struct ChaosEngine {
-    intensity: u8,
+    intensity: ChaosIntensity,
     events: SmallVec<[ChaosEvent; 128]>,
     is_active: bool,
+    /// 発生率から積算した、まだ生成していないイベント数（端数）
+    pending: f64,
 }
 
 impl ChaosEngine {
-    const CHAOS_LEVEL_3_EVENT_COUNT: usize = 1000;
+    const EXTREME_TEST_EVENT_COUNT: usize = 1000;
     #[inline]
-    fn new(intensity: u8) -> Self {
+    fn new(intensity: ChaosIntensity) -> Self {
         Self {
             intensity,
             events: SmallVec::new(),
             is_active: false,
+            pending: 0.0,
         }
     }
     
+    /// `elapsed_ms` の間に強度の発生率で生じるイベント数
+    fn events_due(&mut self, elapsed_ms: f64) -> usize {
+        self.pending += self.intensity.profile().events_per_second * elapsed_ms / 1000.0;
+        let due = self.pending.floor();
+        self.pending -= due;
+        due as usize
+    }
+    
+    /// 強度の構成比と深刻度でイベントを生成して履歴に追加し、生成したものを返す
     #[inline]
-    fn spawn_batch_events(&mut self, count: usize) {
+    fn spawn_batch_events(&mut self, count: usize) -> SmallVec<[ChaosEvent; 16]> {
         // バッチ処理で複数イベントを一度に生成
         let mut batch: SmallVec<[ChaosEvent; 16]> = SmallVec::new();
+        let profile = self.intensity.profile();
         
         for _ in 0..count {
-            let event = match (js_sys::Math::random() * 4.0) as u32 {
+            let event = match profile.pick_ui_event(js_sys::Math::random()) {
                 0 => ChaosEvent::UIGlitch {
                     element_id: format!("chaos-element-{}", (js_sys::Math::random() * 100.0) as u32),
-                    severity: js_sys::Math::random() as f32,
+                    severity: (js_sys::Math::random() * profile.severity) as f32,
                 },
                 1 => ChaosEvent::InputCorruption {
-                    delay_ms: (js_sys::Math::random() * 500.0) as u32,
+                    delay_ms: (js_sys::Math::random() * MAX_INPUT_DELAY_MS * profile.severity) as u32,
                     noise: js_sys::Math::random() as f32,
                 },
                 2 => ChaosEvent::VisualDistortion {
-                    distortion_type: "glitch".to_string(),
+                    distortion_type: Distortion::ALL[(js_sys::Math::random() * Distortion::ALL.len() as f64) as usize]
+                        .name()
+                        .to_string(),
                 },
                 _ => ChaosEvent::TimeWarp {
-                    speed_multiplier: 1.0 + js_sys::Math::random() as f32 * 2.0,
+                    speed_multiplier: 1.0 + (js_sys::Math::random() * 2.0 * profile.severity) as f32,
                 },
             };
             batch.push(event);
         }
         
         // 一度に全イベントを追加
-        self.events.extend(batch);
+        self.events.extend(batch.iter().cloned());
         
         // リングバッファのように古いイベントを削除
         if self.events.len() > 128 {
             self.events.drain(0..(self.events.len() - 128));
         }
+        batch
+    }
+}
+
+/// `/chaos/:intensity`。強度が0〜10の整数でなければエラーページを表示する
+#[component]
+pub fn ChaosRoute(intensity: String) -> Element {
+    match ChaosIntensity::parse(&intensity) {
+        Ok(intensity) => rsx! { ChaosScene { key: "{intensity}", intensity } },
+        Err(message) => rsx! {
+            div {
+                class: "chaos-container",
+                div {
+                    class: "chaos-header",
+                    Link { to: "/", "← ホームに戻る" }
+                    h2 { "カオスモード: 不正な強度" }
+                }
+                p { style: "color: #f44336;", "{message}" }
+                p {
+                    for level in ChaosIntensity::all() {
+                        Link {
+                            key: "{level}",
+                            to: format!("/chaos/{}", level),
+                            style: "margin-right: 8px;",
+                            "{level}"
+                        }
+                    }
+                }
+                ChaosIntensityLegend { current: None }
+            }
+        },
     }
 }
 
 #[component]
-pub fn ChaosRoute(intensity: u8) -> Element {
+fn ChaosScene(intensity: ChaosIntensity) -> Element {
     let mut chaos_engine = use_signal(|| ChaosEngine::new(intensity));
-    let mut fps = use_signal(|| 60.0);
+    // 実フレーム時間によるFPS・落ちたフレーム数・メモリ
+    let mut performance = use_performance_monitor();
     let input_latency = use_signal(|| 0.0);
-    let latency_measurer = use_signal(|| InputLatencyMeasurer::new());
+    let latency_measurer = use_signal(InputLatencyMeasurer::new);
+    // 入力破壊で注入した遅延（入力遅延とは別に集計する）
+    let mut injected_delay = use_signal(LogHistogram::default);
+    // 合成入力による負荷下の遅延判定
+    let mut fuzzer = use_input_fuzzer();
+    let mut fuzz_config = use_signal(FuzzConfig::default);
+    let mut fuzz_result = use_signal(|| None::<LatencyStats>);
+    // Event Timing APIによる測定（有効な間だけ購読する）
+    let mut event_timing = use_signal(|| None::<Rc<EventTimingMonitor>>);
+    let mut event_timing_error = use_signal(|| None::<String>);
+    let mut interactions = use_signal(InteractionTracker::new);
+    let mut effects = use_signal(|| ChaosEffectState::new(EffectConfig::default()));
+    // 効果のDOM反映。ドロップ時にグリッチと時間倍率を元に戻す
+    let executor = use_hook(|| Rc::new(RefCell::new(ChaosEffectExecutor::new(GLITCH_TARGETS))));
+    // 実行中のイベント・フレーム時間・入力遅延を記録し、停止時にレポートにする
+    let recorder = use_hook(|| Rc::new(RefCell::new(ChaosRunRecorder::new())));
+    let mut run_report = use_signal(|| None::<ChaosRunReport>);
+    use_hook({
+        let recorder = recorder.clone();
+        move || {
+            Rc::new(FrameLoop::start(move |frame_ms, timestamp| {
+                if chaos_engine.try_peek().is_ok_and(|e| e.is_active) {
+                    recorder.borrow_mut().record_frame(frame_ms, timestamp);
+                }
+            }))
+        }
+    });
+    
+    // マップへの実際のドラッグ・ホイール・キー入力の遅延（合成入力は入力ファジングが記録する）。
+    // 入力破壊中はLeafletのハンドラに届く入力も遅らせる
+    use_hook({
+        let recorder = recorder.clone();
+        move || {
+            let input_delay = move || effects.try_peek().map(|state| state.input_delay_ms(js_sys::Math::random())).unwrap_or(0.0);
+            Rc::new(MapInputMonitor::with_input_delay(".chaos-scene .leaflet-container", "map", input_delay, move |input| {
+                latency_measurer.peek().add_tagged_measurement(&input.tag, input.latency_ms);
+                recorder.borrow_mut().record_latency(input.start_ms, input.latency_ms);
+                if input.injected_delay_ms > 0.0 {
+                    let mut injected_delay = injected_delay;
+                    injected_delay.write().record(input.injected_delay_ms);
+                }
+                let mut input_latency = input_latency;
+                input_latency.set(latency_measurer.peek().get_stats().p95);
+            }))
+        }
+    });
     
     // Intervalインスタンスを外部に保持
     let mut interval_instance = use_signal(|| None::<crate::utils::interval::Interval>);
     
+    let tick_executor = executor.clone();
+    let tick_recorder = recorder.clone();
     use_effect(move || {
         use crate::utils::interval::Interval;
         
-        let interval = Interval::new(16, move || {
+        let executor = tick_executor.clone();
+        let recorder = tick_recorder.clone();
+        let interval = Interval::new(TICK_MS, move || {
+            profile_scope!("chaos_tick");
             // コンポーネントがアンマウントされているかチェック
             let engine_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                 chaos_engine().is_active
@@ -106,31 +295,35 @@ pub fn ChaosRoute(intensity: u8) -> Element {
             
             match engine_result {
                 Ok(is_active) => {
+                    // 時間倍率の変化をマップへ反映
+                    executor.borrow_mut().sync(&effects.peek());
                     if is_active {
                         // エンジンの更新
                         let update_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                            chaos_engine.with_mut(|engine| {
-                                engine.spawn_batch_events(engine.intensity as usize);
+                            let spawned = chaos_engine.with_mut(|engine| {
+                                let count = engine.events_due(TICK_MS as f64);
+                                engine.spawn_batch_events(count)
+                            });
+                            
+                            // 生成したイベントをシーンへ適用し、期限切れの効果を外す
+                            let now = now_ms();
+                            let executor = executor.borrow();
+                            let mut recorder = recorder.borrow_mut();
+                            for event in &spawned {
+                                recorder.record_event(now, event.kind(), &event.get_display_string());
+                            }
+                            effects.with_mut(|state| {
+                                for event in &spawned {
+                                    if let Some(command) = state.apply(event, now) {
+                                        executor.glitch(&command);
+                                    }
+                                }
+                                state.expire(now);
                             });
                         }));
                         
                         if update_result.is_err() {
                             web_sys::console::log_1(&"Chaos engine update failed, component may be unmounted".into());
-                            return;
-                        }
-                        
-                        // FPS測定
-                        let fps_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                            if let Some(window) = window() {
-                                if let Some(performance) = window.performance() {
-                                    let _now = performance.now();
-                                    fps.set(1000.0 / 16.0);
-                                }
-                            }
-                        }));
-                        
-                        if fps_result.is_err() {
-                            web_sys::console::log_1(&"FPS update failed, component may be unmounted".into());
                         }
                     }
                 }
@@ -143,20 +336,51 @@ pub fn ChaosRoute(intensity: u8) -> Element {
         interval_instance.set(Some(interval));
     });
     
-    // カオス効果の適用
-    let chaos_style = if chaos_engine().is_active {
-        format!(
-            "animation: chaos-glitch 0.1s infinite; filter: hue-rotate({}deg);",
-            (js_sys::Math::random() * 360.0) as u32
-        )
-    } else {
-        "".to_string()
-    };
+    // 過去のセッションを含む累計（ルートを離れる時に今回の分を足す）
+    let cumulative = use_hook(|| input_latency::load_cumulative().map(|h| LatencyStats::from_histogram(&h)));
+    
+    // ルートを離れる時点の入力遅延統計をテレメトリに記録
+    let telemetry = use_context::<Telemetry>();
+    use_drop(move || {
+        // 強度はボタンで変わるため、URLではなくエンジンの現在の強度で記録する
+        let intensity = chaos_engine.try_peek().map(|engine| engine.intensity).unwrap_or(intensity);
+        if let Ok(measurer) = latency_measurer.try_peek() {
+            let stats = measurer.get_stats();
+            if stats.count > 0 {
+                measurer.save_to_cumulative();
+                telemetry.report(TelemetryPayload::Latency {
+                    component: format!("chaos/{}", intensity),
+                    stats,
+                    histogram: Some(measurer.histogram()),
+                });
+            }
+            for tagged in measurer.get_tagged_stats() {
+                telemetry.report(TelemetryPayload::Latency {
+                    component: format!("chaos/{}/{}", intensity, tagged.tag),
+                    stats: tagged.stats,
+                    histogram: None,
+                });
+            }
+        }
+        if let Ok(tracker) = interactions.try_peek() {
+            for report in tracker.by_component() {
+                telemetry.report(TelemetryPayload::Latency {
+                    component: format!("chaos/{}/event-timing/{}", intensity, report.component),
+                    stats: report.total,
+                    histogram: None,
+                });
+            }
+        }
+    });
+    
+    // 描画の歪曲はシーンのコンテナに適用する
+    let scene_style = effects.read().distortion().map(|d| d.css()).unwrap_or("");
+    let input_delay = effects.read().input_delay_ms(0.5);
+    let report = performance.report.read().clone();
     
     rsx! {
         div {
-            class: "chaos-container",
-            style: "{chaos_style}",
+            class: if chaos_engine().is_active { "chaos-container chaos-active" } else { "chaos-container" },
             
             div {
                 class: "chaos-header",
@@ -164,7 +388,7 @@ pub fn ChaosRoute(intensity: u8) -> Element {
                     to: "/",
                     "← ホームに戻る"
                 }
-                h2 { "カオスモード - レベル {intensity}" }
+                h2 { "カオスモード - レベル {chaos_engine().intensity}" }
             }
             
             div {
@@ -173,17 +397,44 @@ pub fn ChaosRoute(intensity: u8) -> Element {
                     class: "stat-box",
                     h3 { "FPS" }
                     p { 
-                        style: if fps() < 30.0 { "color: red;" } else { "" },
-                        {format!("{:.0}", fps())}
+                        style: if report.fps_current < 30.0 { "color: red;" } else { "" },
+                        {format!("{:.0}", report.fps_current)}
                     }
+                    small { {format!("平均 {:.1}", report.fps_average)} }
+                }
+                div {
+                    class: "stat-box",
+                    h3 { "落ちたフレーム" }
+                    p { {format!("{}", report.dropped_frames)} }
+                    small { {format!("P95 {:.1}ms", report.frame_stats.p95_ms)} }
+                }
+                div {
+                    class: "stat-box",
+                    h3 { "メモリ" }
+                    p { {format!("{:.1}MB", report.memory_usage)} }
+                    small { {format!("最大 {:.1}MB", report.peak_memory_mb)} }
                 }
                 div {
                     class: "stat-box",
                     h3 { "入力遅延 (P95)" }
                     p { 
-                        style: if input_latency() > 200.0 { "color: red;" } else { "" },
+                        style: if input_latency() > LATENCY_TARGET_MS { "color: red;" } else { "" },
                         {format!("{:.0}ms", input_latency())} 
                     }
+                    if injected_delay.read().count() > 0 {
+                        small { {format!("注入した遅延 P95 {:.0}ms", injected_delay.read().percentile(0.95))} }
+                    }
+                }
+                if event_timing.read().is_some() {
+                    div {
+                        class: "stat-box",
+                        h3 { "INP" }
+                        p {
+                            style: if interactions.read().inp() > LATENCY_TARGET_MS { "color: red;" } else { "" },
+                            {format!("{:.0}ms", interactions.read().inp())}
+                        }
+                        small { {format!("16ms以上 P95 {:.0}ms", interactions.read().overall().p95)} }
+                    }
                 }
                 div {
                     class: "stat-box",
@@ -192,80 +443,74 @@ pub fn ChaosRoute(intensity: u8) -> Element {
                 }
             }
             
+            div {
+                class: "chaos-effects",
+                {format!(
+                    "グリッチ中: {}要素 / 入力遅延: {:.0}ms / 歪曲: {} / 時間倍率: x{:.2}",
+                    effects.read().active_glitches(),
+                    input_delay,
+                    effects.read().distortion().map(|d| d.name()).unwrap_or("なし"),
+                    effects.read().time_scale(),
+                )}
+            }
+            
+            div {
+                class: "chaos-scene",
+                "data-latency-component": "map",
+                style: "{scene_style}",
+                Map { object_count: SCENE_OBJECT_COUNT, seed: None }
+            }
+            
             div {
                 class: "chaos-controls",
+                "data-latency-component": "controls",
                 button {
                     class: "chaos-button",
-                    onclick: move |_| {
-                        // クリック時点で測定を開始
-                        let start_time = window().unwrap().performance().unwrap().now();
-                        
-                        // 実際の処理
-                        chaos_engine.with_mut(|engine| {
-                            engine.is_active = !engine.is_active;
-                        });
-                        
-                        // requestAnimationFrameを使用して次のフレームで測定完了
-                        let window = window().unwrap();
-                        let performance = window.performance().unwrap();
-                        let latency_measurer_clone = latency_measurer.clone();
-                        let mut input_latency_clone = input_latency.clone();
-                        
-                        let closure = wasm_bindgen::closure::Closure::once(move || {
-                            let end_time = performance.now();
-                            let latency = end_time - start_time;
-                            
-                            // 測定値を直接記録
-                            latency_measurer_clone.with(|m| {
-                                m.add_measurement(latency);
+                    onclick: {
+                        let executor = executor.clone();
+                        let recorder = recorder.clone();
+                        move |_| {
+                            let delay = effects.peek().input_delay_ms(js_sys::Math::random());
+                            let executor = executor.clone();
+                            let action_recorder = recorder.clone();
+                            let tag = LatencyTag::new(InteractionKind::Click, "start-button");
+                            handle_input(delay, tag, latency_measurer, input_latency, injected_delay, recorder.clone(), move || {
+                                chaos_engine.with_mut(|engine| {
+                                    engine.is_active = !engine.is_active;
+                                });
+                                // 開始時から計測し直す
+                                if chaos_engine.peek().is_active {
+                                    performance.reset();
+                                    injected_delay.set(LogHistogram::default());
+                                    action_recorder.borrow_mut().start(now_ms());
+                                    run_report.set(None);
+                                }
+                                // 停止したら効果を残さず、記録をレポートにする
+                                if !chaos_engine.peek().is_active {
+                                    effects.with_mut(|state| state.clear());
+                                    executor.borrow_mut().reset();
+                                    let title = format!("カオスモード レベル{}", chaos_engine.peek().intensity);
+                                    run_report.set(Some(action_recorder.borrow().build_report(&title, &ReportConfig::default())));
+                                }
                             });
-                            
-                            // 統計を更新
-                            let stats = latency_measurer_clone.with(|m| m.get_stats());
-                            input_latency_clone.set(stats.p95);
-                        });
-                        
-                        window.request_animation_frame(closure.as_ref().unchecked_ref())
-                            .expect("Failed to request animation frame");
-                        closure.forget();
+                        }
                     },
                     if chaos_engine().is_active { "カオス停止" } else { "カオス開始" }
                 }
                 
                 button {
                     class: "chaos-button",
-                    onclick: move |_| {
-                        // クリック時点で測定を開始
-                        let start_time = window().unwrap().performance().unwrap().now();
-                        
-                        // 実際の処理
-                        chaos_engine.with_mut(|engine| {
-                            engine.intensity = (engine.intensity % 3) + 1;
-                        });
-                        
-                        // requestAnimationFrameを使用して次のフレームで測定完了
-                        let window = window().unwrap();
-                        let performance = window.performance().unwrap();
-                        let latency_measurer_clone = latency_measurer.clone();
-                        let mut input_latency_clone = input_latency.clone();
-                        
-                        let closure = wasm_bindgen::closure::Closure::once(move || {
-                            let end_time = performance.now();
-                            let latency = end_time - start_time;
-                            
-                            // 測定値を直接記録
-                            latency_measurer_clone.with(|m| {
-                                m.add_measurement(latency);
+                    onclick: {
+                        let recorder = recorder.clone();
+                        move |_| {
+                            let delay = effects.peek().input_delay_ms(js_sys::Math::random());
+                            let tag = LatencyTag::new(InteractionKind::Click, "intensity-button");
+                            handle_input(delay, tag, latency_measurer, input_latency, injected_delay, recorder.clone(), move || {
+                                chaos_engine.with_mut(|engine| {
+                                    engine.intensity = engine.intensity.next();
+                                });
                             });
-                            
-                            // 統計を更新
-                            let stats = latency_measurer_clone.with(|m| m.get_stats());
-                            input_latency_clone.set(stats.p95);
-                        });
-                        
-                        window.request_animation_frame(closure.as_ref().unchecked_ref())
-                            .expect("Failed to request animation frame");
-                        closure.forget();
+                        }
                     },
                     {format!("強度変更 (現在: {})", chaos_engine().intensity)}
                 }
@@ -273,27 +518,154 @@ pub fn ChaosRoute(intensity: u8) -> Element {
                 button {
                     class: "chaos-button",
                     style: "background-color: #ff4444;",
-                    onclick: move |_| {
-                        // カオスレベル3の極限テスト
-                        web_sys::console::log_1(&"Starting Chaos Level 3 extreme test...".into());
+                    onclick: {
+                        let extreme_executor = executor.clone();
+                        let extreme_recorder = recorder.clone();
+                        move |_| {
+                            // 最大強度の極限テスト
+                            web_sys::console::log_1(&"Starting chaos extreme test...".into());
                         
-                        // エンジンをレベル3に設定してアクティブ化
-                        chaos_engine.with_mut(|engine| {
-                            engine.intensity = 3;
-                            engine.is_active = true;
-                            // 1000個のイベントを一気に生成
-                            engine.spawn_batch_events(ChaosEngine::CHAOS_LEVEL_3_EVENT_COUNT);
-                        });
+                            // 停止中からなら記録を開始する
+                            if !chaos_engine.peek().is_active {
+                                extreme_recorder.borrow_mut().start(now_ms());
+                                run_report.set(None);
+                            }
                         
-                        // 遅延測定をリセット
-                        latency_measurer.with(|m| m.reset());
+                            // エンジンを最大強度に設定してアクティブ化
+                            let spawned = chaos_engine.with_mut(|engine| {
+                                engine.intensity = ChaosIntensity::saturating(ChaosIntensity::MAX);
+                                engine.is_active = true;
+                                // 1000個のイベントを一気に生成
+                                engine.spawn_batch_events(ChaosEngine::EXTREME_TEST_EVENT_COUNT)
+                            });
                         
-                        web_sys::console::log_1(&format!("Chaos Level 3 test started with {} events", ChaosEngine::CHAOS_LEVEL_3_EVENT_COUNT).into());
+                            // まとめてシーンへ適用（グリッチは同時適用数の上限まで）
+                            let now = now_ms();
+                            let mut recorder = extreme_recorder.borrow_mut();
+                            for event in &spawned {
+                                recorder.record_event(now, event.kind(), &event.get_display_string());
+                            }
+                            effects.with_mut(|state| {
+                                for event in &spawned {
+                                    if let Some(command) = state.apply(event, now) {
+                                        extreme_executor.borrow().glitch(&command);
+                                    }
+                                }
+                            });
+                        
+                            // 遅延測定とパフォーマンス計測をリセット
+                            latency_measurer.with(|m| m.reset());
+                            injected_delay.set(LogHistogram::default());
+                            performance.reset();
+                        
+                            web_sys::console::log_1(&format!("Chaos extreme test started with {} events", ChaosEngine::EXTREME_TEST_EVENT_COUNT).into());
+                        }
                     },
-                    "🔥 カオスレベル3極限テスト"
+                    "🔥 極限テスト（強度10で1000イベント）"
                 }
             }
             
+            div {
+                class: "chaos-fuzzer",
+                "data-latency-component": "fuzzer",
+                h3 { "入力ファジング" }
+                p {
+                    style: "font-size: 0.8rem; color: #aaa;",
+                    {format!(
+                        "マップとズームボタンへ{}の入力を送り、各入力から次フレームまでを入力遅延として記録します。カオス実行中に使うと負荷下の判定になります。",
+                        FuzzKind::ALL.iter().map(|k| k.label()).collect::<Vec<_>>().join("・")
+                    )}
+                }
+                div {
+                    class: "control-group",
+                    label { "発生率 (件/秒)" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        max: "200",
+                        style: "width: 5em;",
+                        value: "{fuzz_config().events_per_second}",
+                        disabled: (fuzzer.running)(),
+                        onchange: move |evt| {
+                            if let Ok(v) = evt.value().parse::<f64>() {
+                                fuzz_config.write().events_per_second = v.clamp(1.0, 200.0);
+                            }
+                        },
+                    }
+                    label { " 時間 (秒)" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        style: "width: 5em;",
+                        value: "{fuzz_config().duration_ms / 1000.0}",
+                        disabled: (fuzzer.running)(),
+                        onchange: move |evt| {
+                            if let Ok(v) = evt.value().parse::<f64>() {
+                                fuzz_config.write().duration_ms = v.max(1.0) * 1000.0;
+                            }
+                        },
+                    }
+                    button {
+                        class: "chaos-button",
+                        onclick: {
+                            let recorder = recorder.clone();
+                            move |_| {
+                                if (fuzzer.running)() {
+                                    fuzzer.stop();
+                                    return;
+                                }
+                                fuzz_result.set(None);
+                                let recorder = recorder.clone();
+                                let config = FuzzConfig { seed: js_sys::Date::now() as u64, ..fuzz_config() };
+                                fuzzer.start(
+                                    config,
+                                    fuzz_targets(),
+                                    move |tag, latency| {
+                                        // ルートの統計にも含める（ファジングの判定は `fuzzer.latency` だけで行う）
+                                        latency_measurer.peek().add_tagged_measurement(tag, latency);
+                                        recorder.borrow_mut().record_latency(now_ms() - latency, latency);
+                                        let mut input_latency = input_latency;
+                                        input_latency.set(latency_measurer.with(|m| m.get_stats()).p95);
+                                    },
+                                    move || {
+                                        let stats = fuzzer.latency.with(|m| m.get_stats());
+                                        web_sys::console::log_1(&format!(
+                                            "Input fuzz finished: n={} p95={:.1}ms ({})",
+                                            stats.count,
+                                            stats.p95,
+                                            if stats.meets_target(LATENCY_TARGET_MS) { "pass" } else { "fail" }
+                                        ).into());
+                                        fuzz_result.set(Some(stats));
+                                    },
+                                );
+                            }
+                        },
+                        if (fuzzer.running)() { "ファジング停止" } else { "ファジング開始" }
+                    }
+                }
+                if let Some(message) = (fuzzer.error)() {
+                    p { style: "color: #f44336;", "{message}" }
+                }
+                if (fuzzer.running)() {
+                    p { {format!("送信 {}件 / 測定 {}件", (fuzzer.dispatched)(), fuzzer.latency.with(|m| m.get_stats().count))} }
+                }
+                if let Some(stats) = fuzz_result() {
+                    p {
+                        style: if stats.meets_target(LATENCY_TARGET_MS) { "color: #0f0;" } else { "color: #f00;" },
+                        {format!(
+                            "{} 負荷下の入力遅延: P95 {:.0}ms（目標 {:.0}ms, n={}, 送信 {}件）",
+                            if stats.meets_target(LATENCY_TARGET_MS) { "✅" } else { "❌" },
+                            stats.p95,
+                            LATENCY_TARGET_MS,
+                            stats.count,
+                            (fuzzer.dispatched)()
+                        )}
+                    }
+                }
+            }
+            
+            ChaosIntensityLegend { current: Some(chaos_engine().intensity) }
+            
             div {
                 class: "chaos-visualization",
                 h3 { "カオスイベント" }
@@ -309,15 +681,67 @@ pub fn ChaosRoute(intensity: u8) -> Element {
                 }
             }
             
+            if !chaos_engine.read().is_active {
+                ChaosReportView { report: run_report, file_prefix: format!("chaos-level{}", chaos_engine.read().intensity) }
+            }
+            
             div {
                 class: "latency-report",
                 style: "margin-top: 20px; padding: 10px; background: #333; border-radius: 8px;",
                 h3 { "入力遅延レポート" }
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: event_timing.read().is_some(),
+                        onchange: move |evt: FormEvent| {
+                            if !evt.checked() {
+                                event_timing.set(None);
+                                return;
+                            }
+                            interactions.write().reset();
+                            match EventTimingMonitor::start(move |entry| interactions.write().record(&entry)) {
+                                Ok(monitor) => {
+                                    event_timing_error.set(None);
+                                    event_timing.set(Some(Rc::new(monitor)));
+                                }
+                                Err(e) => event_timing_error.set(Some(e)),
+                            }
+                        },
+                    }
+                    " Event Timing APIで測定（入力遅延・処理・表示遅延とINP）"
+                }
+                if let Some(message) = event_timing_error() {
+                    p { style: "color: #f44336;", "{message}" }
+                }
+                if event_timing.read().is_some() {
+                    EventTimingReport { tracker: interactions(), target_ms: LATENCY_TARGET_MS }
+                }
+                h4 { "手動測定（ハンドラ内から次フレームまで）" }
                 pre {
                     style: "font-family: monospace; color: #0f0;",
                     {latency_measurer.with(|m| m.get_stats().format_report())}
                 }
-                if latency_measurer.with(|m| m.get_stats().meets_target(200.0)) {
+                if let Some(total) = cumulative.filter(|s| s.count > 0) {
+                    p {
+                        style: "font-size: 0.8rem; color: #aaa;",
+                        {format!(
+                            "過去のセッションの累計: n={} / P50 {:.1}ms / P95 {:.1}ms / P99 {:.1}ms",
+                            total.count,
+                            total.p50,
+                            total.p95,
+                            total.p99
+                        )}
+                    }
+                }
+                LatencyBreakdown {
+                    stats: latency_measurer.with(|m| m.get_tagged_stats()),
+                    on_target_change: move |(tag, target_ms)| {
+                        // 書き込みとして扱い、判定を再描画する
+                        let mut latency_measurer = latency_measurer;
+                        latency_measurer.write().set_target(tag, target_ms);
+                    },
+                }
+                if latency_measurer.with(|m| m.get_stats().meets_target(LATENCY_TARGET_MS)) {
                     p { 
                         style: "color: #0f0;", 
                         "✅ 目標達成: 95パーセンタイル < 200ms" 
@@ -1,75 +1,30 @@
 use dioxus::prelude::*;
-use crate::utils::performance_metrics::getLoadMetrics;
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct LoadMetrics {
-    total_load_time: f64,
-    dom_load_time: Option<f64>,
-    wasm_init_time: Option<f64>,
-}
-
-// 非同期でロードメトリクスを取得
-async fn fetch_load_metrics() -> Result<LoadMetrics, String> {
-    // JavaScriptの結果を非同期で取得
-    let metrics_js = getLoadMetrics();
-    
-    if metrics_js.is_null() || metrics_js.is_undefined() {
-        return Err("No metrics available yet".to_string());
-    }
-    
-    // JavaScriptオブジェクトから情報を抽出
-    let total_time = js_sys::Reflect::get(&metrics_js, &"total_load_time".into())
-        .ok()
-        .and_then(|v| v.as_f64())
-        .ok_or("Failed to get total_load_time")?;
-    
-    let dom_time = js_sys::Reflect::get(&metrics_js, &"dom_load_time".into())
-        .ok()
-        .and_then(|v| v.as_f64());
-        
-    let wasm_time = js_sys::Reflect::get(&metrics_js, &"wasm_init_time".into())
-        .ok()
-        .and_then(|v| v.as_f64());
-    
-    Ok(LoadMetrics {
-        total_load_time: total_time,
-        dom_load_time: dom_time,
-        wasm_init_time: wasm_time,
-    })
-}
+use crate::components::sparkline::Sparkline;
+use crate::utils::performance_metrics::{history_with, load_history, LoadMetrics};
+use crate::utils::telemetry::Telemetry;
+use std::rc::Rc;
 
 #[component]
 pub fn Home() -> Element {
     let mut show_metrics = use_signal(|| false);
     
-    // use_resourceを使用して非同期でメトリクスを取得（値を返すため）
-    let metrics_resource = use_resource(move || async move {
-        fetch_load_metrics().await
-    });
+    // mainで取得し、Web Vitalsの到着ごとに更新されるロードメトリクス
+    let load_metrics = use_context::<Signal<Option<LoadMetrics>>>();
+    
+    // 過去のロード履歴（保存はページ非表示時なので、読み込みは1回だけにして今回の値を重ねる）
+    let stored_history = use_hook(|| Rc::new(load_history()));
+    let history = use_memo(move || history_with(&stored_history, load_metrics.read().as_ref()));
     
     // メトリクス表示テキストをメモ化
     let metrics_display = use_memo(move || {
-        match &*metrics_resource.read() {
-            Some(Ok(metrics)) => {
-                let mut display = format!(
-                    "Initial Load Time: {:.0}ms (Target: <3000ms) {}",
-                    metrics.total_load_time,
-                    if metrics.total_load_time < 3000.0 { "✅" } else { "❌" }
-                );
-                
-                if let Some(dom_time) = metrics.dom_load_time {
-                    display.push_str(&format!("\nDOM Load: {:.0}ms", dom_time));
-                }
-                
-                if let Some(wasm_time) = metrics.wasm_init_time {
-                    display.push_str(&format!("\nWASM Init: {:.0}ms", wasm_time));
-                }
-                
-                display
-            }
-            Some(Err(_)) => String::new(),
-            None => "Loading metrics...".to_string(),
+        match &*load_metrics.read() {
+            Some(metrics) => format!(
+                "Initial Load Time: {:.0}ms (Target: <3000ms) {}\n\n{}",
+                metrics.total_load_time,
+                if metrics.total_load_time < 3000.0 { "✅" } else { "❌" },
+                metrics.get_formatted_report()
+            ),
+            None => String::new(),
         }
     });
     
@@ -118,45 +73,122 @@ pub fn Home() -> Element {
                 }
                 
                 // パフォーマンスメトリクス表示
-                match &*metrics_resource.read() {
-                    Some(Ok(_)) => rsx! {
-                        div {
-                            class: "mt-8 p-4 bg-gray-800 rounded-lg",
-                            button {
-                                class: "text-sm text-gray-400 hover:text-white transition-colors",
-                                onclick: move |_| show_metrics.set(!show_metrics()),
-                                {format!("Performance Metrics {}", if show_metrics() { "[-]" } else { "[+]" })}
-                            }
-                            
-                            if show_metrics() {
-                                div {
-                                    class: "mt-4 text-left text-sm",
-                                    pre {
-                                        class: "text-green-400 font-mono whitespace-pre-wrap",
-                                        {metrics_display()}
-                                    }
-                                    p {
-                                        class: "text-gray-500 mt-2",
-                                        "Note: Actual 4G network will add communication latency on top of this time"
-                                    }
+                if load_metrics.read().is_some() {
+                    div {
+                        class: "mt-8 p-4 bg-gray-800 rounded-lg",
+                        button {
+                            class: "text-sm text-gray-400 hover:text-white transition-colors",
+                            onclick: move |_| show_metrics.set(!show_metrics()),
+                            {format!("Performance Metrics {}", if show_metrics() { "[-]" } else { "[+]" })}
+                        }
+                        
+                        if show_metrics() {
+                            div {
+                                class: "mt-4 text-left text-sm",
+                                pre {
+                                    class: "text-green-400 font-mono whitespace-pre-wrap",
+                                    {metrics_display()}
+                                }
+                                p {
+                                    class: "text-gray-500 mt-2",
+                                    "Note: Actual 4G network will add communication latency on top of this time"
                                 }
+                                LoadHistoryChart { history: history() }
                             }
                         }
-                    },
-                    Some(Err(e)) => rsx! {
-                        div {
-                            class: "mt-8 p-4 bg-red-900 rounded-lg text-red-300",
-                            "Failed to load metrics: {e}"
-                        }
-                    },
-                    None => rsx! {
-                        div {
-                            class: "mt-8 p-4 bg-gray-800 rounded-lg text-gray-400",
-                            "Loading performance metrics..."
-                        }
+                    }
+                } else {
+                    div {
+                        class: "mt-8 p-4 bg-gray-800 rounded-lg text-gray-400",
+                        "No metrics available yet"
+                    }
+                }
+                
+                TelemetrySettings {}
+            }
+        }
+    }
+}
+
+/// 過去のロード時間の推移
+#[component]
+fn LoadHistoryChart(history: Vec<LoadMetrics>) -> Element {
+    if history.len() < 2 {
+        return rsx! {};
+    }
+    
+    let total: Vec<f64> = history.iter().map(|m| m.total_load_time).collect();
+    let wasm_init: Vec<f64> = history.iter().map(|m| m.wasm_init_time()).collect();
+    let lcp: Vec<f64> = history.iter().filter_map(|m| m.largest_contentful_paint).collect();
+    
+    rsx! {
+        div {
+            class: "mt-4 text-gray-900",
+            p {
+                class: "text-gray-400",
+                {format!("直近{}回のロード推移", history.len())}
+            }
+            Sparkline { label: "Total Load", values: total, color: "#4CAF50", unit: "ms" }
+            Sparkline { label: "WASM Init", values: wasm_init, color: "#2196F3", unit: "ms" }
+            if lcp.len() >= 2 {
+                Sparkline { label: "LCP", values: lcp, color: "#FF9800", unit: "ms" }
+            }
+        }
+    }
+}
+
+/// テレメトリ送信のオプトイン設定
+#[component]
+fn TelemetrySettings() -> Element {
+    let telemetry = use_context::<Telemetry>();
+    let mut config = use_signal(|| telemetry.config());
+    let mut endpoint_draft = use_signal(|| config.peek().endpoint.clone());
+    
+    let toggle_telemetry = telemetry.clone();
+    let save_telemetry = telemetry.clone();
+    
+    rsx! {
+        div {
+            class: "mt-4 p-4 bg-gray-800 rounded-lg text-left text-sm text-gray-300",
+            label {
+                class: "flex items-center gap-2",
+                input {
+                    r#type: "checkbox",
+                    checked: config().enabled,
+                    onchange: move |evt| {
+                        let mut updated = config();
+                        updated.enabled = evt.checked();
+                        toggle_telemetry.set_config(updated.clone());
+                        config.set(updated);
                     }
                 }
+                "計測データを送信する（FPS・ロード時間・入力遅延）"
+            }
+            if config().enabled {
+                div {
+                    class: "mt-2 flex gap-2",
+                    input {
+                        class: "flex-1 px-2 py-1 rounded bg-gray-700 text-white",
+                        r#type: "url",
+                        value: "{endpoint_draft}",
+                        oninput: move |evt| endpoint_draft.set(evt.value()),
+                    }
+                    button {
+                        class: "px-3 py-1 rounded bg-blue-600 hover:bg-blue-700",
+                        onclick: move |_| {
+                            let mut updated = config();
+                            updated.endpoint = endpoint_draft().trim().to_string();
+                            save_telemetry.set_config(updated.clone());
+                            config.set(updated);
+                        },
+                        "保存"
+                    }
+                }
+                p {
+                    class: "mt-2 text-gray-500",
+                    {format!("送信待ち: {}件", telemetry.pending())}
+                }
             }
         }
     }
-}
\ No newline at end of file
+}
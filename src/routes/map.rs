@@ -1,9 +1,19 @@
 use dioxus::prelude::*;
-use crate::components::{map::Map, webgl_map::WebGLMap, canvas_map::CanvasMap, benchmark::BenchmarkPanel};
-use crate::hooks::{use_map_config, RenderMode};
+use crate::components::{map::Map, webgl_map::WebGLMap, canvas_map::CanvasMap, benchmark::BenchmarkPanel, multi_run::MultiRunPanel};
+use crate::hooks::{use_adaptive_render, use_map_config, RenderMode};
+use crate::utils::adaptive_render::{AdaptiveDirection, DetailLevel, RenderStep};
+use crate::utils::long_tasks::{PhaseScope, PipelinePhase};
+use crate::utils::profiler::profile_scope;
+use crate::utils::input_latency::InputLatencyMeasurer;
+use crate::utils::map_input::MapInputMonitor;
+use std::rc::Rc;
 
 #[component]
 pub fn MapRoute(mode: String) -> Element {
+    // コンポーネント本体の実行時間を長時間タスクの原因推定に使う
+    let _render_scope = PhaseScope::new(PipelinePhase::Render);
+    profile_scope!("render:MapRoute");
+    
     // カスタムフックを使用してマップ設定を管理
     let (config, actions) = use_map_config();
     
@@ -26,22 +36,70 @@ pub fn MapRoute(mode: String) -> Element {
         }
     });
     
-    // URLパラメータからレンダーモードを設定
-    let mode_clone = mode.clone();
+    // 入力遅延の測定。スライダーは子コンポーネントが、マップへのドラッグ・ホイール・キー入力はここで記録する
+    let latency_measurer = use_context_provider(|| Signal::new(InputLatencyMeasurer::new()));
+    use_hook(move || {
+        Rc::new(MapInputMonitor::start(".map-content .leaflet-container", "map", move |input| {
+            latency_measurer.peek().add_tagged_measurement(&input.tag, input.latency_ms);
+        }))
+    });
+    
+    // URLパラメータのレンダーモード
+    let url_mode = match mode.as_str() {
+        "webgl" => RenderMode::WebGL,
+        "canvas" => RenderMode::Canvas,
+        _ => RenderMode::DOM,
+    };
+    
+    // 実測フレーム時間によるモード自動調整（有効時はURLのモードより優先）
+    let mut adaptive = use_adaptive_render(RenderStep { mode: url_mode.clone(), detail: DetailLevel::Full });
+    
+    // URLが変わった場合はURLのモードに戻す
+    let mut url_mode_signal = use_signal(|| url_mode.clone());
+    if *url_mode_signal.peek() != url_mode {
+        url_mode_signal.set(url_mode);
+    }
+    use_effect(move || {
+        let mode = url_mode_signal();
+        if adaptive.step.peek().mode != mode {
+            adaptive.reset_to(RenderStep { mode, detail: DetailLevel::Full });
+        }
+    });
+    
+    // 繰り返し計測中のシーンのシード（未指定ならランダム配置）
+    let scene_seed = use_signal(|| None::<u64>);
+    
+    // 現在の段階をマップ設定に反映
     use_effect(move || {
-        let new_mode = match mode_clone.as_str() {
-            "webgl" => RenderMode::WebGL,
-            "canvas" => RenderMode::Canvas,
-            _ => RenderMode::DOM,
-        };
-        (actions.set_render_mode)(new_mode);
+        (actions.set_render_mode)(adaptive.step.read().mode.clone());
     });
     
     // 推奨モードが現在のモードと異なる場合の警告
     let show_mode_suggestion = use_memo(move || {
-        config.read().render_mode != *actions.recommended_mode.read()
+        !(adaptive.enabled)() && config.read().render_mode != *actions.recommended_mode.read()
     });
     
+    let current_step = adaptive.step.read().clone();
+    let active_mode = current_step.mode.as_str().to_lowercase();
+    
+    // `chaos-testing` フィーチャー有効時のみ表示中のマップへカオスイベントを発生させる
+    #[cfg(feature = "chaos-testing")]
+    let chaos_panel = rsx! {
+        crate::components::map_chaos::MapChaosPanel {
+            object_count: object_count_signal,
+            render_mode: current_step.mode.clone(),
+            on_select_mode: move |mode| {
+                if *adaptive.enabled.peek() {
+                    adaptive.enabled.set(false);
+                }
+                adaptive.reset_to(RenderStep { mode, detail: DetailLevel::Full });
+            },
+        }
+        crate::components::tile_faults::TileFaultPanel {}
+    };
+    #[cfg(not(feature = "chaos-testing"))]
+    let chaos_panel = rsx! {};
+    
     rsx! {
         div {
             class: "map-container",
@@ -51,7 +109,23 @@ pub fn MapRoute(mode: String) -> Element {
                     to: "/",
                     "← ホームに戻る"
                 }
-                h2 { "マップモード: {mode}" }
+                h2 { "マップモード: {current_step.label()}" }
+                
+                label {
+                    class: "adaptive-toggle",
+                    input {
+                        r#type: "checkbox",
+                        checked: (adaptive.enabled)(),
+                        onchange: move |evt| {
+                            adaptive.enabled.set(evt.checked());
+                            // 自動調整を切ったらURLのモードに戻す
+                            if !evt.checked() {
+                                adaptive.reset_to(RenderStep { mode: url_mode_signal(), detail: DetailLevel::Full });
+                            }
+                        },
+                    }
+                    " フレーム時間に応じて自動調整"
+                }
                 
                 // パフォーマンスモード推奨表示
                 if show_mode_suggestion() {
@@ -72,15 +146,17 @@ pub fn MapRoute(mode: String) -> Element {
                 
                 div {
                     style: "flex: 1;",
-                    match mode.as_str() {
+                    match active_mode.as_str() {
                         "webgl" => rsx! {
                             WebGLMap {
-                                object_count: object_count_signal()
+                                object_count: object_count_signal(),
+                                seed: scene_seed(),
                             }
                         },
                         "canvas" => rsx! {
                             CanvasMap {
-                                object_count: object_count_signal()
+                                object_count: object_count_signal(),
+                                seed: scene_seed(),
                             }
                         },
                         _ => {
@@ -88,7 +164,8 @@ pub fn MapRoute(mode: String) -> Element {
                             web_sys::console::log_1(&format!("[MapRoute] Rendering Map with object_count: {}", count).into());
                             rsx! {
                                 Map {
-                                    object_count: count
+                                    object_count: count,
+                                    seed: scene_seed(),
                                 }
                             }
                         }
@@ -99,7 +176,46 @@ pub fn MapRoute(mode: String) -> Element {
                     style: "width: 300px; padding: 20px; background: #f5f5f5;",
                     BenchmarkPanel {
                         object_count: object_count_signal,
-                        render_mode: mode.clone(),
+                        render_mode: active_mode.clone(),
+                        scene_seed,
+                    }
+                    
+                    MultiRunPanel {
+                        object_count: object_count_signal,
+                        scene_seed,
+                        on_select_mode: move |mode| {
+                            // 計測中は自動調整を止め、指定の構成に固定する
+                            if *adaptive.enabled.peek() {
+                                adaptive.enabled.set(false);
+                            }
+                            adaptive.reset_to(RenderStep { mode, detail: DetailLevel::Full });
+                        },
+                    }
+                    
+                    {chaos_panel}
+                    
+                    if !adaptive.decisions.read().is_empty() {
+                        div {
+                            class: "adaptive-log",
+                            style: "margin-top: 16px; font-size: 0.8rem;",
+                            h4 { "自動調整ログ" }
+                            for (i, decision) in adaptive.decisions.read().iter().enumerate() {
+                                div {
+                                    key: "{i}",
+                                    style: "margin-bottom: 6px;",
+                                    strong {
+                                        {format!(
+                                            "{} {:.0}s {} → {}",
+                                            if decision.direction == AdaptiveDirection::Downgrade { "▼" } else { "▲" },
+                                            decision.at_ms / 1000.0,
+                                            decision.from.label(),
+                                            decision.to.label()
+                                        )}
+                                    }
+                                    div { style: "color: #666;", "{decision.reason}" }
+                                }
+                            }
+                        }
                     }
                 }
             }
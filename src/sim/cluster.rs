@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+/// 1セルにまとめたマーカー
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cluster {
+    pub x: f64,
+    pub y: f64,
+    pub count: u32,
+}
+
+/// 画面座標をセル単位でまとめるグリッドクラスタリング
+///
+/// 位置はセル内の点の重心。Leaflet.markerclusterの最大半径に近い挙動
+pub fn grid_cluster(points: &[(f64, f64)], cell_px: f64) -> Vec<Cluster> {
+    let mut cells: HashMap<(i64, i64), (f64, f64, u32)> = HashMap::with_capacity(points.len() / 4);
+
+    for &(x, y) in points {
+        let key = ((x / cell_px).floor() as i64, (y / cell_px).floor() as i64);
+        let entry = cells.entry(key).or_insert((0.0, 0.0, 0));
+        entry.0 += x;
+        entry.1 += y;
+        entry.2 += 1;
+    }
+
+    cells
+        .into_values()
+        .map(|(sum_x, sum_y, count)| Cluster {
+            x: sum_x / count as f64,
+            y: sum_y / count as f64,
+            count,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clusters_points_per_cell() {
+        let points = [(1.0, 1.0), (3.0, 3.0), (50.0, 50.0), (-1.0, 2.0)];
+        let mut clusters = grid_cluster(&points, 10.0);
+        clusters.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
+
+        assert_eq!(clusters.len(), 3);
+        assert_eq!(clusters[0], Cluster { x: -1.0, y: 2.0, count: 1 });
+        assert_eq!(clusters[1], Cluster { x: 2.0, y: 2.0, count: 2 });
+        assert_eq!(clusters.iter().map(|c| c.count).sum::<u32>(), 4);
+    }
+}
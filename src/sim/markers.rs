@@ -0,0 +1,109 @@
+use super::projection::GeoBounds;
+use super::rng::SimRng;
+
+/// DOM/Canvasモードのマーカー速度の幅（JS側の `(Math.random() - 0.5) * 0.00005`）
+pub const DEFAULT_SPEED_RANGE: f64 = 0.00005;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimMarker {
+    pub lat: f64,
+    pub lng: f64,
+    pub velocity_lat: f64,
+    pub velocity_lng: f64,
+}
+
+/// 表示範囲内で跳ね返りながら動くマーカー群
+///
+/// `components::map` などの `animate()` と同じ更新式
+#[derive(Debug, Clone)]
+pub struct MarkerSim {
+    bounds: GeoBounds,
+    speed_range: f64,
+    markers: Vec<SimMarker>,
+}
+
+impl MarkerSim {
+    pub fn new(count: usize, bounds: GeoBounds, speed_range: f64, rng: &mut SimRng) -> Self {
+        let mut sim = Self { bounds, speed_range, markers: Vec::with_capacity(count) };
+        sim.set_count(count, rng);
+        sim
+    }
+
+    pub fn markers(&self) -> &[SimMarker] {
+        &self.markers
+    }
+
+    /// マーカー数を変える（カオスシナリオの追加・削除）。増やした分は範囲内にランダム配置する
+    pub fn set_count(&mut self, count: usize, rng: &mut SimRng) {
+        let b = self.bounds;
+        let speed_range = self.speed_range;
+        self.markers.truncate(count);
+        while self.markers.len() < count {
+            self.markers.push(SimMarker {
+                lat: rng.range(b.south, b.north),
+                lng: rng.range(b.west, b.east),
+                velocity_lat: (rng.next_f64() - 0.5) * speed_range,
+                velocity_lng: (rng.next_f64() - 0.5) * speed_range,
+            });
+        }
+    }
+
+    /// 1フレーム分進める
+    pub fn tick(&mut self) {
+        let b = self.bounds;
+        for marker in &mut self.markers {
+            let mut lat = marker.lat + marker.velocity_lat;
+            let mut lng = marker.lng + marker.velocity_lng;
+
+            // 境界でバウンス
+            if lat <= b.south || lat >= b.north {
+                marker.velocity_lat *= -1.0;
+                lat = marker.lat + marker.velocity_lat;
+            }
+            if lng <= b.west || lng >= b.east {
+                marker.velocity_lng *= -1.0;
+                lng = marker.lng + marker.velocity_lng;
+            }
+
+            marker.lat = lat;
+            marker.lng = lng;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_bounce_and_stay_in_bounds() {
+        let bounds = GeoBounds { south: 0.0, west: 0.0, north: 0.001, east: 0.001 };
+        let mut sim = MarkerSim::new(200, bounds, 0.0002, &mut SimRng::new(7));
+
+        for _ in 0..1_000 {
+            sim.tick();
+        }
+
+        assert!(sim.markers().iter().all(|m| {
+            m.lat > bounds.south - 0.0002
+                && m.lat < bounds.north + 0.0002
+                && m.lng > bounds.west - 0.0002
+                && m.lng < bounds.east + 0.0002
+        }));
+    }
+
+    #[test]
+    fn set_count_keeps_existing_markers() {
+        let bounds = GeoBounds { south: 0.0, west: 0.0, north: 1.0, east: 1.0 };
+        let mut rng = SimRng::new(7);
+        let mut sim = MarkerSim::new(10, bounds, DEFAULT_SPEED_RANGE, &mut rng);
+        let first = sim.markers()[..5].to_vec();
+
+        sim.set_count(5, &mut rng);
+        assert_eq!(sim.markers(), &first[..]);
+        sim.set_count(50, &mut rng);
+        assert_eq!(sim.markers().len(), 50);
+        assert_eq!(&sim.markers()[..5], &first[..]);
+        assert!(sim.markers().iter().all(|m| bounds.contains(m.lat, m.lng)));
+    }
+}
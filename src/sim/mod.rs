@@ -0,0 +1,10 @@
+//! ブラウザに依存しないシミュレーション部分
+//!
+//! JS側のマーカーアニメーションと同じ計算をRustで再現し、
+//! ネイティブベンチマーク（`src/bin/bench.rs`）から使う。乱数はアプリのカオスシナリオと共有する。
+
+pub mod rng;
+pub mod projection;
+pub mod markers;
+pub mod spatial;
+pub mod cluster;
@@ -0,0 +1,112 @@
+use std::f64::consts::PI;
+
+/// Leafletと同じタイルサイズ
+pub const TILE_SIZE: f64 = 256.0;
+/// Web Mercatorで扱える最大緯度
+pub const MAX_LATITUDE: f64 = 85.051_128_779_806_6;
+
+/// 緯度経度の矩形範囲
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoBounds {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl GeoBounds {
+    pub fn contains(&self, lat: f64, lng: f64) -> bool {
+        lat >= self.south && lat <= self.north && lng >= self.west && lng <= self.east
+    }
+}
+
+/// Web Mercator（EPSG:3857）のズームレベルでのピクセル座標
+pub fn project(lat: f64, lng: f64, zoom: f64) -> (f64, f64) {
+    let scale = TILE_SIZE * 2f64.powf(zoom);
+    let lat = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE);
+    let sin = (lat * PI / 180.0).sin();
+
+    let x = (lng + 180.0) / 360.0 * scale;
+    let y = (0.5 - ((1.0 + sin) / (1.0 - sin)).ln() / (4.0 * PI)) * scale;
+    (x, y)
+}
+
+/// `project` の逆変換
+pub fn unproject(x: f64, y: f64, zoom: f64) -> (f64, f64) {
+    let scale = TILE_SIZE * 2f64.powf(zoom);
+    let lng = x / scale * 360.0 - 180.0;
+    let n = PI - 2.0 * PI * y / scale;
+    let lat = n.sinh().atan() * 180.0 / PI;
+    (lat, lng)
+}
+
+/// 地図の表示範囲（`L.map` の中心・ズーム・コンテナサイズに相当）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub center_lat: f64,
+    pub center_lng: f64,
+    pub zoom: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Viewport {
+    /// 各マップコンポーネントの初期表示（東京, ズーム13）
+    pub fn tokyo(width: f64, height: f64) -> Self {
+        Self {
+            center_lat: 35.6762,
+            center_lng: 139.6503,
+            zoom: 13.0,
+            width,
+            height,
+        }
+    }
+
+    fn origin(&self) -> (f64, f64) {
+        let (cx, cy) = project(self.center_lat, self.center_lng, self.zoom);
+        (cx - self.width / 2.0, cy - self.height / 2.0)
+    }
+
+    /// `map.getBounds()` に相当
+    pub fn bounds(&self) -> GeoBounds {
+        let (ox, oy) = self.origin();
+        let (north, west) = unproject(ox, oy, self.zoom);
+        let (south, east) = unproject(ox + self.width, oy + self.height, self.zoom);
+        GeoBounds { south, west, north, east }
+    }
+
+    /// `map.latLngToContainerPoint()` に相当
+    pub fn to_container_point(self, lat: f64, lng: f64) -> (f64, f64) {
+        let (ox, oy) = self.origin();
+        let (x, y) = project(lat, lng, self.zoom);
+        (x - ox, y - oy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projection_round_trips() {
+        let (x, y) = project(35.6762, 139.6503, 13.0);
+        let (lat, lng) = unproject(x, y, 13.0);
+        assert!((lat - 35.6762).abs() < 1e-9);
+        assert!((lng - 139.6503).abs() < 1e-9);
+
+        // 赤道・本初子午線はズーム0で256pxタイルの中央
+        assert_eq!(project(0.0, 0.0, 0.0), (128.0, 128.0));
+    }
+
+    #[test]
+    fn viewport_corners_map_to_container_edges() {
+        let viewport = Viewport::tokyo(1280.0, 720.0);
+        let bounds = viewport.bounds();
+
+        let (x, y) = viewport.to_container_point(bounds.north, bounds.west);
+        assert!(x.abs() < 1e-6 && y.abs() < 1e-6);
+        let (x, y) = viewport.to_container_point(bounds.south, bounds.east);
+        assert!((x - 1280.0).abs() < 1e-6 && (y - 720.0).abs() < 1e-6);
+        assert!(bounds.contains(viewport.center_lat, viewport.center_lng));
+    }
+}
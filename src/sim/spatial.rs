@@ -0,0 +1,104 @@
+/// 画面座標の矩形
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Rect {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+}
+
+/// 固定セルサイズのグリッド空間インデックス
+///
+/// 毎フレーム全点が動くため、木構造より再構築の安いグリッドを使う
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f64,
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<u32>>,
+    points: Vec<(f64, f64)>,
+}
+
+impl SpatialGrid {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> Self {
+        let columns = (width / cell_size).ceil().max(1.0) as usize;
+        let rows = (height / cell_size).ceil().max(1.0) as usize;
+        Self {
+            cell_size,
+            columns,
+            rows,
+            cells: vec![Vec::new(); columns * rows],
+            points: Vec::new(),
+        }
+    }
+
+    fn cell_of(&self, x: f64, y: f64) -> (usize, usize) {
+        let column = ((x / self.cell_size).floor().max(0.0) as usize).min(self.columns - 1);
+        let row = ((y / self.cell_size).floor().max(0.0) as usize).min(self.rows - 1);
+        (column, row)
+    }
+
+    /// 全点を入れ直す（セルのVecは再利用する）
+    pub fn rebuild(&mut self, points: impl IntoIterator<Item = (f64, f64)>) {
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+        self.points.clear();
+        self.points.extend(points);
+
+        for (i, &(x, y)) in self.points.iter().enumerate() {
+            let (column, row) = self.cell_of(x, y);
+            self.cells[row * self.columns + column].push(i as u32);
+        }
+    }
+
+    /// 矩形内の点のインデックスを `out` に追加
+    pub fn query(&self, rect: &Rect, out: &mut Vec<u32>) {
+        let (min_column, min_row) = self.cell_of(rect.min_x, rect.min_y);
+        let (max_column, max_row) = self.cell_of(rect.max_x, rect.max_y);
+
+        for row in min_row..=max_row {
+            for column in min_column..=max_column {
+                for &i in &self.cells[row * self.columns + column] {
+                    let (x, y) = self.points[i as usize];
+                    if rect.contains(x, y) {
+                        out.push(i);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_matches_linear_scan() {
+        let points: Vec<(f64, f64)> = (0..500)
+            .map(|i| (((i * 37) % 800) as f64, ((i * 91) % 600) as f64))
+            .collect();
+        let mut grid = SpatialGrid::new(800.0, 600.0, 64.0);
+        grid.rebuild(points.iter().copied());
+
+        let rect = Rect { min_x: 100.0, min_y: 50.0, max_x: 350.0, max_y: 420.0 };
+        let mut found = Vec::new();
+        grid.query(&rect, &mut found);
+        found.sort_unstable();
+
+        let expected: Vec<u32> = points
+            .iter()
+            .enumerate()
+            .filter(|(_, &(x, y))| rect.contains(x, y))
+            .map(|(i, _)| i as u32)
+            .collect();
+        assert_eq!(found, expected);
+    }
+}
@@ -0,0 +1,324 @@
+//! 実測フレーム時間に基づくレンダリング方式の自動調整
+//!
+//! 予算超過が一定時間続いたら詳細度を下げ、それでも足りなければバックエンドを切り替える
+//! （DOM → Canvas → WebGL）。余裕が続いた場合は逆順に戻す。
+//! 上げ下げで閾値と継続時間を分け、直後に再度下げた場合は次の引き上げ条件を厳しくして往復を防ぐ。
+
+use crate::hooks::RenderMode;
+use crate::utils::frame_stats::{FrameTimeStats, FRAME_BUDGET_60FPS_MS};
+
+/// マーカーアニメーションの詳細度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DetailLevel {
+    #[default]
+    Full,
+    Reduced,
+    Minimal,
+}
+
+impl DetailLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DetailLevel::Full => "標準",
+            DetailLevel::Reduced => "軽量",
+            DetailLevel::Minimal => "最小",
+        }
+    }
+
+    /// 1フレームで更新するマーカーの間隔（N個に1個を更新し、移動量をN倍にする）
+    pub fn update_stride(&self) -> u32 {
+        match self {
+            DetailLevel::Full => 1,
+            DetailLevel::Reduced => 2,
+            DetailLevel::Minimal => 4,
+        }
+    }
+}
+
+/// 調整の1段階（重い順に並べた `LADDER` の要素）
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderStep {
+    pub mode: RenderMode,
+    pub detail: DetailLevel,
+}
+
+impl RenderStep {
+    pub fn label(&self) -> String {
+        format!("{} / {}", self.mode.as_str(), self.detail.label())
+    }
+}
+
+/// 重い順。同じバックエンド内で詳細度を下げてから次のバックエンドに移る
+const LADDER: [(RenderMode, DetailLevel); 7] = [
+    (RenderMode::DOM, DetailLevel::Full),
+    (RenderMode::DOM, DetailLevel::Reduced),
+    (RenderMode::Canvas, DetailLevel::Full),
+    (RenderMode::Canvas, DetailLevel::Reduced),
+    (RenderMode::WebGL, DetailLevel::Full),
+    (RenderMode::WebGL, DetailLevel::Reduced),
+    (RenderMode::WebGL, DetailLevel::Minimal),
+];
+
+fn ladder_index(step: &RenderStep) -> usize {
+    LADDER
+        .iter()
+        .position(|(mode, detail)| *mode == step.mode && *detail == step.detail)
+        .unwrap_or(0)
+}
+
+fn ladder_step(index: usize) -> RenderStep {
+    let (mode, detail) = LADDER[index.min(LADDER.len() - 1)].clone();
+    RenderStep { mode, detail }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveConfig {
+    /// 目標フレーム時間（ms）
+    pub budget_ms: f64,
+    /// p95がこの倍率×予算を超えた区間を「超過」とみなす
+    pub downgrade_ratio: f64,
+    /// p95がこの倍率×予算を下回った区間を「余裕あり」とみなす
+    pub upgrade_ratio: f64,
+    /// 引き下げまでに超過が続く必要のある時間（ms）
+    pub downgrade_after_ms: f64,
+    /// 引き上げまでに余裕が続く必要のある時間（ms）
+    pub upgrade_after_ms: f64,
+    /// 切り替え直後に判定を止める時間（ms）
+    pub cooldown_ms: f64,
+    /// 判定に必要な区間内の最小フレーム数
+    pub min_frames: u32,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        Self {
+            budget_ms: FRAME_BUDGET_60FPS_MS,
+            downgrade_ratio: 1.25,
+            upgrade_ratio: 0.6,
+            downgrade_after_ms: 3000.0,
+            upgrade_after_ms: 10000.0,
+            cooldown_ms: 3000.0,
+            min_frames: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdaptiveDirection {
+    Downgrade,
+    Upgrade,
+}
+
+/// 切り替えの記録
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveDecision {
+    pub at_ms: f64,
+    pub direction: AdaptiveDirection,
+    pub from: RenderStep,
+    pub to: RenderStep,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdaptiveController {
+    config: AdaptiveConfig,
+    index: usize,
+    /// 引き上げの上限（初期段階、または最後に手動・URLで選んだ段階）
+    ceiling: usize,
+    over_since: Option<f64>,
+    headroom_since: Option<f64>,
+    cooldown_until: f64,
+    /// 引き上げ直後に引き下げた回数（引き上げに必要な時間を倍にしていく）
+    failed_upgrades: u32,
+    last_upgrade_at: Option<f64>,
+}
+
+impl AdaptiveController {
+    pub fn new(config: AdaptiveConfig, initial: RenderStep) -> Self {
+        let index = ladder_index(&initial);
+        Self {
+            config,
+            index,
+            ceiling: index,
+            over_since: None,
+            headroom_since: None,
+            cooldown_until: 0.0,
+            failed_upgrades: 0,
+            last_upgrade_at: None,
+        }
+    }
+
+    pub fn step(&self) -> RenderStep {
+        ladder_step(self.index)
+    }
+
+    /// 手動でモードが変わった場合に現在位置を合わせる（判定状態はリセット）
+    ///
+    /// 選んだ段階より重い段階には引き上げない。
+    pub fn reset_to(&mut self, step: RenderStep, now_ms: f64) {
+        self.index = ladder_index(&step);
+        self.ceiling = self.index;
+        self.over_since = None;
+        self.headroom_since = None;
+        self.cooldown_until = now_ms + self.config.cooldown_ms;
+    }
+
+    fn required_upgrade_ms(&self) -> f64 {
+        self.config.upgrade_after_ms * 2f64.powi(self.failed_upgrades.min(4) as i32)
+    }
+
+    /// 直近区間（`window_end_ms` に終わる）の集計を渡し、切り替えが必要なら返す
+    pub fn observe(&mut self, window: &FrameTimeStats, window_end_ms: f64) -> Option<AdaptiveDecision> {
+        if window.frame_count < self.config.min_frames || window_end_ms < self.cooldown_until {
+            return None;
+        }
+
+        let budget = self.config.budget_ms;
+        if window.p95_ms > budget * self.config.downgrade_ratio {
+            self.headroom_since = None;
+            let since = *self.over_since.get_or_insert(window_end_ms);
+            let sustained = window_end_ms - since;
+            if sustained >= self.config.downgrade_after_ms && self.index + 1 < LADDER.len() {
+                // 引き上げから間もない引き下げは「失敗した引き上げ」として数える
+                if self.last_upgrade_at.is_some_and(|at| window_end_ms - at < self.required_upgrade_ms()) {
+                    self.failed_upgrades += 1;
+                }
+                let reason = format!(
+                    "p95 {:.1}ms が予算 {:.1}ms を {:.1}秒間超過（jank {:.0}%）",
+                    window.p95_ms,
+                    budget,
+                    sustained / 1000.0,
+                    window.jank_ratio()
+                );
+                return Some(self.shift(self.index + 1, AdaptiveDirection::Downgrade, window_end_ms, reason));
+            }
+        } else if window.p95_ms < budget * self.config.upgrade_ratio {
+            self.over_since = None;
+            let since = *self.headroom_since.get_or_insert(window_end_ms);
+            let sustained = window_end_ms - since;
+            if sustained >= self.required_upgrade_ms() && self.index > self.ceiling {
+                let reason = format!(
+                    "p95 {:.1}ms で予算 {:.1}ms に {:.1}秒間余裕あり",
+                    window.p95_ms,
+                    budget,
+                    sustained / 1000.0
+                );
+                self.last_upgrade_at = Some(window_end_ms);
+                return Some(self.shift(self.index - 1, AdaptiveDirection::Upgrade, window_end_ms, reason));
+            }
+        } else {
+            // 閾値の間（ヒステリシス帯）では両方の継続をリセット
+            self.over_since = None;
+            self.headroom_since = None;
+        }
+
+        None
+    }
+
+    fn shift(&mut self, to: usize, direction: AdaptiveDirection, now_ms: f64, reason: String) -> AdaptiveDecision {
+        let from = self.step();
+        self.index = to;
+        self.over_since = None;
+        self.headroom_since = None;
+        self.cooldown_until = now_ms + self.config.cooldown_ms;
+        AdaptiveDecision { at_ms: now_ms, direction, from, to: self.step(), reason }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(p95_ms: f64) -> FrameTimeStats {
+        FrameTimeStats { frame_count: 60, p95_ms, ..Default::default() }
+    }
+
+    fn dom() -> RenderStep {
+        RenderStep { mode: RenderMode::DOM, detail: DetailLevel::Full }
+    }
+
+    #[test]
+    fn downgrades_only_after_sustained_overrun() {
+        let mut controller = AdaptiveController::new(AdaptiveConfig::default(), dom());
+
+        // 1秒ごとの区間。超過が3秒続くまでは切り替えない
+        assert!(controller.observe(&window(40.0), 1000.0).is_none());
+        assert!(controller.observe(&window(40.0), 2000.0).is_none());
+        assert!(controller.observe(&window(40.0), 3000.0).is_none());
+        let decision = controller.observe(&window(40.0), 4000.0).unwrap();
+        assert_eq!(decision.direction, AdaptiveDirection::Downgrade);
+        assert_eq!(decision.to, RenderStep { mode: RenderMode::DOM, detail: DetailLevel::Reduced });
+
+        // ヒステリシス帯（予算付近）では上げも下げもしない
+        for t in 5..30 {
+            assert!(controller.observe(&window(16.0), t as f64 * 1000.0).is_none());
+        }
+    }
+
+    #[test]
+    fn failed_upgrade_doubles_required_headroom() {
+        let mut controller = AdaptiveController::new(AdaptiveConfig::default(), dom());
+
+        // 超過で1段階下げる
+        let mut t = 1000.0;
+        while controller.observe(&window(40.0), t).is_none() {
+            t += 1000.0;
+        }
+
+        // 余裕が10秒続いたら1段階戻す
+        t += 3000.0;
+        let headroom_start = t;
+        let upgrade = loop {
+            if let Some(decision) = controller.observe(&window(5.0), t) {
+                break decision;
+            }
+            t += 1000.0;
+        };
+        assert_eq!(upgrade.direction, AdaptiveDirection::Upgrade);
+        assert_eq!(upgrade.to, dom());
+        assert_eq!(upgrade.at_ms - headroom_start, 10000.0);
+
+        // 戻した直後に再び超過 → 引き下げ、次の引き上げには20秒必要
+        t += 3000.0;
+        let start = t;
+        let downgrade = loop {
+            if let Some(decision) = controller.observe(&window(40.0), t) {
+                break decision;
+            }
+            t += 1000.0;
+        };
+        assert_eq!(downgrade.direction, AdaptiveDirection::Downgrade);
+        assert!(t - start >= 3000.0);
+
+        t += 3000.0;
+        let headroom_start = t;
+        while controller.observe(&window(5.0), t).is_none() {
+            t += 1000.0;
+        }
+        assert_eq!(t - headroom_start, 20000.0);
+    }
+
+    #[test]
+    fn never_upgrades_past_initial_step() {
+        let canvas = RenderStep { mode: RenderMode::Canvas, detail: DetailLevel::Full };
+        let mut controller = AdaptiveController::new(AdaptiveConfig::default(), canvas.clone());
+
+        for t in 1..120 {
+            assert!(controller.observe(&window(2.0), t as f64 * 1000.0).is_none());
+        }
+        assert_eq!(controller.step(), canvas);
+    }
+
+    #[test]
+    fn manual_selection_becomes_the_ceiling() {
+        let canvas = RenderStep { mode: RenderMode::Canvas, detail: DetailLevel::Full };
+        let mut controller = AdaptiveController::new(AdaptiveConfig::default(), dom());
+        controller.reset_to(canvas.clone(), 0.0);
+
+        // DOMから始めても、選び直したCanvasより重い段階には戻さない
+        for t in 1..120 {
+            assert!(controller.observe(&window(2.0), t as f64 * 1000.0).is_none());
+        }
+        assert_eq!(controller.step(), canvas);
+    }
+}
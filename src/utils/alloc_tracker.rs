@@ -0,0 +1,227 @@
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use serde::{Deserialize, Serialize};
+
+/// 割り当て量を数えるグローバルアロケータのラッパー
+///
+/// `alloc-tracking` フィーチャー有効時に `lib.rs` で wee_alloc または
+/// システムアロケータを包んで使う。WASMはシングルスレッドなので `Relaxed` で十分。
+pub struct CountingAllocator<A> {
+    inner: A,
+    live_bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+    allocations: AtomicUsize,
+    deallocations: AtomicUsize,
+}
+
+impl<A> CountingAllocator<A> {
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            live_bytes: AtomicUsize::new(0),
+            peak_bytes: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+            deallocations: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> AllocStats {
+        AllocStats {
+            live_bytes: self.live_bytes.load(Ordering::Relaxed),
+            peak_bytes: self.peak_bytes.load(Ordering::Relaxed),
+            allocations: self.allocations.load(Ordering::Relaxed),
+            deallocations: self.deallocations.load(Ordering::Relaxed),
+        }
+    }
+
+    /// ピーク値を現在の使用量にリセット（計測区間の開始時に使う）
+    pub fn reset_peak(&self) {
+        self.peak_bytes.store(self.live_bytes.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn on_alloc(&self, size: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        let live = self.live_bytes.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak_bytes.fetch_max(live, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn on_dealloc(&self, size: usize) {
+        self.deallocations.fetch_add(1, Ordering::Relaxed);
+        self.live_bytes.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            self.on_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.on_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.on_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            // reallocは解放と確保の1組として数える
+            self.on_dealloc(layout.size());
+            self.on_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// アロケータの累計値
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AllocStats {
+    pub live_bytes: usize,
+    pub peak_bytes: usize,
+    pub allocations: usize,
+    pub deallocations: usize,
+}
+
+/// 計測用アロケータの現在値（`alloc-tracking` 無効時は `None`）
+pub fn current_stats() -> Option<AllocStats> {
+    #[cfg(feature = "alloc-tracking")]
+    {
+        Some(crate::ALLOC.stats())
+    }
+    #[cfg(not(feature = "alloc-tracking"))]
+    {
+        None
+    }
+}
+
+/// 計測区間の開始時にピーク値をリセット
+pub fn reset_peak() {
+    #[cfg(feature = "alloc-tracking")]
+    crate::ALLOC.reset_peak();
+}
+
+/// 使用中のアロケータ名
+pub fn allocator_name() -> &'static str {
+    if cfg!(feature = "wee_alloc") {
+        "wee_alloc"
+    } else {
+        "dlmalloc"
+    }
+}
+
+/// フレームごとの割り当て回数の集計
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrameAllocStats {
+    pub frames: u32,
+    /// 1回以上割り当てが発生したフレーム数
+    pub frames_with_allocations: u32,
+    pub max_allocations_per_frame: usize,
+    pub total_allocations: usize,
+    pub peak_bytes: usize,
+}
+
+impl FrameAllocStats {
+    pub fn avg_allocations_per_frame(&self) -> f64 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.total_allocations as f64 / self.frames as f64
+    }
+
+    /// 定常状態でフレームごとの割り当てがゼロか
+    pub fn is_allocation_free(&self) -> bool {
+        self.frames > 0 && self.frames_with_allocations == 0
+    }
+}
+
+/// 前フレームからの割り当て回数の差分を取る
+#[derive(Debug, Clone, Default)]
+pub struct FrameAllocCounter {
+    last_allocations: Option<usize>,
+    stats: FrameAllocStats,
+}
+
+impl FrameAllocCounter {
+    /// フレーム境界で呼び出す
+    pub fn on_frame(&mut self, current: &AllocStats) {
+        if let Some(last) = self.last_allocations {
+            let delta = current.allocations.saturating_sub(last);
+            self.stats.frames += 1;
+            self.stats.total_allocations += delta;
+            if delta > 0 {
+                self.stats.frames_with_allocations += 1;
+            }
+            self.stats.max_allocations_per_frame = self.stats.max_allocations_per_frame.max(delta);
+        }
+        self.stats.peak_bytes = self.stats.peak_bytes.max(current.peak_bytes);
+        self.last_allocations = Some(current.allocations);
+    }
+
+    pub fn stats(&self) -> FrameAllocStats {
+        self.stats
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::System;
+
+    #[test]
+    fn counts_live_and_peak_bytes() {
+        let allocator = CountingAllocator::new(System);
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(1024, 8).unwrap();
+
+        unsafe {
+            let a = allocator.alloc(small);
+            let b = allocator.alloc_zeroed(large);
+            allocator.dealloc(b, large);
+            let a = allocator.realloc(a, small, 128);
+            allocator.dealloc(a, Layout::from_size_align(128, 8).unwrap());
+        }
+
+        let stats = allocator.stats();
+        assert_eq!(stats.live_bytes, 0);
+        assert_eq!(stats.peak_bytes, 64 + 1024);
+        assert_eq!(stats.allocations, 3);
+        assert_eq!(stats.deallocations, 3);
+    }
+
+    #[test]
+    fn frame_counter_tracks_per_frame_deltas() {
+        let mut counter = FrameAllocCounter::default();
+        let mut stats = AllocStats::default();
+
+        // 最初のフレームは基準値の記録のみ
+        counter.on_frame(&stats);
+        for allocations in [0, 0, 3, 0] {
+            stats.allocations += allocations;
+            counter.on_frame(&stats);
+        }
+
+        let result = counter.stats();
+        assert_eq!(result.frames, 4);
+        assert_eq!(result.frames_with_allocations, 1);
+        assert_eq!(result.max_allocations_per_frame, 3);
+        assert!(!result.is_allocation_free());
+        assert!((result.avg_allocations_per_frame() - 0.75).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,318 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::frame_stats::FrameTimeStats;
+use crate::utils::alloc_tracker::FrameAllocStats;
+
+/// エクスポート形式のバージョン（フィールド追加時に上げる）
+///
+/// - 2: `allocations` とCSVの末尾の割り当て列を追加
+pub const BENCHMARK_SCHEMA_VERSION: u32 = 2;
+
+/// 1回分のベンチマーク記録（JSON/CSVでエクスポートする単位）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    pub schema_version: u32,
+    pub mode: String,
+    pub object_count: i32,
+    pub seed: Option<u64>,
+    pub recorded_at: String,
+    pub duration_ms: f64,
+    pub min_fps: f64,
+    pub max_fps: f64,
+    pub avg_fps: f64,
+    pub frame_stats: FrameTimeStats,
+    pub memory_mb: Option<f64>,
+    /// `alloc-tracking` 有効時のみ
+    #[serde(default)]
+    pub allocations: Option<FrameAllocStats>,
+    pub user_agent: String,
+    pub build_profile: String,
+}
+
+impl BenchmarkRun {
+    /// ビルド構成を表す文字列（例: "release+wee_alloc"）
+    pub fn current_build_profile() -> String {
+        let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+        let allocator = crate::utils::alloc_tracker::allocator_name();
+        let tracking = if cfg!(feature = "alloc-tracking") { "+alloc-tracking" } else { "" };
+        format!("{}+{}{}", profile, allocator, tracking)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let run: Self = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid benchmark JSON: {}", e))?;
+        if run.schema_version > BENCHMARK_SCHEMA_VERSION {
+            return Err(format!(
+                "Unsupported schema version: {} (supported: {})",
+                run.schema_version, BENCHMARK_SCHEMA_VERSION
+            ));
+        }
+        Ok(run)
+    }
+
+    /// 列は末尾に追加していく（既存の列の位置は変えない）
+    pub fn csv_header() -> &'static str {
+        "schema_version,mode,object_count,seed,recorded_at,duration_ms,min_fps,max_fps,avg_fps,\
+frame_count,avg_frame_ms,p50_ms,p95_ms,p99_ms,max_frame_ms,over_16_7ms,over_33ms,over_50ms,\
+longest_stall_ms,memory_mb,user_agent,build_profile,allocs_per_frame_avg,allocs_per_frame_max,alloc_peak_bytes"
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        let f = &self.frame_stats;
+        [
+            self.schema_version.to_string(),
+            csv_escape(&self.mode),
+            self.object_count.to_string(),
+            self.seed.map(|s| s.to_string()).unwrap_or_default(),
+            csv_escape(&self.recorded_at),
+            format!("{:.1}", self.duration_ms),
+            format!("{:.2}", self.min_fps),
+            format!("{:.2}", self.max_fps),
+            format!("{:.2}", self.avg_fps),
+            f.frame_count.to_string(),
+            format!("{:.3}", f.avg_ms),
+            format!("{:.3}", f.p50_ms),
+            format!("{:.3}", f.p95_ms),
+            format!("{:.3}", f.p99_ms),
+            format!("{:.3}", f.max_ms),
+            f.over_16_7ms.to_string(),
+            f.over_33ms.to_string(),
+            f.over_50ms.to_string(),
+            format!("{:.3}", f.longest_stall_ms),
+            self.memory_mb.map(|m| format!("{:.2}", m)).unwrap_or_default(),
+            csv_escape(&self.user_agent),
+            csv_escape(&self.build_profile),
+            self.allocations.map(|a| format!("{:.3}", a.avg_allocations_per_frame())).unwrap_or_default(),
+            self.allocations.map(|a| a.max_allocations_per_frame.to_string()).unwrap_or_default(),
+            self.allocations.map(|a| a.peak_bytes.to_string()).unwrap_or_default(),
+        ]
+        .join(",")
+    }
+
+    /// ヘッダー付きのCSV
+    pub fn to_csv(&self) -> String {
+        format!("{}\n{}\n", Self::csv_header(), self.to_csv_row())
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 回帰判定のしきい値（いずれも悪化方向の許容量）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegressionThresholds {
+    /// 平均FPSの低下率（%）
+    pub avg_fps_drop_pct: f64,
+    /// P95フレーム時間の増加率（%）
+    pub p95_increase_pct: f64,
+    /// P99フレーム時間の増加率（%）
+    pub p99_increase_pct: f64,
+    /// 16.7ms超過フレーム割合の増加（ポイント）
+    pub jank_increase_points: f64,
+    /// メモリ使用量の増加率（%）
+    pub memory_increase_pct: f64,
+}
+
+impl Default for RegressionThresholds {
+    fn default() -> Self {
+        Self {
+            avg_fps_drop_pct: 5.0,
+            p95_increase_pct: 10.0,
+            p99_increase_pct: 15.0,
+            jank_increase_points: 2.0,
+            memory_increase_pct: 10.0,
+        }
+    }
+}
+
+/// 1指標分のベースラインとの差分
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDelta {
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    pub passed: bool,
+}
+
+impl MetricDelta {
+    pub fn delta(&self) -> f64 {
+        self.current - self.baseline
+    }
+
+    pub fn delta_pct(&self) -> f64 {
+        if self.baseline == 0.0 {
+            return 0.0;
+        }
+        self.delta() * 100.0 / self.baseline
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineComparison {
+    pub deltas: Vec<MetricDelta>,
+    /// モードやオブジェクト数が異なる場合の注意
+    pub warnings: Vec<String>,
+}
+
+impl BaselineComparison {
+    pub fn passed(&self) -> bool {
+        self.deltas.iter().all(|d| d.passed)
+    }
+}
+
+/// ベースラインと比較し、しきい値に基づいて合否を判定
+pub fn compare_with_baseline(
+    baseline: &BenchmarkRun,
+    current: &BenchmarkRun,
+    thresholds: &RegressionThresholds,
+) -> BaselineComparison {
+    let mut deltas = Vec::new();
+
+    let fps_floor = baseline.avg_fps * (1.0 - thresholds.avg_fps_drop_pct / 100.0);
+    deltas.push(MetricDelta {
+        name: "平均FPS",
+        unit: "fps",
+        baseline: baseline.avg_fps,
+        current: current.avg_fps,
+        passed: current.avg_fps >= fps_floor,
+    });
+
+    let p95_ceiling = baseline.frame_stats.p95_ms * (1.0 + thresholds.p95_increase_pct / 100.0);
+    deltas.push(MetricDelta {
+        name: "P95フレーム時間",
+        unit: "ms",
+        baseline: baseline.frame_stats.p95_ms,
+        current: current.frame_stats.p95_ms,
+        passed: current.frame_stats.p95_ms <= p95_ceiling,
+    });
+
+    let p99_ceiling = baseline.frame_stats.p99_ms * (1.0 + thresholds.p99_increase_pct / 100.0);
+    deltas.push(MetricDelta {
+        name: "P99フレーム時間",
+        unit: "ms",
+        baseline: baseline.frame_stats.p99_ms,
+        current: current.frame_stats.p99_ms,
+        passed: current.frame_stats.p99_ms <= p99_ceiling,
+    });
+
+    let baseline_jank = baseline.frame_stats.jank_ratio();
+    let current_jank = current.frame_stats.jank_ratio();
+    deltas.push(MetricDelta {
+        name: ">16.7ms割合",
+        unit: "%",
+        baseline: baseline_jank,
+        current: current_jank,
+        passed: current_jank <= baseline_jank + thresholds.jank_increase_points,
+    });
+
+    if let (Some(base_mem), Some(cur_mem)) = (baseline.memory_mb, current.memory_mb) {
+        let mem_ceiling = base_mem * (1.0 + thresholds.memory_increase_pct / 100.0);
+        deltas.push(MetricDelta {
+            name: "メモリ",
+            unit: "MB",
+            baseline: base_mem,
+            current: cur_mem,
+            passed: cur_mem <= mem_ceiling,
+        });
+    }
+
+    let mut warnings = Vec::new();
+    if baseline.mode != current.mode {
+        warnings.push(format!("レンダリングモードが異なります ({} → {})", baseline.mode, current.mode));
+    }
+    if baseline.object_count != current.object_count {
+        warnings.push(format!(
+            "オブジェクト数が異なります ({} → {})",
+            baseline.object_count, current.object_count
+        ));
+    }
+    if baseline.build_profile != current.build_profile {
+        warnings.push(format!(
+            "ビルド構成が異なります ({} → {})",
+            baseline.build_profile, current.build_profile
+        ));
+    }
+
+    BaselineComparison { deltas, warnings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_run(avg_fps: f64, p95_ms: f64) -> BenchmarkRun {
+        BenchmarkRun {
+            schema_version: BENCHMARK_SCHEMA_VERSION,
+            mode: "canvas".to_string(),
+            object_count: 1000,
+            seed: Some(42),
+            recorded_at: "2025-06-14T00:00:00.000Z".to_string(),
+            duration_ms: 10_000.0,
+            min_fps: avg_fps - 5.0,
+            max_fps: avg_fps + 1.0,
+            avg_fps,
+            frame_stats: FrameTimeStats {
+                frame_count: 600,
+                avg_ms: 16.7,
+                p50_ms: 16.5,
+                p95_ms,
+                p99_ms: p95_ms + 5.0,
+                max_ms: 60.0,
+                over_16_7ms: 12,
+                over_33ms: 2,
+                over_50ms: 1,
+                longest_stall_ms: 60.0,
+                longest_stall_at_ms: 1234.0,
+            },
+            memory_mb: Some(40.0),
+            allocations: None,
+            user_agent: "Mozilla/5.0 (X11; Linux x86_64), \"test\"".to_string(),
+            build_profile: "release+wee_alloc".to_string(),
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let run = sample_run(58.0, 18.0);
+        assert_eq!(BenchmarkRun::from_json(&run.to_json()), Ok(run));
+    }
+
+    #[test]
+    fn csv_row_matches_header_and_escapes_fields() {
+        let run = sample_run(58.0, 18.0);
+        let csv = run.to_csv();
+        let mut lines = csv.lines();
+        let header_fields = lines.next().unwrap().split(',').count();
+        let row = lines.next().unwrap();
+
+        assert_eq!(header_fields, 25);
+        assert!(row.contains("\"Mozilla/5.0 (X11; Linux x86_64), \"\"test\"\"\""));
+        // 追加した列は末尾（バージョン1の列の位置は変わらない）
+        assert_eq!(BenchmarkRun::csv_header().split(',').nth(21), Some("build_profile"));
+        assert!(row.ends_with("release+wee_alloc,,,"));
+    }
+
+    #[test]
+    fn comparison_flags_regressions() {
+        let baseline = sample_run(58.0, 18.0);
+        let thresholds = RegressionThresholds::default();
+
+        let same = compare_with_baseline(&baseline, &sample_run(57.0, 19.0), &thresholds);
+        assert!(same.passed());
+        assert!(same.warnings.is_empty());
+
+        let slower = compare_with_baseline(&baseline, &sample_run(50.0, 25.0), &thresholds);
+        assert!(!slower.passed());
+        let failed: Vec<_> = slower.deltas.iter().filter(|d| !d.passed).map(|d| d.name).collect();
+        assert_eq!(failed, vec!["平均FPS", "P95フレーム時間", "P99フレーム時間"]);
+    }
+}
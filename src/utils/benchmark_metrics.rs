@@ -0,0 +1,371 @@
+//! ベンチマークのメトリクス集計とスコアリング
+//!
+//! 目標FPSは端末クラスごとに異なり、スコアの段階は目標FPSに対する比率で判定する。
+
+use serde::{Deserialize, Serialize};
+use crate::utils::alloc_tracker::FrameAllocStats;
+use crate::utils::benchmark_export::{BenchmarkRun, BENCHMARK_SCHEMA_VERSION};
+use crate::utils::frame_stats::FrameTimeStats;
+use crate::utils::storage::{load_item, save_item};
+
+const SCORING_STORAGE_KEY: &str = "benchmark-scoring";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BenchmarkMetrics {
+    pub fps: f64,
+    pub min_fps: f64,
+    pub max_fps: f64,
+    pub avg_fps: f64,
+    /// FPSの記録回数（1秒に1回）
+    pub frame_count: u32,
+    pub frame_stats: FrameTimeStats,
+    pub peak_memory_mb: Option<f64>,
+    memory_total_mb: f64,
+    memory_samples: u32,
+    pub allocations: Option<FrameAllocStats>,
+}
+
+impl BenchmarkMetrics {
+    #[inline]
+    pub fn update_fps(&mut self, current_fps: f64) {
+        self.frame_count += 1;
+        self.fps = current_fps;
+
+        if self.min_fps == 0.0 || current_fps < self.min_fps {
+            self.min_fps = current_fps;
+        }
+        if current_fps > self.max_fps {
+            self.max_fps = current_fps;
+        }
+
+        // 移動平均でavg_fpsを計算
+        self.avg_fps = (self.avg_fps * (self.frame_count - 1) as f64 + current_fps) / self.frame_count as f64;
+    }
+
+    #[inline]
+    pub fn record_memory(&mut self, total_mb: f64) {
+        if self.peak_memory_mb.is_none_or(|peak| total_mb > peak) {
+            self.peak_memory_mb = Some(total_mb);
+        }
+        self.memory_total_mb += total_mb;
+        self.memory_samples += 1;
+    }
+
+    pub fn avg_memory_mb(&self) -> Option<f64> {
+        (self.memory_samples > 0).then(|| self.memory_total_mb / self.memory_samples as f64)
+    }
+
+    /// エクスポート用の記録に変換（`seed` はマーカー配置のシード。ランダム配置なら `None`）
+    pub fn to_run(&self, mode: &str, object_count: i32, seed: Option<u64>, duration_ms: f64) -> BenchmarkRun {
+        let user_agent = web_sys::window()
+            .and_then(|w| w.navigator().user_agent().ok())
+            .unwrap_or_default();
+
+        BenchmarkRun {
+            schema_version: BENCHMARK_SCHEMA_VERSION,
+            mode: mode.to_string(),
+            object_count,
+            seed,
+            recorded_at: js_sys::Date::new_0().to_iso_string().into(),
+            duration_ms,
+            min_fps: self.min_fps,
+            max_fps: self.max_fps,
+            avg_fps: self.avg_fps,
+            frame_stats: self.frame_stats.clone(),
+            memory_mb: self.peak_memory_mb,
+            allocations: self.allocations,
+            user_agent,
+            build_profile: BenchmarkRun::current_build_profile(),
+        }
+    }
+}
+
+/// 端末クラス（目標FPSの基準）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceClass {
+    /// 120Hz以上のディスプレイ
+    HighRefresh,
+    Desktop,
+    Mobile,
+    LowEnd,
+}
+
+impl DeviceClass {
+    pub const ALL: [DeviceClass; 4] = [
+        DeviceClass::HighRefresh,
+        DeviceClass::Desktop,
+        DeviceClass::Mobile,
+        DeviceClass::LowEnd,
+    ];
+
+    pub fn target_fps(&self) -> f64 {
+        match self {
+            DeviceClass::HighRefresh => 120.0,
+            DeviceClass::Desktop | DeviceClass::Mobile => 60.0,
+            DeviceClass::LowEnd => 30.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeviceClass::HighRefresh => "高リフレッシュレート",
+            DeviceClass::Desktop => "デスクトップ",
+            DeviceClass::Mobile => "モバイル",
+            DeviceClass::LowEnd => "低性能端末",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeviceClass::HighRefresh => "high_refresh",
+            DeviceClass::Desktop => "desktop",
+            DeviceClass::Mobile => "mobile",
+            DeviceClass::LowEnd => "low_end",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|class| class.name() == name)
+    }
+
+    /// UA・論理コア数・端末メモリ（GB）から推定する（リフレッシュレートは推定しない）
+    pub fn detect(user_agent: &str, hardware_concurrency: Option<f64>, device_memory_gb: Option<f64>) -> Self {
+        let low_cores = hardware_concurrency.is_some_and(|cores| cores <= 2.0);
+        let low_memory = device_memory_gb.is_some_and(|gb| gb <= 2.0);
+        if low_cores || low_memory {
+            return DeviceClass::LowEnd;
+        }
+
+        let ua = user_agent.to_ascii_lowercase();
+        if ["mobi", "android", "iphone", "ipad"].iter().any(|m| ua.contains(m)) {
+            DeviceClass::Mobile
+        } else {
+            DeviceClass::Desktop
+        }
+    }
+
+    /// 実行中のブラウザから推定
+    pub fn detect_current() -> Self {
+        let Some(navigator) = web_sys::window().map(|w| w.navigator()) else {
+            return DeviceClass::Desktop;
+        };
+        let user_agent = navigator.user_agent().unwrap_or_default();
+        // deviceMemoryはChrome系のみ
+        let device_memory = js_sys::Reflect::get(&navigator, &"deviceMemory".into())
+            .ok()
+            .and_then(|v| v.as_f64());
+        Self::detect(&user_agent, Some(navigator.hardware_concurrency()), device_memory)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PerformanceScore {
+    Excellent,
+    Good,
+    Fair,
+    Poor,
+}
+
+impl PerformanceScore {
+    pub fn color(&self) -> &'static str {
+        match self {
+            Self::Excellent => "#4CAF50",
+            Self::Good => "#8BC34A",
+            Self::Fair => "#FF9800",
+            Self::Poor => "#f44336",
+        }
+    }
+
+    pub fn text(&self) -> &'static str {
+        match self {
+            Self::Excellent => "優秀",
+            Self::Good => "良好",
+            Self::Fair => "可",
+            Self::Poor => "要改善",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Excellent => "🔥",
+            Self::Good => "✅",
+            Self::Fair => "⚠️",
+            Self::Poor => "🆘",
+        }
+    }
+}
+
+/// 各段階の下限（目標FPSに対する比率）
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreTiers {
+    pub excellent: f64,
+    pub good: f64,
+    pub fair: f64,
+}
+
+impl Default for ScoreTiers {
+    fn default() -> Self {
+        // 60fps基準で 55 / 45 / 30
+        Self {
+            excellent: 55.0 / 60.0,
+            good: 0.75,
+            fair: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoringConfig {
+    pub device_class: DeviceClass,
+    /// 未指定なら端末クラスの目標FPS
+    pub target_fps: Option<f64>,
+    pub tiers: ScoreTiers,
+    /// 平均メモリがこれを超えたら推奨事項に含める（MB）
+    pub high_memory_mb: f64,
+}
+
+impl ScoringConfig {
+    pub fn for_device(device_class: DeviceClass) -> Self {
+        Self {
+            device_class,
+            target_fps: None,
+            tiers: ScoreTiers::default(),
+            high_memory_mb: 100.0,
+        }
+    }
+
+    /// 保存済みの設定、なければ端末クラスを推定した既定値
+    pub fn load() -> Self {
+        load_item(SCORING_STORAGE_KEY)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(|| Self::for_device(DeviceClass::detect_current()))
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string(self) {
+            save_item(SCORING_STORAGE_KEY, &json);
+        }
+    }
+
+    pub fn target_fps(&self) -> f64 {
+        self.target_fps.unwrap_or_else(|| self.device_class.target_fps())
+    }
+
+    /// 各段階の下限FPS（優秀・良好・可）
+    pub fn thresholds(&self) -> [f64; 3] {
+        let target = self.target_fps();
+        [target * self.tiers.excellent, target * self.tiers.good, target * self.tiers.fair]
+    }
+
+    pub fn score(&self, fps: f64) -> PerformanceScore {
+        let [excellent, good, fair] = self.thresholds();
+        match fps {
+            f if f >= excellent => PerformanceScore::Excellent,
+            f if f >= good => PerformanceScore::Good,
+            f if f >= fair => PerformanceScore::Fair,
+            _ => PerformanceScore::Poor,
+        }
+    }
+
+    pub fn recommendations(&self, metrics: &BenchmarkMetrics) -> Vec<String> {
+        let target = self.target_fps();
+        let [_, _, fair] = self.thresholds();
+        let mut recs = Vec::new();
+
+        if metrics.avg_fps < fair {
+            recs.push(format!(
+                "平均FPSが目標（{:.0}fps）の{:.0}%（{:.0}fps）を下回っています。オブジェクト数を減らすか、Canvas/WebGLモードを検討してください",
+                target,
+                self.tiers.fair * 100.0,
+                fair
+            ));
+        }
+
+        // 目標FPSの1/3以上の変動（60fpsなら20fps）
+        if metrics.max_fps - metrics.min_fps > target / 3.0 {
+            recs.push("FPSの変動が大きいです。処理の最適化を検討してください".to_string());
+        }
+
+        if metrics.frame_stats.frame_count > 0 && metrics.frame_stats.p99_ms > 1000.0 / fair {
+            recs.push(format!(
+                "P99フレーム時間が{:.1}msです。長時間タスクのタイムラインで原因を確認してください",
+                metrics.frame_stats.p99_ms
+            ));
+        }
+
+        if metrics.avg_memory_mb().is_some_and(|avg| avg > self.high_memory_mb) {
+            recs.push("メモリ使用量が高いです。不要なオブジェクトの削除を検討してください".to_string());
+        }
+
+        if metrics.allocations.is_some_and(|a| !a.is_allocation_free()) {
+            recs.push("フレーム中の割り当てがあります。アニメーション処理での確保を見直してください".to_string());
+        }
+
+        if recs.is_empty() {
+            recs.push("パフォーマンスは良好です！".to_string());
+        }
+
+        recs
+    }
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self::for_device(DeviceClass::Desktop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiers_scale_with_device_target() {
+        let desktop = ScoringConfig::for_device(DeviceClass::Desktop);
+        assert_eq!(desktop.score(56.0), PerformanceScore::Excellent);
+        assert_eq!(desktop.score(50.0), PerformanceScore::Good);
+        assert_eq!(desktop.score(30.0), PerformanceScore::Fair);
+        assert_eq!(desktop.score(29.0), PerformanceScore::Poor);
+
+        // 低性能端末では30fpsが目標
+        let low_end = ScoringConfig::for_device(DeviceClass::LowEnd);
+        assert_eq!(low_end.score(29.0), PerformanceScore::Excellent);
+
+        let high_refresh = ScoringConfig::for_device(DeviceClass::HighRefresh);
+        assert_eq!(high_refresh.score(60.0), PerformanceScore::Fair);
+    }
+
+    #[test]
+    fn detects_device_class_from_navigator_hints() {
+        let iphone = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X)";
+        assert_eq!(DeviceClass::detect(iphone, Some(6.0), None), DeviceClass::Mobile);
+        assert_eq!(DeviceClass::detect("Mozilla/5.0 (X11; Linux x86_64)", Some(8.0), Some(8.0)), DeviceClass::Desktop);
+        assert_eq!(DeviceClass::detect("Mozilla/5.0 (X11; Linux x86_64)", Some(2.0), None), DeviceClass::LowEnd);
+    }
+
+    #[test]
+    fn recommendations_follow_thresholds() {
+        let config = ScoringConfig::for_device(DeviceClass::Desktop);
+        let mut metrics = BenchmarkMetrics::default();
+        for fps in [58.0, 60.0, 59.0] {
+            metrics.update_fps(fps);
+        }
+        metrics.record_memory(40.0);
+        assert_eq!(config.recommendations(&metrics), vec!["パフォーマンスは良好です！".to_string()]);
+
+        // 平均が30fpsを割り、変動とメモリも閾値を超える
+        for _ in 0..4 {
+            metrics.update_fps(5.0);
+        }
+        metrics.record_memory(400.0);
+        let recs = config.recommendations(&metrics);
+        assert_eq!(recs.len(), 3, "{recs:?}");
+        assert!(recs.iter().any(|r| r.contains("メモリ")));
+        assert!(recs[0].contains("目標（60fps）の50%（30fps）"), "{recs:?}");
+
+        // 「可」の下限を変えると文言も追従する
+        let strict = ScoringConfig { tiers: ScoreTiers { fair: 0.75, ..ScoreTiers::default() }, ..config };
+        assert!(strict.recommendations(&metrics)[0].contains("目標（60fps）の75%（45fps）"));
+    }
+}
@@ -0,0 +1,325 @@
+//! カオスイベントを実行中のマップシーンへ適用する
+//!
+//! どのイベントがいつまで有効かは `ChaosEffectState` が管理し、DOMやJSのグローバルへの反映は
+//! `ChaosEffectExecutor` が行う。
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(inline_js = r#"
+const glitched = new Map();
+
+function restore(el) {
+    const saved = glitched.get(el);
+    if (!saved) return;
+    clearTimeout(saved.timer);
+    el.style.transform = saved.transform;
+    el.style.opacity = saved.opacity;
+    el.style.filter = saved.filter;
+    glitched.delete(el);
+}
+
+export function glitchElement(selector, key, severity, durationMs) {
+    const targets = document.querySelectorAll(selector);
+    if (targets.length === 0) return false;
+    const el = targets[key % targets.length];
+    if (!glitched.has(el)) {
+        glitched.set(el, { transform: el.style.transform, opacity: el.style.opacity, filter: el.style.filter });
+    }
+    const saved = glitched.get(el);
+    clearTimeout(saved.timer);
+    const shift = (Math.random() - 0.5) * 20 * severity;
+    const skew = (Math.random() - 0.5) * 30 * severity;
+    el.style.transform = `${saved.transform} translate(${shift}px, ${-shift / 2}px) skewX(${skew}deg)`;
+    el.style.opacity = String(1 - 0.7 * severity);
+    el.style.filter = `hue-rotate(${Math.round(360 * severity)}deg)`;
+    saved.timer = setTimeout(() => restore(el), durationMs);
+    return true;
+}
+
+export function restoreAllGlitches() {
+    Array.from(glitched.keys()).forEach(restore);
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = glitchElement)]
+    fn glitch_element(selector: &str, key: u32, severity: f32, duration_ms: f64) -> bool;
+
+    #[wasm_bindgen(js_name = restoreAllGlitches)]
+    fn restore_all_glitches();
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChaosEvent {
+    UIGlitch { element_id: String, severity: f32 },
+    InputCorruption { delay_ms: u32, noise: f32 },
+    VisualDistortion { distortion_type: String },
+    TimeWarp { speed_multiplier: f32 },
+}
+
+/// 描画の歪め方
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distortion {
+    Glitch,
+    Blur,
+    Wave,
+    Invert,
+}
+
+impl Distortion {
+    pub const ALL: [Distortion; 4] = [Distortion::Glitch, Distortion::Blur, Distortion::Wave, Distortion::Invert];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distortion::Glitch => "glitch",
+            Distortion::Blur => "blur",
+            Distortion::Wave => "wave",
+            Distortion::Invert => "invert",
+        }
+    }
+
+    /// 未知の種類は `Glitch` として扱う
+    pub fn from_name(name: &str) -> Self {
+        Self::ALL.into_iter().find(|d| d.name() == name).unwrap_or(Distortion::Glitch)
+    }
+
+    /// シーンのコンテナに付けるCSS
+    pub fn css(&self) -> &'static str {
+        match self {
+            Distortion::Glitch => "filter: hue-rotate(90deg) contrast(1.6); transform: translateX(3px);",
+            Distortion::Blur => "filter: blur(3px);",
+            Distortion::Wave => "transform: skewX(6deg) scaleY(0.96);",
+            Distortion::Invert => "filter: invert(1);",
+        }
+    }
+}
+
+/// 各効果の持続時間と同時適用数の上限
+#[derive(Clone, Debug, PartialEq)]
+pub struct EffectConfig {
+    pub glitch_ms: f64,
+    pub input_ms: f64,
+    pub distortion_ms: f64,
+    pub time_warp_ms: f64,
+    /// 同時にグリッチさせる要素数の上限（大量イベント時にDOM更新が爆発しないように）
+    pub max_glitches: usize,
+}
+
+impl Default for EffectConfig {
+    fn default() -> Self {
+        Self {
+            glitch_ms: 400.0,
+            input_ms: 3000.0,
+            distortion_ms: 800.0,
+            time_warp_ms: 2000.0,
+            max_glitches: 32,
+        }
+    }
+}
+
+/// グリッチの適用指示
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlitchCommand {
+    /// 対象要素の選択に使うキー（要素IDのハッシュ）
+    pub key: u32,
+    pub severity: f32,
+    pub duration_ms: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct InputEffect {
+    delay_ms: f64,
+    noise: f64,
+    until_ms: f64,
+}
+
+/// 現在有効なカオス効果
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChaosEffectState {
+    config: EffectConfig,
+    glitch_until: Vec<f64>,
+    input: Option<InputEffect>,
+    distortion: Option<(Distortion, f64)>,
+    time_warp: Option<(f64, f64)>,
+}
+
+impl ChaosEffectState {
+    pub fn new(config: EffectConfig) -> Self {
+        Self {
+            config,
+            glitch_until: Vec::new(),
+            input: None,
+            distortion: None,
+            time_warp: None,
+        }
+    }
+
+    /// イベントを適用する。DOM要素に反映すべきグリッチがあれば返す
+    pub fn apply(&mut self, event: &ChaosEvent, now_ms: f64) -> Option<GlitchCommand> {
+        match event {
+            ChaosEvent::UIGlitch { element_id, severity } => {
+                if self.glitch_until.len() >= self.config.max_glitches {
+                    return None;
+                }
+                self.glitch_until.push(now_ms + self.config.glitch_ms);
+                Some(GlitchCommand {
+                    key: element_key(element_id),
+                    severity: severity.clamp(0.0, 1.0),
+                    duration_ms: self.config.glitch_ms,
+                })
+            }
+            ChaosEvent::InputCorruption { delay_ms, noise } => {
+                self.input = Some(InputEffect {
+                    delay_ms: *delay_ms as f64,
+                    noise: noise.clamp(0.0, 1.0) as f64,
+                    until_ms: now_ms + self.config.input_ms,
+                });
+                None
+            }
+            ChaosEvent::VisualDistortion { distortion_type } => {
+                self.distortion = Some((Distortion::from_name(distortion_type), now_ms + self.config.distortion_ms));
+                None
+            }
+            ChaosEvent::TimeWarp { speed_multiplier } => {
+                // 重ねがけで発散しないよう最新の倍率で置き換える
+                self.time_warp = Some((speed_multiplier.max(0.0) as f64, now_ms + self.config.time_warp_ms));
+                None
+            }
+        }
+    }
+
+    /// 期限切れの効果を外す
+    pub fn expire(&mut self, now_ms: f64) {
+        self.glitch_until.retain(|&until| until > now_ms);
+        if self.input.as_ref().is_some_and(|i| i.until_ms <= now_ms) {
+            self.input = None;
+        }
+        if self.distortion.is_some_and(|(_, until)| until <= now_ms) {
+            self.distortion = None;
+        }
+        if self.time_warp.is_some_and(|(_, until)| until <= now_ms) {
+            self.time_warp = None;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new(self.config.clone());
+    }
+
+    pub fn active_glitches(&self) -> usize {
+        self.glitch_until.len()
+    }
+
+    /// シミュレーション時間の倍率（効果なしなら1）
+    pub fn time_scale(&self) -> f64 {
+        self.time_warp.map(|(scale, _)| scale).unwrap_or(1.0)
+    }
+
+    pub fn distortion(&self) -> Option<Distortion> {
+        self.distortion.map(|(d, _)| d)
+    }
+
+    /// 入力処理を遅らせる時間。`random` は0..1の一様乱数で、遅延の±noise倍だけ揺らす
+    pub fn input_delay_ms(&self, random: f64) -> f64 {
+        match &self.input {
+            Some(input) => (input.delay_ms * (1.0 + input.noise * (random * 2.0 - 1.0))).max(0.0),
+            None => 0.0,
+        }
+    }
+}
+
+/// 要素IDから対象選択用のキーを作る（FNV-1a）
+fn element_key(element_id: &str) -> u32 {
+    element_id
+        .bytes()
+        .fold(0x811c_9dc5u32, |hash, b| (hash ^ b as u32).wrapping_mul(0x0100_0193))
+}
+
+/// 効果をシーンに反映する。ドロップ時にすべて元に戻す
+pub struct ChaosEffectExecutor {
+    /// グリッチ対象を選ぶCSSセレクタ
+    selector: String,
+    time_scale: f64,
+}
+
+impl ChaosEffectExecutor {
+    pub fn new(selector: impl Into<String>) -> Self {
+        set_time_scale(1.0);
+        Self { selector: selector.into(), time_scale: 1.0 }
+    }
+
+    pub fn glitch(&self, command: &GlitchCommand) {
+        glitch_element(&self.selector, command.key, command.severity, command.duration_ms);
+    }
+
+    /// 時間倍率をマップのアニメーションループへ反映する（変化時のみ）
+    pub fn sync(&mut self, state: &ChaosEffectState) {
+        let scale = state.time_scale();
+        if scale != self.time_scale {
+            set_time_scale(scale);
+            self.time_scale = scale;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        restore_all_glitches();
+        set_time_scale(1.0);
+        self.time_scale = 1.0;
+    }
+}
+
+impl Drop for ChaosEffectExecutor {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+/// マップのJSコードが参照する時間倍率
+fn set_time_scale(scale: f64) {
+    if let Some(window) = web_sys::window() {
+        let _ = js_sys::Reflect::set(&window, &"__chaosTimeScale".into(), &scale.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_expire_and_glitches_are_capped() {
+        let mut state = ChaosEffectState::new(EffectConfig { max_glitches: 2, ..Default::default() });
+        let glitch = ChaosEvent::UIGlitch { element_id: "chaos-element-7".to_string(), severity: 1.5 };
+
+        let command = state.apply(&glitch, 0.0).unwrap();
+        assert_eq!(command.severity, 1.0);
+        assert_eq!(command.key, element_key("chaos-element-7"));
+        assert!(state.apply(&glitch, 0.0).is_some());
+        assert!(state.apply(&glitch, 0.0).is_none());
+
+        state.apply(&ChaosEvent::TimeWarp { speed_multiplier: 2.0 }, 0.0);
+        state.apply(&ChaosEvent::TimeWarp { speed_multiplier: 3.0 }, 100.0);
+        state.apply(&ChaosEvent::VisualDistortion { distortion_type: "blur".to_string() }, 0.0);
+        assert_eq!(state.time_scale(), 3.0);
+        assert_eq!(state.distortion(), Some(Distortion::Blur));
+
+        state.expire(1000.0);
+        assert_eq!(state.active_glitches(), 0);
+        assert_eq!(state.distortion(), None);
+        assert_eq!(state.time_scale(), 3.0);
+        state.expire(2100.0);
+        assert_eq!(state.time_scale(), 1.0);
+    }
+
+    #[test]
+    fn input_delay_is_jittered_within_noise() {
+        let mut state = ChaosEffectState::new(EffectConfig::default());
+        assert_eq!(state.input_delay_ms(0.9), 0.0);
+
+        state.apply(&ChaosEvent::InputCorruption { delay_ms: 100, noise: 0.5 }, 0.0);
+        assert_eq!(state.input_delay_ms(0.0), 50.0);
+        assert_eq!(state.input_delay_ms(0.5), 100.0);
+        assert_eq!(state.input_delay_ms(1.0), 150.0);
+
+        state.expire(3000.0);
+        assert_eq!(state.input_delay_ms(1.0), 0.0);
+    }
+}
@@ -0,0 +1,167 @@
+//! マップ上のカオスイベントがFPSとイベント処理遅延に与える影響の集計
+//!
+//! イベント発生から一定時間内のフレームはそのイベント種別に、それ以外はベースラインに振り分ける。
+
+use std::collections::HashMap;
+use crate::utils::chaos_intensity::ChaosEventType;
+use crate::utils::histogram::LogHistogram;
+use crate::utils::input_latency::LatencyStats;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct FrameSum {
+    frames: usize,
+    total_ms: f64,
+}
+
+impl FrameSum {
+    fn add(&mut self, frame_ms: f64) {
+        self.frames += 1;
+        self.total_ms += frame_ms;
+    }
+
+    fn fps(&self) -> Option<f64> {
+        (self.frames > 0 && self.total_ms > 0.0).then(|| self.frames as f64 * 1000.0 / self.total_ms)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct TypeImpact {
+    events: usize,
+    frames: FrameSum,
+    /// 実行中の全件（メモリは件数によらず一定）
+    latencies: LogHistogram,
+}
+
+/// イベント種別ごとの影響
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventImpact {
+    pub event_type: ChaosEventType,
+    pub events: usize,
+    /// イベント直後の平均FPS（該当フレームがなければ `None`）
+    pub fps: Option<f64>,
+    /// ベースラインからのFPS低下率（%）
+    pub fps_drop_percent: Option<f64>,
+    /// イベント適用から次のフレームまでの遅延
+    pub latency: LatencyStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChaosImpactTracker {
+    /// イベントの影響とみなす期間（ms）
+    window_ms: f64,
+    last_event: Option<(ChaosEventType, f64)>,
+    /// 次のフレームを待っているイベント
+    pending: Vec<(ChaosEventType, f64)>,
+    baseline: FrameSum,
+    by_type: HashMap<ChaosEventType, TypeImpact>,
+}
+
+impl ChaosImpactTracker {
+    pub fn new(window_ms: f64) -> Self {
+        Self {
+            window_ms,
+            last_event: None,
+            pending: Vec::new(),
+            baseline: FrameSum::default(),
+            by_type: HashMap::new(),
+        }
+    }
+
+    pub fn on_event(&mut self, event_type: ChaosEventType, at_ms: f64) {
+        self.by_type.entry(event_type.clone()).or_default().events += 1;
+        self.pending.push((event_type.clone(), at_ms));
+        self.last_event = Some((event_type, at_ms));
+    }
+
+    /// 今回のフレームで確定した (イベント時刻, 遅延) を返す
+    pub fn on_frame(&mut self, frame_ms: f64, timestamp: f64) -> Vec<(f64, f64)> {
+        let mut resolved = Vec::with_capacity(self.pending.len());
+        for (event_type, at_ms) in self.pending.drain(..) {
+            let latency = (timestamp - at_ms).max(0.0);
+            self.by_type.entry(event_type).or_default().latencies.record(latency);
+            resolved.push((at_ms, latency));
+        }
+
+        match &self.last_event {
+            Some((event_type, at_ms)) if timestamp - at_ms < self.window_ms => {
+                self.by_type.entry(event_type.clone()).or_default().frames.add(frame_ms);
+            }
+            _ => self.baseline.add(frame_ms),
+        }
+        resolved
+    }
+
+    pub fn baseline_fps(&self) -> Option<f64> {
+        self.baseline.fps()
+    }
+
+    /// 発生したイベント種別の影響（`ChaosEventType::ALL` の順）
+    pub fn report(&self) -> Vec<EventImpact> {
+        let baseline = self.baseline_fps();
+        ChaosEventType::ALL
+            .iter()
+            .filter_map(|event_type| {
+                let impact = self.by_type.get(event_type)?;
+                let fps = impact.frames.fps();
+                Some(EventImpact {
+                    event_type: event_type.clone(),
+                    events: impact.events,
+                    fps,
+                    fps_drop_percent: baseline.zip(fps).map(|(base, fps)| (base - fps) / base * 100.0),
+                    latency: LatencyStats::from_histogram(&impact.latencies),
+                })
+            })
+            .collect()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.window_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_after_an_event_are_attributed_to_its_type() {
+        let mut tracker = ChaosImpactTracker::new(500.0);
+        // ベースライン: 60fps
+        for i in 1..=10 {
+            tracker.on_frame(1000.0 / 60.0, i as f64 * 16.0);
+        }
+        tracker.on_event(ChaosEventType::MemoryPressure, 200.0);
+        // 直後の2フレームは30fps相当、ウィンドウ外は再びベースライン
+        tracker.on_frame(1000.0 / 30.0, 240.0);
+        tracker.on_frame(1000.0 / 30.0, 280.0);
+        tracker.on_frame(1000.0 / 60.0, 800.0);
+
+        let report = tracker.report();
+        assert_eq!(report.len(), 1);
+        let impact = &report[0];
+        assert_eq!(impact.event_type, ChaosEventType::MemoryPressure);
+        assert_eq!(impact.events, 1);
+        assert!((impact.fps.unwrap() - 30.0).abs() < 1e-9);
+        assert!((impact.fps_drop_percent.unwrap() - 50.0).abs() < 1e-9);
+        assert_eq!(impact.latency.count, 1);
+        assert_eq!(impact.latency.max, 40.0);
+        assert!((tracker.baseline_fps().unwrap() - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn report_follows_declaration_order_and_reset_clears() {
+        let mut tracker = ChaosImpactTracker::new(500.0);
+        tracker.on_event(ChaosEventType::ModeSwitch, 0.0);
+        tracker.on_event(ChaosEventType::ObjectSpawn, 10.0);
+        tracker.on_frame(16.0, 20.0);
+
+        let types: Vec<_> = tracker.report().into_iter().map(|i| i.event_type).collect();
+        assert_eq!(types, vec![ChaosEventType::ObjectSpawn, ChaosEventType::ModeSwitch]);
+        // 最後のイベント種別にのみフレームが振り分けられる
+        assert_eq!(tracker.report()[1].fps, None);
+        assert_eq!(tracker.baseline_fps(), None);
+
+        tracker.reset();
+        assert!(tracker.report().is_empty());
+    }
+}
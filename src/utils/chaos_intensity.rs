@@ -0,0 +1,205 @@
+//! カオス強度（0〜10）の定義
+//!
+//! `/chaos/:intensity` とマップのカオスモードで同じ段階を使い、段階ごとに発生率・イベントの構成比・
+//! 1件あたりの大きさ（深刻度）を決める。
+
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// `/chaos/:intensity` のイベント種別（`ChaosEvent` の宣言順）
+pub const UI_EVENT_KINDS: [&str; 4] = ["UI破壊", "入力遅延", "視覚歪曲", "時間歪曲"];
+
+/// マップのカオスモードのイベント種別
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChaosEventType {
+    ObjectSpawn,
+    ObjectRemove,
+    ViewportChange,
+    ModeSwitch,
+    MemoryPressure,
+}
+
+impl ChaosEventType {
+    pub const ALL: [ChaosEventType; 5] = [
+        ChaosEventType::ObjectSpawn,
+        ChaosEventType::ObjectRemove,
+        ChaosEventType::ViewportChange,
+        ChaosEventType::ModeSwitch,
+        ChaosEventType::MemoryPressure,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChaosEventType::ObjectSpawn => "マーカー追加",
+            ChaosEventType::ObjectRemove => "マーカー削除",
+            ChaosEventType::ViewportChange => "パン/ズーム",
+            ChaosEventType::ModeSwitch => "モード切替",
+            ChaosEventType::MemoryPressure => "メモリ圧迫",
+        }
+    }
+}
+
+/// 構成比の1項目（種別と重み）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventWeight {
+    pub event: ChaosEventType,
+    pub weight: f64,
+}
+
+/// 段階ごとの平均発生率（件/秒）
+const EVENTS_PER_SECOND: [f64; 11] = [0.0, 0.5, 1.0, 2.0, 3.0, 5.0, 8.0, 12.0, 20.0, 30.0, 50.0];
+
+/// 構成比の帯（強度の下限, 名前, マップの重み, 画面の重み）
+/// マップの重みは `ChaosEventType::ALL`、画面の重みは `UI_EVENT_KINDS` の順
+const BANDS: [(u8, &str, [f64; 5], [f64; 4]); 5] = [
+    (0, "停止", [0.0; 5], [0.0; 4]),
+    (1, "軽微", [1.0, 1.0, 2.0, 0.0, 0.0], [3.0, 0.0, 1.0, 0.0]),
+    (4, "中程度", [2.0, 2.0, 2.0, 1.0, 0.0], [2.0, 1.0, 2.0, 1.0]),
+    (7, "強", [2.0, 2.0, 1.0, 1.0, 1.0], [1.0, 1.0, 1.0, 1.0]),
+    (10, "極限", [2.0, 1.0, 1.0, 2.0, 2.0], [1.0, 2.0, 1.0, 2.0]),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChaosIntensity(u8);
+
+impl ChaosIntensity {
+    pub const MAX: u8 = 10;
+
+    pub fn new(level: u8) -> Result<Self, String> {
+        if level > Self::MAX {
+            return Err(format!("intensity must be 0..={}, got {}", Self::MAX, level));
+        }
+        Ok(Self(level))
+    }
+
+    /// 範囲外は上限に丸める（スライダーやシナリオの値用）
+    pub fn saturating(level: u8) -> Self {
+        Self(level.min(Self::MAX))
+    }
+
+    /// URLのパラメータから読む
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let level: u8 = value
+            .trim()
+            .parse()
+            .map_err(|_| format!("intensity must be an integer 0..={}, got {:?}", Self::MAX, value))?;
+        Self::new(level)
+    }
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..=Self::MAX).map(Self)
+    }
+
+    /// 0〜10を順に巡る
+    pub fn next(self) -> Self {
+        Self((self.0 + 1) % (Self::MAX + 1))
+    }
+
+    pub fn profile(self) -> IntensityProfile {
+        let (_, label, map_weights, ui_weights) = BANDS.iter().rev().find(|(min, ..)| self.0 >= *min).copied().unwrap_or(BANDS[0]);
+        IntensityProfile {
+            intensity: self,
+            label,
+            events_per_second: EVENTS_PER_SECOND[self.0 as usize],
+            severity: self.0 as f64 / Self::MAX as f64,
+            map_weights,
+            ui_weights,
+        }
+    }
+}
+
+impl fmt::Display for ChaosIntensity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// 強度1段階の中身
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IntensityProfile {
+    pub intensity: ChaosIntensity,
+    pub label: &'static str,
+    pub events_per_second: f64,
+    /// 1件あたりの大きさ（0〜1）。マーカー数・確保量・遅延・倍率にかける
+    pub severity: f64,
+    map_weights: [f64; 5],
+    ui_weights: [f64; 4],
+}
+
+impl IntensityProfile {
+    /// マップのイベントの構成比（重み0の種別は含まない）
+    pub fn map_mix(&self) -> Vec<EventWeight> {
+        ChaosEventType::ALL
+            .iter()
+            .zip(self.map_weights)
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|(event, weight)| EventWeight { event: event.clone(), weight })
+            .collect()
+    }
+
+    /// `UI_EVENT_KINDS` の添字を `roll`（0〜1）で重みに従って選ぶ
+    pub fn pick_ui_event(&self, roll: f64) -> usize {
+        let total: f64 = self.ui_weights.iter().sum();
+        let mut target = roll * total;
+        for (index, weight) in self.ui_weights.iter().enumerate() {
+            if target < *weight {
+                return index;
+            }
+            target -= weight;
+        }
+        self.ui_weights.iter().rposition(|w| *w > 0.0).unwrap_or(0)
+    }
+
+    /// 凡例に出す構成比の説明
+    pub fn mix_description(&self) -> String {
+        let map: Vec<&str> = self.map_mix().iter().map(|w| w.event.label()).collect();
+        let ui: Vec<&str> = UI_EVENT_KINDS
+            .iter()
+            .zip(self.ui_weights)
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|(kind, _)| *kind)
+            .collect();
+        if map.is_empty() {
+            return "イベントなし".to_string();
+        }
+        format!("マップ: {} / 画面: {}", map.join("・"), ui.join("・"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_only_levels_zero_to_ten() {
+        assert_eq!(ChaosIntensity::parse("0"), Ok(ChaosIntensity(0)));
+        assert_eq!(ChaosIntensity::parse(" 10"), Ok(ChaosIntensity(10)));
+        assert!(ChaosIntensity::parse("11").unwrap_err().contains("0..=10"));
+        assert!(ChaosIntensity::parse("255").is_err());
+        assert!(ChaosIntensity::parse("-1").is_err());
+        assert!(ChaosIntensity::parse("abc").is_err());
+        assert_eq!(ChaosIntensity::saturating(200), ChaosIntensity(10));
+        assert_eq!(ChaosIntensity::saturating(10).next(), ChaosIntensity(0));
+    }
+
+    #[test]
+    fn every_level_has_a_defined_rate_mix_and_severity() {
+        let profiles: Vec<_> = ChaosIntensity::all().map(|i| i.profile()).collect();
+        assert_eq!(profiles.len(), 11);
+        assert_eq!(profiles[0].events_per_second, 0.0);
+        assert!(profiles[0].map_mix().is_empty());
+        assert!(profiles.windows(2).all(|w| w[1].events_per_second > w[0].events_per_second));
+        assert!(profiles.windows(2).all(|w| w[1].severity > w[0].severity));
+        for profile in &profiles[1..] {
+            assert!(!profile.map_mix().is_empty());
+            assert!(profile.ui_weights.iter().sum::<f64>() > 0.0);
+        }
+        // 重いイベント（メモリ圧迫）は強い段階にだけ含まれる
+        let has_pressure = |p: &IntensityProfile| p.map_mix().iter().any(|w| w.event == ChaosEventType::MemoryPressure);
+        assert!(!has_pressure(&profiles[6]));
+        assert!(has_pressure(&profiles[7]));
+        // 重み0の種別は選ばれない
+        assert!((0..100).all(|i| profiles[1].pick_ui_event(i as f64 / 100.0) != 1));
+    }
+}
@@ -0,0 +1,517 @@
+//! カオス実行後のレポート
+//!
+//! イベントログをフレーム時間・遅延と時刻で突き合わせ、どの種別のイベントの直後に
+//! フレーム落ちや遅延スパイクが起きたか、バースト後の回復に何msかかったかを集計する。
+
+use serde::{Deserialize, Serialize};
+use crate::utils::frame_stats::{FrameTimeHistogram, FrameTimeStats, FRAME_BUDGET_30FPS_MS, FRAME_BUDGET_60FPS_MS};
+use crate::utils::input_latency::LatencyStats;
+use crate::utils::histogram::{HistogramSummary, LogHistogram};
+
+/// 記録するフレーム数の上限（60fpsで約1時間）
+const FRAME_LIMIT: usize = 216_000;
+/// 記録するイベント・遅延の件数の上限
+const EVENT_LIMIT: usize = 100_000;
+/// HTMLのグラフに描く最大点数
+const CHART_POINTS: usize = 600;
+/// イベント発生率を数える区間（ms）
+const RATE_WINDOW_MS: f64 = 1000.0;
+/// イベント発生率（件/秒）の分解能
+const RATE_RESOLUTION: f64 = 0.01;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportConfig {
+    /// フレーム落ちとみなすフレーム時間（ms）
+    pub drop_threshold_ms: f64,
+    /// 遅延スパイクとみなす遅延（ms）
+    pub latency_spike_ms: f64,
+    /// 落ち・スパイクの原因候補としてさかのぼる時間（ms）
+    pub lookback_ms: f64,
+    /// これより間隔が短いイベントを1つのバーストとみなす（ms）
+    pub burst_gap_ms: f64,
+    /// 回復とみなすフレーム時間（ms）
+    pub recovered_frame_ms: f64,
+    /// 回復とみなすのに必要な連続フレーム数
+    pub recovered_frames: usize,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            drop_threshold_ms: FRAME_BUDGET_30FPS_MS,
+            latency_spike_ms: 100.0,
+            lookback_ms: 500.0,
+            burst_gap_ms: 500.0,
+            recovered_frame_ms: FRAME_BUDGET_60FPS_MS * 1.25,
+            recovered_frames: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    /// 開始からの時刻（ms）
+    pub at_ms: f64,
+    pub kind: String,
+    pub description: String,
+}
+
+/// 実行中のイベント・フレーム時間・遅延を記録する
+#[derive(Debug, Clone, Default)]
+pub struct ChaosRunRecorder {
+    started_at: Option<f64>,
+    events: Vec<LoggedEvent>,
+    /// (開始からの時刻, フレーム時間)
+    frames: Vec<(f64, f64)>,
+    /// (開始からの時刻, 遅延)
+    latencies: Vec<(f64, f64)>,
+}
+
+impl ChaosRunRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self, now_ms: f64) {
+        *self = Self { started_at: Some(now_ms), ..Self::default() };
+    }
+
+    #[cfg(feature = "chaos-testing")]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.frames.is_empty()
+    }
+
+    fn elapsed(&self, at_ms: f64) -> Option<f64> {
+        self.started_at.map(|start| at_ms - start)
+    }
+
+    pub fn record_event(&mut self, at_ms: f64, kind: &str, description: &str) {
+        if let Some(at_ms) = self.elapsed(at_ms) {
+            push_capped(&mut self.events, LoggedEvent { at_ms, kind: kind.to_string(), description: description.to_string() }, EVENT_LIMIT);
+        }
+    }
+
+    pub fn record_frame(&mut self, frame_ms: f64, timestamp: f64) {
+        if let Some(at_ms) = self.elapsed(timestamp) {
+            push_capped(&mut self.frames, (at_ms, frame_ms), FRAME_LIMIT);
+        }
+    }
+
+    /// `at_ms` は入力（またはイベント）の発生時刻
+    pub fn record_latency(&mut self, at_ms: f64, latency_ms: f64) {
+        if let Some(at_ms) = self.elapsed(at_ms) {
+            push_capped(&mut self.latencies, (at_ms, latency_ms), EVENT_LIMIT);
+        }
+    }
+
+    pub fn build_report(&self, title: &str, config: &ReportConfig) -> ChaosRunReport {
+        let mut histogram = FrameTimeHistogram::new();
+        for &(at_ms, frame_ms) in &self.frames {
+            histogram.record(frame_ms, at_ms);
+        }
+        let latency_samples: Vec<f64> = self.latencies.iter().map(|&(_, ms)| ms).collect();
+
+        // 種別ごとの件数（EventStats::by_type と同じく件数の降順）
+        let mut by_type: Vec<KindImpact> = Vec::new();
+        for event in &self.events {
+            match by_type.iter_mut().find(|k| k.kind == event.kind) {
+                Some(kind) => kind.events += 1,
+                None => by_type.push(KindImpact { kind: event.kind.clone(), events: 1, ..Default::default() }),
+            }
+        }
+
+        // フレーム落ち・遅延スパイクの直前にあったイベント種別
+        let drops: Vec<f64> = self.frames.iter().filter(|(_, ms)| *ms > config.drop_threshold_ms).map(|(at, _)| *at).collect();
+        let spikes: Vec<f64> = self.latencies.iter().filter(|(_, ms)| *ms > config.latency_spike_ms).map(|(at, _)| *at).collect();
+        for (times, is_drop) in [(&drops, true), (&spikes, false)] {
+            for &at in times {
+                for kind in self.kinds_before(at, config.lookback_ms) {
+                    if let Some(impact) = by_type.iter_mut().find(|k| k.kind == kind) {
+                        if is_drop {
+                            impact.preceded_drops += 1;
+                        } else {
+                            impact.preceded_spikes += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        let bursts = self.bursts(config);
+        for impact in &mut by_type {
+            let recoveries: Vec<f64> = bursts
+                .iter()
+                .filter(|b| b.kinds.iter().any(|(kind, _)| *kind == impact.kind))
+                .filter_map(|b| b.recovery_ms)
+                .collect();
+            if !recoveries.is_empty() {
+                impact.avg_recovery_ms = Some(recoveries.iter().sum::<f64>() / recoveries.len() as f64);
+            }
+        }
+        by_type.sort_by(|a, b| b.events.cmp(&a.events).then_with(|| a.kind.cmp(&b.kind)));
+
+        let duration_ms = self.frames.last().map(|(at, _)| *at).unwrap_or(0.0);
+        ChaosRunReport {
+            title: title.to_string(),
+            config: config.clone(),
+            duration_ms,
+            total_events: self.events.len(),
+            event_rate: self.event_rate(duration_ms),
+            frame_stats: histogram.stats(),
+            latency: LatencyStats::from_samples(&latency_samples),
+            frame_drops: drops.len(),
+            latency_spikes: spikes.len(),
+            by_type,
+            bursts,
+            events: self.events.clone(),
+            frame_series: downsample(&self.frames, CHART_POINTS),
+        }
+    }
+
+    /// 1秒ごとのイベント発生率（件/秒）の分布
+    ///
+    /// 最後の1秒に満たない区間は、短い区間の1件で発生率が跳ね上がるため含めない。
+    fn event_rate(&self, duration_ms: f64) -> HistogramSummary {
+        let duration_ms = self.events.last().map_or(duration_ms, |e| duration_ms.max(e.at_ms));
+        let windows = (duration_ms / RATE_WINDOW_MS).floor() as usize;
+        if self.events.is_empty() || windows == 0 {
+            return HistogramSummary::default();
+        }
+        let mut counts = vec![0u32; windows];
+        for event in &self.events {
+            if let Some(count) = counts.get_mut((event.at_ms / RATE_WINDOW_MS) as usize) {
+                *count += 1;
+            }
+        }
+
+        let mut histogram = LogHistogram::new(RATE_RESOLUTION);
+        for count in counts {
+            histogram.record(count as f64 * 1000.0 / RATE_WINDOW_MS);
+        }
+        histogram.summary()
+    }
+
+    /// `at_ms` の直前 `lookback_ms` 以内に発生したイベント種別（重複なし）
+    fn kinds_before(&self, at_ms: f64, lookback_ms: f64) -> Vec<String> {
+        let start = self.events.partition_point(|e| e.at_ms < at_ms - lookback_ms);
+        let mut kinds: Vec<String> = Vec::new();
+        for event in self.events[start..].iter().take_while(|e| e.at_ms <= at_ms) {
+            if !kinds.contains(&event.kind) {
+                kinds.push(event.kind.clone());
+            }
+        }
+        kinds
+    }
+
+    fn bursts(&self, config: &ReportConfig) -> Vec<Burst> {
+        let mut bursts: Vec<Burst> = Vec::new();
+        for event in &self.events {
+            match bursts.last_mut() {
+                Some(burst) if event.at_ms - burst.end_ms <= config.burst_gap_ms => {
+                    burst.end_ms = event.at_ms;
+                    burst.add_kind(&event.kind);
+                }
+                _ => {
+                    let mut burst = Burst { start_ms: event.at_ms, end_ms: event.at_ms, ..Default::default() };
+                    burst.add_kind(&event.kind);
+                    bursts.push(burst);
+                }
+            }
+        }
+
+        for burst in &mut bursts {
+            let first = self.frames.partition_point(|(at, _)| *at < burst.start_ms);
+            let after = self.frames.partition_point(|(at, _)| *at <= burst.end_ms);
+            burst.worst_frame_ms = self.frames[first..after].iter().map(|(_, ms)| *ms).fold(0.0, f64::max);
+
+            // バースト終了後、回復フレームが所定数続いた最初の時点
+            let mut streak_start = None;
+            let mut streak = 0;
+            for &(at, ms) in &self.frames[after..] {
+                if ms <= config.recovered_frame_ms {
+                    streak_start.get_or_insert(at - ms);
+                    streak += 1;
+                    if streak >= config.recovered_frames {
+                        break;
+                    }
+                } else {
+                    burst.worst_frame_ms = burst.worst_frame_ms.max(ms);
+                    streak_start = None;
+                    streak = 0;
+                }
+            }
+            if streak >= config.recovered_frames {
+                burst.recovery_ms = streak_start.map(|at| (at - burst.end_ms).max(0.0));
+            }
+        }
+        bursts
+    }
+}
+
+fn push_capped<T>(items: &mut Vec<T>, item: T, limit: usize) {
+    if items.len() >= limit {
+        // 古い半分をまとめて捨てて償却O(1)にする
+        items.drain(0..limit / 2);
+    }
+    items.push(item);
+}
+
+/// 区間ごとの最大値を残して間引く（スパイクを消さないため）
+fn downsample(frames: &[(f64, f64)], points: usize) -> Vec<(f64, f64)> {
+    if frames.len() <= points {
+        return frames.to_vec();
+    }
+    let chunk = frames.len().div_ceil(points);
+    frames
+        .chunks(chunk)
+        .map(|c| c.iter().copied().fold((c[0].0, 0.0), |acc, (at, ms)| if ms > acc.1 { (at, ms) } else { acc }))
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KindImpact {
+    pub kind: String,
+    pub events: usize,
+    /// 直前にこの種別のイベントがあったフレーム落ちの数
+    pub preceded_drops: usize,
+    /// 直前にこの種別のイベントがあった遅延スパイクの数
+    pub preceded_spikes: usize,
+    /// この種別を含むバーストからの平均回復時間
+    pub avg_recovery_ms: Option<f64>,
+}
+
+/// 間隔の短いイベントのまとまり
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Burst {
+    pub start_ms: f64,
+    pub end_ms: f64,
+    /// 種別ごとの件数
+    pub kinds: Vec<(String, usize)>,
+    /// バースト中から回復までの最長フレーム
+    pub worst_frame_ms: f64,
+    /// バースト終了から回復までの時間（回復しなければ `None`）
+    pub recovery_ms: Option<f64>,
+}
+
+impl Burst {
+    fn add_kind(&mut self, kind: &str) {
+        match self.kinds.iter_mut().find(|(k, _)| k == kind) {
+            Some((_, count)) => *count += 1,
+            None => self.kinds.push((kind.to_string(), 1)),
+        }
+    }
+
+    pub fn event_count(&self) -> usize {
+        self.kinds.iter().map(|(_, n)| n).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaosRunReport {
+    pub title: String,
+    pub config: ReportConfig,
+    pub duration_ms: f64,
+    pub total_events: usize,
+    /// 1秒ごとのイベント発生率（件/秒）
+    pub event_rate: HistogramSummary,
+    pub frame_stats: FrameTimeStats,
+    pub latency: LatencyStats,
+    pub frame_drops: usize,
+    pub latency_spikes: usize,
+    /// 件数の降順
+    pub by_type: Vec<KindImpact>,
+    pub bursts: Vec<Burst>,
+    pub events: Vec<LoggedEvent>,
+    /// グラフ用に間引いた (時刻, フレーム時間)
+    pub frame_series: Vec<(f64, f64)>,
+}
+
+impl ChaosRunReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// 単体で開けるHTML（フレーム時間のグラフにイベントを重ねる）
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str(&format!(
+            "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>body{{font-family:sans-serif;margin:2rem;}}table{{border-collapse:collapse;margin:1rem 0;}}\
+             th,td{{border:1px solid #ccc;padding:4px 8px;text-align:right;}}th:first-child,td:first-child{{text-align:left;}}</style>\n\
+             </head>\n<body>\n<h1>{title}</h1>\n",
+            title = escape_html(&self.title)
+        ));
+        html.push_str(&format!(
+            "<p>{:.1}秒 / イベント{}件 / フレーム落ち{}回（&gt;{:.0}ms） / 遅延スパイク{}回（&gt;{:.0}ms）</p>\n",
+            self.duration_ms / 1000.0,
+            self.total_events,
+            self.frame_drops,
+            self.config.drop_threshold_ms,
+            self.latency_spikes,
+            self.config.latency_spike_ms
+        ));
+        html.push_str(&format!(
+            "<p>フレーム時間 P50 {:.1}ms / P95 {:.1}ms / 最大 {:.1}ms、遅延 P50 {:.0}ms / P95 {:.0}ms / 最大 {:.0}ms（n={}）</p>\n",
+            self.frame_stats.p50_ms,
+            self.frame_stats.p95_ms,
+            self.frame_stats.max_ms,
+            self.latency.p50,
+            self.latency.p95,
+            self.latency.max,
+            self.latency.count
+        ));
+        html.push_str(&format!(
+            "<p>イベント発生率 P50 {:.1}件/秒 / P95 {:.1}件/秒 / 最大 {:.1}件/秒</p>\n",
+            self.event_rate.p50,
+            self.event_rate.p95,
+            self.event_rate.max
+        ));
+        html.push_str(&self.chart_svg());
+
+        html.push_str("<h2>種別ごとの影響</h2>\n<table>\n<tr><th>種別</th><th>件数</th><th>直後のフレーム落ち</th><th>直後の遅延スパイク</th><th>平均回復時間</th></tr>\n");
+        for kind in &self.by_type {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&kind.kind),
+                kind.events,
+                kind.preceded_drops,
+                kind.preceded_spikes,
+                format_recovery(kind.avg_recovery_ms)
+            ));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>バースト</h2>\n<table>\n<tr><th>区間</th><th>イベント</th><th>最長フレーム</th><th>回復時間</th></tr>\n");
+        for burst in &self.bursts {
+            let kinds: Vec<String> = burst.kinds.iter().map(|(k, n)| format!("{}×{}", escape_html(k), n)).collect();
+            html.push_str(&format!(
+                "<tr><td>{:.1}–{:.1}s</td><td>{}</td><td>{:.1}ms</td><td>{}</td></tr>\n",
+                burst.start_ms / 1000.0,
+                burst.end_ms / 1000.0,
+                kinds.join(", "),
+                burst.worst_frame_ms,
+                format_recovery(burst.recovery_ms)
+            ));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>イベントログ</h2>\n<table>\n<tr><th>時刻</th><th>種別</th><th>内容</th></tr>\n");
+        for event in &self.events {
+            html.push_str(&format!(
+                "<tr><td>{:.2}s</td><td>{}</td><td>{}</td></tr>\n",
+                event.at_ms / 1000.0,
+                escape_html(&event.kind),
+                escape_html(&event.description)
+            ));
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+
+    fn chart_svg(&self) -> String {
+        const WIDTH: f64 = 900.0;
+        const HEIGHT: f64 = 200.0;
+        let max_ms = self.frame_series.iter().map(|(_, ms)| *ms).fold(self.config.drop_threshold_ms * 2.0, f64::max);
+        let duration = self.duration_ms.max(1.0);
+        let x = |at: f64| at / duration * WIDTH;
+        let y = |ms: f64| HEIGHT - ms / max_ms * HEIGHT;
+
+        let mut svg = format!("<svg width=\"{WIDTH}\" height=\"{HEIGHT}\" style=\"background:#fafafa;border:1px solid #ccc;\">\n");
+        for event in &self.events {
+            svg.push_str(&format!(
+                "<line x1=\"{0:.1}\" x2=\"{0:.1}\" y1=\"0\" y2=\"{HEIGHT}\" stroke=\"#ff9800\" stroke-opacity=\"0.4\"><title>{1}</title></line>\n",
+                x(event.at_ms),
+                escape_html(&event.description)
+            ));
+        }
+        svg.push_str(&format!(
+            "<line x1=\"0\" x2=\"{WIDTH}\" y1=\"{0:.1}\" y2=\"{0:.1}\" stroke=\"#f44336\" stroke-dasharray=\"4\"/>\n",
+            y(self.config.drop_threshold_ms)
+        ));
+        let points: Vec<String> = self.frame_series.iter().map(|&(at, ms)| format!("{:.1},{:.1}", x(at), y(ms))).collect();
+        svg.push_str(&format!("<polyline fill=\"none\" stroke=\"#2196f3\" points=\"{}\"/>\n</svg>\n", points.join(" ")));
+        svg
+    }
+}
+
+fn format_recovery(recovery_ms: Option<f64>) -> String {
+    recovery_ms.map(|ms| format!("{:.0}ms", ms)).unwrap_or_else(|| "未回復".to_string())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorder_with_burst() -> ChaosRunRecorder {
+        let mut recorder = ChaosRunRecorder::new();
+        recorder.start(1000.0);
+        let mut now = 1000.0;
+        let mut frame = |recorder: &mut ChaosRunRecorder, ms: f64| {
+            now += ms;
+            recorder.record_frame(ms, now);
+        };
+        for _ in 0..60 {
+            frame(&mut recorder, 16.0);
+        }
+        // 約1960msにバースト: 追加2件と移動1件、その後3フレームが重い
+        recorder.record_event(1960.0, "spawn", "+100");
+        recorder.record_event(2000.0, "spawn", "+100");
+        recorder.record_event(2100.0, "pan", "<z13>");
+        recorder.record_latency(2100.0, 150.0);
+        recorder.record_latency(3500.0, 20.0);
+        for ms in [80.0, 60.0, 40.0] {
+            frame(&mut recorder, ms);
+        }
+        for _ in 0..60 {
+            frame(&mut recorder, 16.0);
+        }
+        recorder
+    }
+
+    #[test]
+    fn drops_and_spikes_are_attributed_to_preceding_events() {
+        let report = recorder_with_burst().build_report("test", &ReportConfig::default());
+
+        assert_eq!(report.total_events, 3);
+        assert_eq!(report.frame_drops, 3);
+        assert_eq!(report.latency_spikes, 1);
+        assert_eq!(report.by_type[0].kind, "spawn");
+        assert_eq!(report.by_type[0].events, 2);
+        // 開始から1040・1100・1140msの落ちはどれも追加の後、移動は1100msなので後ろ2つだけ
+        assert_eq!(report.by_type[0].preceded_drops, 3);
+        assert_eq!(report.by_type[1].kind, "pan");
+        assert_eq!(report.by_type[1].preceded_drops, 2);
+        assert_eq!(report.by_type[1].preceded_spikes, 1);
+        assert_eq!(report.by_type[0].preceded_spikes, 1);
+
+        // 0〜1秒に1件、1〜2秒に2件。残り約0.1秒の端数の区間は含めない
+        assert_eq!(report.event_rate.count, 2);
+        assert_eq!(report.event_rate.p50, 1.0);
+        assert_eq!(report.event_rate.max, 2.0);
+        assert_eq!(report.event_rate.min, 1.0);
+    }
+
+    #[test]
+    fn burst_recovery_is_measured_and_html_escapes() {
+        let report = recorder_with_burst().build_report("<run>", &ReportConfig::default());
+
+        assert_eq!(report.bursts.len(), 1);
+        let burst = &report.bursts[0];
+        assert_eq!(burst.event_count(), 3);
+        assert_eq!(burst.worst_frame_ms, 80.0);
+        // バースト終了(1100ms)後、40msのフレームが1140msに終わってから回復が始まる
+        let recovery = burst.recovery_ms.unwrap();
+        assert!((recovery - 40.0).abs() < 1e-9, "recovery = {}", recovery);
+
+        let html = report.to_html();
+        assert!(html.contains("&lt;run&gt;"));
+        assert!(html.contains("&lt;z13&gt;"));
+        assert!(!html.contains("<z13>"));
+        let json: ChaosRunReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json, report);
+    }
+}
@@ -0,0 +1,312 @@
+//! JSONで記述するカオスシナリオと、シード付きの決定的な実行
+//!
+//! シナリオは時間区切りのフェーズの列で、各フェーズがイベントの構成比と発生率・オブジェクト数・
+//! ビューポートの経路を指定する。同じシナリオとシードなら常に同じアクション列になる。
+
+
+use serde::{Deserialize, Serialize};
+use crate::sim::rng::SimRng;
+use crate::utils::chaos_intensity::{ChaosEventType, ChaosIntensity, EventWeight};
+
+/// ビューポート経路をサンプリングする間隔（ms）
+const VIEWPORT_STEP_MS: f64 = 500.0;
+
+/// 同梱のシナリオ（名前, JSON）。追加したらCI（`native-bench.yml` の `scenarios` ジョブ）の一覧にも加える
+pub const BUILTIN_SCENARIOS: [(&str, &str); 3] = [
+    ("steady-pan", include_str!("../../scenarios/chaos/steady-pan.json")),
+    ("marker-storm", include_str!("../../scenarios/chaos/marker-storm.json")),
+    ("mode-thrash", include_str!("../../scenarios/chaos/mode-thrash.json")),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ViewportPoint {
+    pub lat: f64,
+    pub lng: f64,
+    pub zoom: f64,
+}
+
+impl ViewportPoint {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Self {
+            lat: self.lat + (other.lat - self.lat) * t,
+            lng: self.lng + (other.lng - self.lng) * t,
+            zoom: self.zoom + (other.zoom - self.zoom) * t,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioPhase {
+    pub name: String,
+    pub duration_ms: f64,
+    /// フェーズ開始時に設定するオブジェクト数
+    #[serde(default)]
+    pub object_count: Option<i32>,
+    /// カオス強度（0〜10、`ChaosIntensity`）
+    #[serde(default)]
+    pub intensity: Option<u8>,
+    /// 平均発生率（ポアソン過程）
+    #[serde(default)]
+    pub events_per_second: f64,
+    #[serde(default)]
+    pub mix: Vec<EventWeight>,
+    /// フェーズの期間で等間隔にたどる経由点
+    #[serde(default)]
+    pub viewport_path: Vec<ViewportPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaosScenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub seed: u64,
+    pub phases: Vec<ScenarioPhase>,
+}
+
+impl ChaosScenario {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let scenario: Self = serde_json::from_str(json).map_err(|e| format!("Invalid scenario: {}", e))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        BUILTIN_SCENARIOS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .and_then(|(_, json)| Self::from_json(json).ok())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.phases.is_empty() {
+            return Err(format!("{}: phases is empty", self.name));
+        }
+        for phase in &self.phases {
+            let context = format!("{}/{}", self.name, phase.name);
+            if phase.duration_ms.is_nan() || phase.duration_ms <= 0.0 {
+                return Err(format!("{}: duration_ms must be positive", context));
+            }
+            if phase.events_per_second.is_nan() || phase.events_per_second < 0.0 {
+                return Err(format!("{}: events_per_second must not be negative", context));
+            }
+            if phase.mix.iter().any(|w| w.weight.is_nan() || w.weight < 0.0) {
+                return Err(format!("{}: weights must not be negative", context));
+            }
+            if phase.events_per_second > 0.0 && phase.mix.iter().map(|w| w.weight).sum::<f64>() <= 0.0 {
+                return Err(format!("{}: mix needs a positive weight when events_per_second > 0", context));
+            }
+            if phase.object_count.is_some_and(|c| c < 0) {
+                return Err(format!("{}: object_count must not be negative", context));
+            }
+            if let Some(Err(e)) = phase.intensity.map(ChaosIntensity::new) {
+                return Err(format!("{}: {}", context, e));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn duration_ms(&self) -> f64 {
+        self.phases.iter().map(|p| p.duration_ms).sum()
+    }
+
+    /// シナリオ全体のアクション列（開始からの時刻順）
+    pub fn timeline(&self, seed: u64) -> Vec<TimedAction> {
+        let mut rng = SimRng::new(seed);
+        let mut actions = Vec::new();
+        let mut phase_start = 0.0;
+
+        for (index, phase) in self.phases.iter().enumerate() {
+            let phase_end = phase_start + phase.duration_ms;
+            let mut push = |at_ms: f64, action: ScenarioAction| actions.push(TimedAction { at_ms, action });
+
+            push(phase_start, ScenarioAction::PhaseStarted { index });
+            if let Some(intensity) = phase.intensity {
+                push(phase_start, ScenarioAction::SetIntensity(intensity));
+            }
+            if let Some(count) = phase.object_count {
+                push(phase_start, ScenarioAction::SetObjectCount(count));
+            }
+
+            // ビューポートとイベントを時刻順にマージする
+            let mut viewports = viewport_samples(phase, phase_start).into_iter().peekable();
+            let mut next_event = next_arrival(&mut rng, phase.events_per_second, phase_start);
+            loop {
+                let viewport_at = viewports.peek().map(|(at, _)| *at).unwrap_or(f64::INFINITY);
+                let event_at = next_event.filter(|at| *at < phase_end).unwrap_or(f64::INFINITY);
+                if viewport_at.is_infinite() && event_at.is_infinite() {
+                    break;
+                }
+                if viewport_at <= event_at {
+                    let (at, point) = viewports.next().unwrap();
+                    push(at, ScenarioAction::SetViewport(point));
+                } else {
+                    push(event_at, ScenarioAction::Fire(pick_event(&mut rng, &phase.mix)));
+                    next_event = next_arrival(&mut rng, phase.events_per_second, event_at);
+                }
+            }
+            phase_start = phase_end;
+        }
+
+        actions.push(TimedAction { at_ms: phase_start, action: ScenarioAction::Finished });
+        actions
+    }
+}
+
+/// 次のイベント時刻（指数分布の到着間隔）
+fn next_arrival(rng: &mut SimRng, events_per_second: f64, from_ms: f64) -> Option<f64> {
+    if events_per_second <= 0.0 {
+        return None;
+    }
+    Some(from_ms - (1.0 - rng.next_f64()).ln() / events_per_second * 1000.0)
+}
+
+/// 重みに従って種別を選ぶ（`mix` には正の重みが1つ以上必要）
+pub fn pick_event(rng: &mut SimRng, mix: &[EventWeight]) -> ChaosEventType {
+    let total: f64 = mix.iter().map(|w| w.weight).sum();
+    let mut target = rng.next_f64() * total;
+    for weight in mix {
+        if target < weight.weight {
+            return weight.event.clone();
+        }
+        target -= weight.weight;
+    }
+    // 浮動小数点の誤差で最後まで来た場合
+    mix.iter().rev().find(|w| w.weight > 0.0).map(|w| w.event.clone()).unwrap()
+}
+
+/// 経由点を期間内で等間隔にたどる位置（開始時刻とVIEWPORT_STEP_MSごと）
+fn viewport_samples(phase: &ScenarioPhase, phase_start: f64) -> Vec<(f64, ViewportPoint)> {
+    match phase.viewport_path.as_slice() {
+        [] => Vec::new(),
+        [only] => vec![(phase_start, *only)],
+        path => {
+            let segments = (path.len() - 1) as f64;
+            let steps = (phase.duration_ms / VIEWPORT_STEP_MS).floor() as usize;
+            (0..=steps)
+                .map(|step| {
+                    let elapsed = (step as f64 * VIEWPORT_STEP_MS).min(phase.duration_ms);
+                    let position = elapsed / phase.duration_ms * segments;
+                    let segment = (position.floor() as usize).min(path.len() - 2);
+                    let point = path[segment].lerp(&path[segment + 1], position - segment as f64);
+                    (phase_start + elapsed, point)
+                })
+                .collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioAction {
+    PhaseStarted { index: usize },
+    SetIntensity(u8),
+    SetObjectCount(i32),
+    SetViewport(ViewportPoint),
+    Fire(ChaosEventType),
+    Finished,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedAction {
+    /// シナリオ開始からの時刻（ms）
+    pub at_ms: f64,
+    pub action: ScenarioAction,
+}
+
+/// 実時間に合わせてアクションを取り出す
+#[derive(Debug, Clone)]
+pub struct ScenarioRunner {
+    pub scenario: ChaosScenario,
+    pub seed: u64,
+    timeline: Vec<TimedAction>,
+    cursor: usize,
+    started_at: f64,
+    /// 終わったらシードを変えて最初から繰り返す（ソークテスト用）
+    repeat: bool,
+    cycle: u64,
+}
+
+impl ScenarioRunner {
+    pub fn new(scenario: ChaosScenario, seed: u64, now_ms: f64) -> Self {
+        let timeline = scenario.timeline(seed);
+        Self { scenario, seed, timeline, cursor: 0, started_at: now_ms, repeat: false, cycle: 0 }
+    }
+
+    /// `Finished` を出さずに繰り返し実行する。n周目は `seed + n` のアクション列になる
+    pub fn repeating(scenario: ChaosScenario, seed: u64, now_ms: f64) -> Self {
+        Self { repeat: true, ..Self::new(scenario, seed, now_ms) }
+    }
+
+    /// 時刻 `now_ms` までに実行すべきアクション
+    pub fn due(&mut self, now_ms: f64) -> Vec<ScenarioAction> {
+        let mut due = Vec::new();
+        while self.cursor < self.timeline.len() && self.timeline[self.cursor].at_ms <= now_ms - self.started_at {
+            let action = self.timeline[self.cursor].action.clone();
+            self.cursor += 1;
+            if self.repeat && action == ScenarioAction::Finished {
+                self.cycle += 1;
+                self.started_at += self.scenario.duration_ms();
+                self.timeline = self.scenario.timeline(self.seed.wrapping_add(self.cycle));
+                self.cursor = 0;
+                continue;
+            }
+            due.push(action);
+        }
+        due
+    }
+
+    /// 進捗（0〜1）
+    pub fn progress(&self, now_ms: f64) -> f64 {
+        ((now_ms - self.started_at) / self.scenario.duration_ms()).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_scenarios_are_valid() {
+        for (name, json) in BUILTIN_SCENARIOS {
+            let scenario = ChaosScenario::from_json(json).unwrap_or_else(|e| panic!("{}: {}", name, e));
+            assert_eq!(scenario.name, name);
+            assert_eq!(scenario.timeline(scenario.seed).last().unwrap().action, ScenarioAction::Finished);
+        }
+
+        let invalid = r#"{ "name": "x", "phases": [{ "name": "p", "duration_ms": 1000, "events_per_second": 2 }] }"#;
+        assert!(ChaosScenario::from_json(invalid).unwrap_err().contains("mix"));
+    }
+
+    #[test]
+    fn runner_is_deterministic_regardless_of_tick_timing() {
+        let scenario = ChaosScenario::builtin("marker-storm").unwrap();
+        let timeline = scenario.timeline(7);
+        assert_eq!(timeline, scenario.timeline(7));
+        assert_ne!(timeline, scenario.timeline(8));
+        assert!(timeline.windows(2).all(|w| w[0].at_ms <= w[1].at_ms));
+        assert!(timeline.iter().any(|a| matches!(a.action, ScenarioAction::Fire(_))));
+
+        // 細かいティックと粗いティックで同じアクション列になる
+        let collect = |step: f64| {
+            let mut runner = ScenarioRunner::new(scenario.clone(), 7, 1000.0);
+            let mut actions = Vec::new();
+            let mut now = 1000.0;
+            while !actions.contains(&ScenarioAction::Finished) {
+                actions.extend(runner.due(now));
+                now += step;
+            }
+            actions
+        };
+        let fine = collect(16.0);
+        assert_eq!(fine, collect(750.0));
+        assert_eq!(fine, timeline.into_iter().map(|a| a.action).collect::<Vec<_>>());
+
+        // 繰り返し実行では終わらずに2周目が始まる
+        let mut runner = ScenarioRunner::repeating(scenario.clone(), 7, 0.0);
+        let actions = runner.due(scenario.duration_ms() * 1.5);
+        assert!(!actions.contains(&ScenarioAction::Finished));
+        let restarts = actions.iter().filter(|a| **a == ScenarioAction::PhaseStarted { index: 0 }).count();
+        assert_eq!(restarts, 2);
+    }
+}
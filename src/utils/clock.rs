@@ -0,0 +1,7 @@
+/// `performance.now()`（`std::time::Instant` はwasm32では使えない）
+pub fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map(|p| p.now())
+        .unwrap_or(0.0)
+}
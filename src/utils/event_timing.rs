@@ -0,0 +1,336 @@
+//! Event Timing API による入力遅延の測定
+//!
+//! `PerformanceEventTiming` はイベントが発生してからハンドラが走るまでの待ち（入力遅延）、
+//! ハンドラの実行（処理時間）、次の描画まで（表示遅延）を含む。
+//! 手動の `performance.now()` による測定では、最初と最後が抜け落ちる。
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use std::collections::{BTreeMap, VecDeque};
+use crate::utils::input_latency::LatencyStats;
+
+/// 受け取るエントリの最小継続時間（ms）。仕様上の下限は16
+const DURATION_THRESHOLD_MS: f64 = 16.0;
+/// 保持するインタラクション数
+const MAX_INTERACTIONS: usize = 1000;
+/// 同じインタラクションのエントリを探す範囲（直近の件数）
+const MERGE_WINDOW: usize = 16;
+/// INPで外れ値として除く割合（50件ごとに最悪の1件）
+const INP_IGNORE_PER: usize = 50;
+/// INPのために保持する最も遅いインタラクションの数（web-vitalsと同じ10件。500件を超えると10番目を使う）
+const INP_CANDIDATES: usize = 10;
+
+/// (イベント名, interactionId, 発生時刻, 処理開始, 処理終了, 継続時間, 部品名)
+type EventTimingCallback = Closure<dyn FnMut(String, f64, f64, f64, f64, f64, String)>;
+
+#[wasm_bindgen(inline_js = r#"
+export function observeEventTiming(callback, durationThreshold) {
+    if (!PerformanceObserver.supportedEntryTypes || !PerformanceObserver.supportedEntryTypes.includes('event')) {
+        return undefined;
+    }
+    const observer = new PerformanceObserver((list) => {
+        for (const entry of list.getEntries()) {
+            // interactionIdのないイベント（mousemoveなど）はインタラクションではない
+            if (!entry.interactionId) continue;
+            const target = entry.target;
+            const tagged = target && target.closest ? target.closest('[data-latency-component]') : null;
+            const component = tagged
+                ? tagged.dataset.latencyComponent
+                : (target && target.tagName ? target.tagName.toLowerCase() : 'unknown');
+            callback(entry.name, entry.interactionId, entry.startTime, entry.processingStart,
+                entry.processingEnd, entry.duration, component);
+        }
+    });
+    observer.observe({ type: 'event', durationThreshold, buffered: false });
+    return () => observer.disconnect();
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = observeEventTiming)]
+    fn observe_event_timing(callback: &EventTimingCallback, duration_threshold: f64) -> Option<js_sys::Function>;
+}
+
+/// `PerformanceEventTiming` の1件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventTimingEntry {
+    pub name: String,
+    pub interaction_id: u64,
+    pub start_ms: f64,
+    pub processing_start_ms: f64,
+    pub processing_end_ms: f64,
+    /// 発生から次の描画まで（8ms単位に丸められている）
+    pub duration_ms: f64,
+    /// `data-latency-component` 属性、なければタグ名
+    pub component: String,
+}
+
+/// 1回のインタラクションの内訳
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractionTiming {
+    pub interaction_id: u64,
+    /// 継続時間が最長だったイベントの名前
+    pub name: String,
+    pub component: String,
+    pub start_ms: f64,
+    pub input_delay_ms: f64,
+    pub processing_ms: f64,
+    pub presentation_delay_ms: f64,
+    pub duration_ms: f64,
+}
+
+impl InteractionTiming {
+    pub fn from_entry(entry: &EventTimingEntry) -> Self {
+        let input_delay_ms = (entry.processing_start_ms - entry.start_ms).max(0.0);
+        let processing_ms = (entry.processing_end_ms - entry.processing_start_ms).max(0.0);
+        // durationは丸められているので、処理終了を越えない場合は0とする
+        let presentation_delay_ms = (entry.start_ms + entry.duration_ms - entry.processing_end_ms).max(0.0);
+        Self {
+            interaction_id: entry.interaction_id,
+            name: entry.name.clone(),
+            component: entry.component.clone(),
+            start_ms: entry.start_ms,
+            input_delay_ms,
+            processing_ms,
+            presentation_delay_ms,
+            duration_ms: entry.duration_ms,
+        }
+    }
+}
+
+/// 部品ごとの合計と内訳の統計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentTiming {
+    pub component: String,
+    pub total: LatencyStats,
+    pub input_delay: LatencyStats,
+    pub processing: LatencyStats,
+    pub presentation_delay: LatencyStats,
+}
+
+/// INPの候補（全期間で最も遅いインタラクションだけを保持するので、件数が増えてもメモリは一定）
+///
+/// Event Timing の監視とWeb Vitalsの監視で共有する
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InpCandidates {
+    /// 最も遅いインタラクション（id, 継続時間）。遅い順に最大 `INP_CANDIDATES` 件
+    worst: Vec<(u64, f64)>,
+    /// 直近のinteractionId（同じ操作の別エントリを1件に数えるため）
+    recent: VecDeque<u64>,
+    /// 記録したインタラクション数（外れ値の除外に使う）
+    count: usize,
+}
+
+impl InpCandidates {
+    pub fn record(&mut self, interaction_id: u64, duration_ms: f64) {
+        if !self.recent.contains(&interaction_id) {
+            self.count += 1;
+            self.recent.push_back(interaction_id);
+            if self.recent.len() > MERGE_WINDOW {
+                self.recent.pop_front();
+            }
+        }
+
+        match self.worst.iter_mut().find(|(id, _)| *id == interaction_id) {
+            Some((_, worst)) => *worst = worst.max(duration_ms),
+            None => self.worst.push((interaction_id, duration_ms)),
+        }
+        self.worst.sort_by(|a, b| b.1.total_cmp(&a.1));
+        self.worst.truncate(INP_CANDIDATES);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// 最も遅いインタラクション（50件ごとに最悪の1件を除く）。記録がなければ `None`
+    pub fn value(&self) -> Option<f64> {
+        let index = (self.count / INP_IGNORE_PER).min(self.worst.len().checked_sub(1)?);
+        Some(self.worst[index].1)
+    }
+}
+
+/// エントリをインタラクション単位にまとめる
+///
+/// pointerdown・pointerup・click のように同じ `interactionId` を持つエントリは1件とし、
+/// 継続時間が最長のエントリの内訳を採用する
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InteractionTracker {
+    interactions: VecDeque<InteractionTiming>,
+    /// 保持数を超えて捨てた分も含めたINPの集計
+    inp: InpCandidates,
+}
+
+impl InteractionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, entry: &EventTimingEntry) {
+        let timing = InteractionTiming::from_entry(entry);
+        self.inp.record(entry.interaction_id, timing.duration_ms);
+        if let Some(existing) = self
+            .interactions
+            .iter_mut()
+            .rev()
+            .take(MERGE_WINDOW)
+            .find(|i| i.interaction_id == entry.interaction_id)
+        {
+            if timing.duration_ms > existing.duration_ms {
+                *existing = timing;
+            }
+            return;
+        }
+
+        self.interactions.push_back(timing);
+        if self.interactions.len() > MAX_INTERACTIONS {
+            self.interactions.pop_front();
+        }
+    }
+
+    /// 記録したインタラクション数（保持数を超えた分も含む）
+    pub fn count(&self) -> usize {
+        self.inp.count()
+    }
+
+    /// Interaction to Next Paint: 全期間で最も遅いインタラクション（50件ごとに最悪の1件を除く）
+    pub fn inp(&self) -> f64 {
+        self.inp.value().unwrap_or(0.0)
+    }
+
+    /// 保持している直近の16ms以上のインタラクションの継続時間の統計
+    pub fn overall(&self) -> LatencyStats {
+        let durations: Vec<f64> = self.interactions.iter().map(|i| i.duration_ms).collect();
+        LatencyStats::from_samples(&durations)
+    }
+
+    /// 部品ごとの統計（部品名順）
+    pub fn by_component(&self) -> Vec<ComponentTiming> {
+        let mut groups: BTreeMap<&str, Vec<&InteractionTiming>> = BTreeMap::new();
+        for interaction in &self.interactions {
+            groups.entry(interaction.component.as_str()).or_default().push(interaction);
+        }
+        groups
+            .into_iter()
+            .map(|(component, interactions)| {
+                let stats = |phase: fn(&InteractionTiming) -> f64| {
+                    LatencyStats::from_samples(&interactions.iter().map(|i| phase(i)).collect::<Vec<_>>())
+                };
+                ComponentTiming {
+                    component: component.to_string(),
+                    total: stats(|i| i.duration_ms),
+                    input_delay: stats(|i| i.input_delay_ms),
+                    processing: stats(|i| i.processing_ms),
+                    presentation_delay: stats(|i| i.presentation_delay_ms),
+                }
+            })
+            .collect()
+    }
+
+    pub fn reset(&mut self) {
+        self.interactions.clear();
+        self.inp = InpCandidates::default();
+    }
+}
+
+/// `PerformanceEventTiming` の購読。ドロップ時に解除する
+pub struct EventTimingMonitor {
+    _callback: EventTimingCallback,
+    disconnect: js_sys::Function,
+}
+
+impl EventTimingMonitor {
+    /// 非対応のブラウザではエラー
+    pub fn start(mut on_entry: impl FnMut(EventTimingEntry) + 'static) -> Result<Self, String> {
+        let callback = Closure::new(
+            move |name: String, interaction_id: f64, start: f64, processing_start: f64, processing_end: f64, duration: f64, component: String| {
+                on_entry(EventTimingEntry {
+                    name,
+                    interaction_id: interaction_id as u64,
+                    start_ms: start,
+                    processing_start_ms: processing_start,
+                    processing_end_ms: processing_end,
+                    duration_ms: duration,
+                    component,
+                });
+            },
+        );
+        let disconnect = observe_event_timing(&callback, DURATION_THRESHOLD_MS)
+            .ok_or_else(|| "Event Timing API is not supported in this browser".to_string())?;
+
+        Ok(Self {
+            _callback: callback,
+            disconnect,
+        })
+    }
+}
+
+impl Drop for EventTimingMonitor {
+    fn drop(&mut self) {
+        let _ = self.disconnect.call0(&JsValue::NULL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, id: u64, start: f64, delay: f64, processing: f64, duration: f64, component: &str) -> EventTimingEntry {
+        EventTimingEntry {
+            name: name.to_string(),
+            interaction_id: id,
+            start_ms: start,
+            processing_start_ms: start + delay,
+            processing_end_ms: start + delay + processing,
+            duration_ms: duration,
+            component: component.to_string(),
+        }
+    }
+
+    #[test]
+    fn splits_phases_and_merges_entries_of_one_interaction() {
+        let mut tracker = InteractionTracker::new();
+        tracker.record(&entry("pointerdown", 1, 100.0, 4.0, 2.0, 24.0, "map"));
+        tracker.record(&entry("click", 1, 110.0, 30.0, 50.0, 120.0, "map"));
+        tracker.record(&entry("pointerup", 1, 108.0, 2.0, 1.0, 16.0, "map"));
+        tracker.record(&entry("keydown", 2, 500.0, 10.0, 5.0, 40.0, "controls"));
+
+        assert_eq!(tracker.count(), 2);
+        let reports = tracker.by_component();
+        assert_eq!(reports.iter().map(|r| r.component.as_str()).collect::<Vec<_>>(), ["controls", "map"]);
+        let map = &reports[1];
+        assert_eq!(map.total.count, 1);
+        assert_eq!(map.total.p50, 120.0);
+        assert_eq!(map.input_delay.p50, 30.0);
+        assert_eq!(map.processing.p50, 50.0);
+        assert_eq!(map.presentation_delay.p50, 40.0);
+
+        // 丸めで処理終了より前に終わる場合の表示遅延は0
+        let rounded = InteractionTiming::from_entry(&entry("click", 3, 0.0, 5.0, 12.0, 16.0, "map"));
+        assert_eq!(rounded.presentation_delay_ms, 0.0);
+    }
+
+    #[test]
+    fn inp_ignores_one_outlier_per_fifty_interactions() {
+        let mut tracker = InteractionTracker::new();
+        tracker.record(&entry("click", 1, 0.0, 1.0, 1.0, 80.0, "map"));
+        tracker.record(&entry("click", 2, 0.0, 1.0, 1.0, 300.0, "map"));
+        assert_eq!(tracker.inp(), 300.0);
+
+        for id in 3..=100 {
+            tracker.record(&entry("click", id, 0.0, 1.0, 1.0, 40.0, "map"));
+        }
+        // 100件なら最悪の2件を除く
+        assert_eq!(tracker.count(), 100);
+        assert_eq!(tracker.inp(), 40.0);
+        assert!(InteractionTracker::new().inp() == 0.0);
+
+        // 保持数を超えて捨てたインタラクションもINPに残る
+        let mut tracker = InteractionTracker::new();
+        for id in 1..=1500 {
+            let duration = if id <= 20 { 500.0 } else { 40.0 };
+            tracker.record(&entry("click", id, 0.0, 1.0, 1.0, duration, "map"));
+        }
+        assert_eq!(tracker.overall().max, 40.0);
+        assert_eq!(tracker.inp(), 500.0);
+    }
+}
@@ -1,4 +1,5 @@
 use wasm_bindgen::prelude::*;
+use crate::utils::live_handles::{self, HandleKind};
 
 #[wasm_bindgen(inline_js = r#"
 let frameCount = 0;
@@ -43,8 +44,83 @@ export function stopFPSCounter() {
         animationId = null;
     }
 }
+
+let frameTimerId = null;
+
+// 1フレームごとにフレーム時間(ms)と時刻を通知する
+export function startFrameTimer(callback) {
+    stopFrameTimer();
+    
+    let previous = null;
+    
+    function onFrame(timestamp) {
+        if (previous !== null) {
+            try {
+                callback(timestamp - previous, timestamp);
+            } catch (e) {
+                console.log('Frame timer callback error, stopping timer');
+                stopFrameTimer();
+                return;
+            }
+        }
+        previous = timestamp;
+        frameTimerId = requestAnimationFrame(onFrame);
+    }
+    
+    frameTimerId = requestAnimationFrame(onFrame);
+}
+
+export function stopFrameTimer() {
+    if (frameTimerId !== null) {
+        cancelAnimationFrame(frameTimerId);
+        frameTimerId = null;
+    }
+}
+
+// startFrameTimerと同じ通知を独立したループで行う（複数同時に利用可能）。戻り値は停止関数
+export function startFrameLoop(callback) {
+    let previous = null;
+    let id = null;
+    
+    function onFrame(timestamp) {
+        if (previous !== null) {
+            callback(timestamp - previous, timestamp);
+        }
+        previous = timestamp;
+        id = requestAnimationFrame(onFrame);
+    }
+    
+    id = requestAnimationFrame(onFrame);
+    return () => cancelAnimationFrame(id);
+}
 "#)]
 extern "C" {
     pub fn startFPSCounter(callback: &Closure<dyn FnMut(f64)>);
     pub fn stopFPSCounter();
+    pub fn startFrameTimer(callback: &Closure<dyn FnMut(f64, f64)>);
+    pub fn stopFrameTimer();
+    #[wasm_bindgen(js_name = startFrameLoop)]
+    fn start_frame_loop(callback: &Closure<dyn FnMut(f64, f64)>) -> js_sys::Function;
+}
+
+/// 毎フレーム `(フレーム時間, 時刻)` を通知する独立したループ。ドロップ時に停止する
+pub struct FrameLoop {
+    _callback: Closure<dyn FnMut(f64, f64)>,
+    stop: js_sys::Function,
+}
+
+impl FrameLoop {
+    pub fn start(callback: impl FnMut(f64, f64) + 'static) -> Self {
+        let callback = Closure::new(callback);
+        let stop = start_frame_loop(&callback);
+        live_handles::acquire(HandleKind::FrameLoop);
+        Self { _callback: callback, stop }
+    }
+}
+
+impl Drop for FrameLoop {
+    fn drop(&mut self) {
+        let _ = self.stop.call0(&JsValue::NULL);
+        live_handles::release(HandleKind::FrameLoop);
+    }
 }
\ No newline at end of file
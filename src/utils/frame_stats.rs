@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::histogram::LogHistogram;
+
+/// 60fpsのフレーム予算（ms）
+pub const FRAME_BUDGET_60FPS_MS: f64 = 1000.0 / 60.0;
+/// 30fps相当のフレーム時間（ms）
+pub const FRAME_BUDGET_30FPS_MS: f64 = 33.0;
+/// 体感できるカクつきとみなすフレーム時間（ms）
+pub const FRAME_STALL_MS: f64 = 50.0;
+
+/// フレーム時間の集計結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrameTimeStats {
+    pub frame_count: u32,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub over_16_7ms: u32,
+    pub over_33ms: u32,
+    pub over_50ms: u32,
+    /// 最長フレーム（ストール）の長さ
+    pub longest_stall_ms: f64,
+    /// 最長フレームが終了した時刻（performance.now()基準）
+    pub longest_stall_at_ms: f64,
+}
+
+impl FrameTimeStats {
+    /// 60fps予算を超えたフレームの割合（%）
+    pub fn jank_ratio(&self) -> f64 {
+        if self.frame_count == 0 {
+            return 0.0;
+        }
+        self.over_16_7ms as f64 * 100.0 / self.frame_count as f64
+    }
+}
+
+/// 1フレームごとの時間を記録するヒストグラム（分布は `LogHistogram`）
+#[derive(Debug, Clone)]
+pub struct FrameTimeHistogram {
+    histogram: LogHistogram,
+    max_at_ms: f64,
+    over_16_7ms: u32,
+    over_33ms: u32,
+    over_50ms: u32,
+}
+
+impl Default for FrameTimeHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameTimeHistogram {
+    pub fn new() -> Self {
+        Self {
+            histogram: LogHistogram::default(),
+            max_at_ms: 0.0,
+            over_16_7ms: 0,
+            over_33ms: 0,
+            over_50ms: 0,
+        }
+    }
+
+    /// 1フレーム分の時間を記録
+    ///
+    /// `timestamp_ms` はフレーム終了時刻で、最長ストールの発生時刻として保持する
+    pub fn record(&mut self, frame_ms: f64, timestamp_ms: f64) {
+        if !frame_ms.is_finite() || frame_ms < 0.0 {
+            return;
+        }
+
+        if frame_ms > self.histogram.max() || self.histogram.count() == 0 {
+            self.max_at_ms = timestamp_ms;
+        }
+        self.histogram.record(frame_ms);
+
+        if frame_ms > FRAME_BUDGET_60FPS_MS {
+            self.over_16_7ms += 1;
+        }
+        if frame_ms > FRAME_BUDGET_30FPS_MS {
+            self.over_33ms += 1;
+        }
+        if frame_ms > FRAME_STALL_MS {
+            self.over_50ms += 1;
+        }
+    }
+
+    /// 毎秒の区間ごとに呼ばれるため、ヒストグラムは確保し直さずに空にする
+    pub fn reset(&mut self) {
+        self.histogram.clear();
+        self.max_at_ms = 0.0;
+        self.over_16_7ms = 0;
+        self.over_33ms = 0;
+        self.over_50ms = 0;
+    }
+
+    pub fn stats(&self) -> FrameTimeStats {
+        if self.histogram.count() == 0 {
+            return FrameTimeStats::default();
+        }
+
+        let summary = self.histogram.summary();
+        FrameTimeStats {
+            frame_count: summary.count as u32,
+            avg_ms: summary.mean,
+            p50_ms: summary.p50,
+            p95_ms: summary.p95,
+            p99_ms: summary.p99,
+            max_ms: summary.max,
+            over_16_7ms: self.over_16_7ms,
+            over_33ms: self.over_33ms,
+            over_50ms: self.over_50ms,
+            longest_stall_ms: summary.max,
+            longest_stall_at_ms: self.max_at_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_follow_recorded_distribution() {
+        let mut hist = FrameTimeHistogram::new();
+        for i in 0..100 {
+            // 99フレームは16ms、1フレームだけ80msのストール
+            let frame_ms = if i == 42 { 80.0 } else { 16.0 };
+            hist.record(frame_ms, i as f64 * 16.0);
+        }
+
+        let stats = hist.stats();
+        assert_eq!(stats.frame_count, 100);
+        assert!((stats.p50_ms - 16.0).abs() < 0.16);
+        assert!((stats.p95_ms - 16.0).abs() < 0.16);
+        assert!((stats.p99_ms - 16.0).abs() < 0.16);
+        assert_eq!(stats.max_ms, 80.0);
+        assert_eq!(stats.longest_stall_at_ms, 42.0 * 16.0);
+        assert_eq!(stats.over_16_7ms, 1);
+        assert_eq!(stats.over_33ms, 1);
+        assert_eq!(stats.over_50ms, 1);
+    }
+
+    #[test]
+    fn long_frames_report_max() {
+        let mut hist = FrameTimeHistogram::new();
+        hist.record(10.0, 0.0);
+        hist.record(400.0, 1.0);
+
+        assert_eq!(hist.histogram.percentile(1.0), 400.0);
+        assert_eq!(hist.stats().over_50ms, 1);
+    }
+
+    #[test]
+    fn reset_starts_a_new_window() {
+        let mut hist = FrameTimeHistogram::new();
+        hist.record(80.0, 100.0);
+        hist.reset();
+        assert_eq!(hist.stats(), FrameTimeStats::default());
+
+        // 前の区間の最大値や超過数を引き継がない
+        hist.record(12.0, 2000.0);
+        let stats = hist.stats();
+        assert_eq!(stats.frame_count, 1);
+        assert_eq!(stats.max_ms, 12.0);
+        assert_eq!(stats.longest_stall_at_ms, 2000.0);
+        assert_eq!(stats.over_16_7ms, 0);
+    }
+
+    #[test]
+    fn invalid_samples_are_ignored() {
+        let mut hist = FrameTimeHistogram::new();
+        hist.record(f64::NAN, 0.0);
+        hist.record(-1.0, 0.0);
+
+        assert_eq!(hist.stats(), FrameTimeStats::default());
+    }
+}
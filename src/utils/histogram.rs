@@ -0,0 +1,310 @@
+//! 固定メモリの対数バケットヒストグラム（HDR Histogram 方式）
+//!
+//! 値を分解能の整数倍に丸め、2のべき乗ごとの区間を64等分したバケットに数える。
+//! 記録はO(1)、メモリは値の数によらず一定で、パーセンタイルの誤差は分解能の半分か相対1%未満。
+//! 同じ分解能どうしならセッションをまたいで足し合わせられる。
+//! 入力遅延・フレーム時間・カオスのイベント発生率で共通に使う。
+
+use serde::{Deserialize, Serialize};
+
+/// 2のべき乗の区間ごとのバケット数の指数（64分割）
+const SUB_BUCKET_BITS: u32 = 6;
+const SUB_BUCKET_COUNT: usize = 1 << SUB_BUCKET_BITS;
+/// 記録できる範囲（分解能の2^36倍。ms単位・分解能1µsで約19時間）
+const MAGNITUDE_BITS: u32 = 36;
+const MAX_UNITS: u64 = (1 << MAGNITUDE_BITS) - 1;
+const BUCKET_COUNT: usize = (MAGNITUDE_BITS - SUB_BUCKET_BITS + 1) as usize * SUB_BUCKET_COUNT;
+
+/// ms単位の時間を記録するときの分解能（1µs）
+pub const TIME_RESOLUTION_MS: f64 = 0.001;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "HistogramSnapshot", try_from = "HistogramSnapshot")]
+pub struct LogHistogram {
+    resolution: f64,
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+/// パーセンタイルの要約
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistogramSummary {
+    pub count: u64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+impl Default for LogHistogram {
+    fn default() -> Self {
+        Self::new(TIME_RESOLUTION_MS)
+    }
+}
+
+impl LogHistogram {
+    /// `resolution` は区別できる最小の値の幅（正の値）
+    pub fn new(resolution: f64) -> Self {
+        assert!(resolution.is_finite() && resolution > 0.0, "resolution must be positive");
+        Self {
+            resolution,
+            counts: vec![0; BUCKET_COUNT],
+            count: 0,
+            sum: 0.0,
+            min: 0.0,
+            max: 0.0,
+        }
+    }
+
+    /// 負の値・NaN・無限大は無視する
+    pub fn record(&mut self, value: f64) {
+        if !value.is_finite() || value < 0.0 {
+            return;
+        }
+        let units = ((value / self.resolution).round() as u64).min(MAX_UNITS);
+        self.counts[bucket_index(units)] += 1;
+
+        if self.count == 0 || value < self.min {
+            self.min = value;
+        }
+        if value > self.max {
+            self.max = value;
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+
+    /// 値をすべて捨てる（バケットは確保し直さない）
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.count = 0;
+        self.sum = 0.0;
+        self.min = 0.0;
+        self.max = 0.0;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum / self.count as f64
+    }
+
+    /// nearest-rank法のパーセンタイル（`p` は0〜1）。バケットの中央値を最小・最大の範囲に収めて返す
+    pub fn percentile(&self, p: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let rank = ((p.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        // 両端は正確な値を持っている
+        if rank == 1 {
+            return self.min;
+        }
+        if rank >= self.count {
+            return self.max;
+        }
+        let mut seen = 0;
+        for (index, &n) in self.counts.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                // バケットは丸めた単位で [lower, lower + width) を数えている
+                let (lower, width) = bucket_range(index);
+                let mid = (lower as f64 + (width - 1) as f64 / 2.0) * self.resolution;
+                return mid.clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+
+    pub fn summary(&self) -> HistogramSummary {
+        if self.count == 0 {
+            return HistogramSummary::default();
+        }
+        HistogramSummary {
+            count: self.count,
+            mean: self.mean(),
+            min: self.min,
+            max: self.max,
+            p50: self.percentile(0.5),
+            p95: self.percentile(0.95),
+            p99: self.percentile(0.99),
+        }
+    }
+
+    /// 別のヒストグラムの値を足し合わせる（分解能が同じこと）
+    pub fn merge(&mut self, other: &LogHistogram) -> Result<(), String> {
+        if self.resolution != other.resolution {
+            return Err(format!(
+                "cannot merge histograms with different resolutions ({} and {})",
+                self.resolution, other.resolution
+            ));
+        }
+        if other.count == 0 {
+            return Ok(());
+        }
+        for (count, &n) in self.counts.iter_mut().zip(&other.counts) {
+            *count += n;
+        }
+        self.min = if self.count == 0 { other.min } else { self.min.min(other.min) };
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+        self.sum += other.sum;
+        Ok(())
+    }
+}
+
+fn bucket_index(units: u64) -> usize {
+    if units < SUB_BUCKET_COUNT as u64 {
+        return units as usize;
+    }
+    let msb = 63 - units.leading_zeros();
+    let shift = msb - SUB_BUCKET_BITS;
+    (shift as usize + 1) * SUB_BUCKET_COUNT + ((units >> shift) as usize - SUB_BUCKET_COUNT)
+}
+
+/// バケットの (下限, 幅)（分解能の単位）
+fn bucket_range(index: usize) -> (u64, u64) {
+    let block = index / SUB_BUCKET_COUNT;
+    if block == 0 {
+        return (index as u64, 1);
+    }
+    let shift = block as u32 - 1;
+    let sub = (index % SUB_BUCKET_COUNT + SUB_BUCKET_COUNT) as u64;
+    (sub << shift, 1 << shift)
+}
+
+/// 保存・送信用の形式（空のバケットを省く）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct HistogramSnapshot {
+    resolution: f64,
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    /// (バケット番号, 件数)
+    buckets: Vec<(usize, u64)>,
+}
+
+impl From<LogHistogram> for HistogramSnapshot {
+    fn from(histogram: LogHistogram) -> Self {
+        Self {
+            resolution: histogram.resolution,
+            count: histogram.count,
+            sum: histogram.sum,
+            min: histogram.min,
+            max: histogram.max,
+            buckets: histogram
+                .counts
+                .iter()
+                .enumerate()
+                .filter(|(_, &n)| n > 0)
+                .map(|(index, &n)| (index, n))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<HistogramSnapshot> for LogHistogram {
+    type Error = String;
+
+    fn try_from(snapshot: HistogramSnapshot) -> Result<Self, Self::Error> {
+        if !snapshot.resolution.is_finite() || snapshot.resolution <= 0.0 {
+            return Err("resolution must be positive".to_string());
+        }
+        let mut histogram = LogHistogram::new(snapshot.resolution);
+        for (index, n) in snapshot.buckets {
+            let bucket = histogram
+                .counts
+                .get_mut(index)
+                .ok_or_else(|| format!("bucket index {} out of range", index))?;
+            *bucket += n;
+        }
+        if histogram.counts.iter().sum::<u64>() != snapshot.count {
+            return Err("bucket counts do not add up to count".to_string());
+        }
+        histogram.count = snapshot.count;
+        histogram.sum = snapshot.sum;
+        histogram.min = snapshot.min;
+        histogram.max = snapshot.max;
+        Ok(histogram)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::rng::SimRng;
+
+    /// 正確なnearest-rank法のパーセンタイル
+    fn exact_percentile(sorted: &[f64], p: f64) -> f64 {
+        let rank = ((p * sorted.len() as f64).ceil() as usize).max(1);
+        sorted[rank - 1]
+    }
+
+    #[test]
+    fn percentiles_are_within_one_percent_over_a_wide_range() {
+        let mut rng = SimRng::new(3);
+        let mut histogram = LogHistogram::default();
+        let mut values = Vec::new();
+        for _ in 0..20_000 {
+            // 0.1ms〜約40秒の対数一様分布
+            let value = 0.1 * 10f64.powf(rng.range(0.0, 5.6));
+            histogram.record(value);
+            values.push(value);
+        }
+        values.sort_by(f64::total_cmp);
+
+        for p in [0.01, 0.25, 0.5, 0.9, 0.95, 0.99, 0.999] {
+            let exact = exact_percentile(&values, p);
+            let estimate = histogram.percentile(p);
+            assert!((estimate - exact).abs() / exact < 0.01, "p{}: {} vs {}", p, estimate, exact);
+        }
+        assert_eq!(histogram.percentile(0.0), values[0]);
+        assert_eq!(histogram.percentile(1.0), values[values.len() - 1]);
+        assert_eq!(histogram.count(), 20_000);
+
+        // 単一の値はそのまま返る
+        let mut single = LogHistogram::default();
+        single.record(16.0);
+        single.record(f64::NAN);
+        single.record(-1.0);
+        assert_eq!(single.summary().p50, 16.0);
+        assert_eq!(single.count(), 1);
+    }
+
+    #[test]
+    fn merges_and_round_trips_through_json() {
+        let mut a = LogHistogram::default();
+        let mut b = LogHistogram::default();
+        let mut all = LogHistogram::default();
+        for i in 0..500 {
+            let value = i as f64 * 0.7;
+            if i % 3 == 0 { a.record(value) } else { b.record(value) }
+            all.record(value);
+        }
+
+        // 別セッションの集計をJSONで受け取って足し合わせる
+        let json = serde_json::to_string(&b).unwrap();
+        let restored: LogHistogram = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, b);
+        a.merge(&restored).unwrap();
+        assert_eq!(a.summary(), all.summary());
+
+        assert!(a.merge(&LogHistogram::new(0.01)).is_err());
+        let tampered = json.replace(&format!("\"count\":{}", b.count()), "\"count\":1");
+        assert!(serde_json::from_str::<LogHistogram>(&tampered).is_err());
+    }
+}
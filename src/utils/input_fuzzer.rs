@@ -0,0 +1,303 @@
+//! マップと操作部品への合成入力（ファジング）
+//!
+//! ポインタ・ホイール・ドラッグ・キーボードのイベントを指定の発生率で組み立て、DOMへ送る。
+//! どの要素にどの操作を送るかはシード付き乱数で決めるので、同じシードなら同じ操作列になる。
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use crate::sim::rng::SimRng;
+use crate::utils::input_latency::{InteractionKind, LatencyTag};
+
+#[wasm_bindgen(inline_js = r#"
+function pointAt(el, fx, fy) {
+    const rect = el.getBoundingClientRect();
+    return { clientX: rect.left + rect.width * fx, clientY: rect.top + rect.height * fy };
+}
+
+function pointer(el, type, point, buttons) {
+    const init = { bubbles: true, cancelable: true, view: window, button: 0, buttons, ...point };
+    if (typeof PointerEvent !== 'undefined') {
+        el.dispatchEvent(new PointerEvent(type.replace('mouse', 'pointer'), { ...init, pointerId: 1, pointerType: 'mouse', isPrimary: true }));
+    }
+    el.dispatchEvent(new MouseEvent(type, init));
+}
+
+export function dispatchFuzzEvent(selector, kind, pick, fx, fy, dx, dy, key) {
+    const targets = document.querySelectorAll(selector);
+    if (targets.length === 0) return false;
+    const el = targets[Math.min(targets.length - 1, Math.floor(pick * targets.length))];
+    const start = pointAt(el, fx, fy);
+    switch (kind) {
+        case 'click':
+            pointer(el, 'mousedown', start, 1);
+            pointer(el, 'mouseup', start, 0);
+            el.dispatchEvent(new MouseEvent('click', { bubbles: true, cancelable: true, view: window, ...start }));
+            break;
+        case 'wheel':
+            el.dispatchEvent(new WheelEvent('wheel', { bubbles: true, cancelable: true, view: window, deltaY: dy, deltaMode: 0, ...start }));
+            break;
+        case 'drag': {
+            // Leafletはmousedown後のmousemoveをdocumentで受ける
+            pointer(el, 'mousedown', start, 1);
+            const steps = 5;
+            for (let i = 1; i <= steps; i++) {
+                const point = { clientX: start.clientX + dx * i / steps, clientY: start.clientY + dy * i / steps };
+                pointer(document, 'mousemove', point, 1);
+            }
+            pointer(document, 'mouseup', { clientX: start.clientX + dx, clientY: start.clientY + dy }, 0);
+            break;
+        }
+        case 'key':
+            if (el.tabIndex < 0 && !el.hasAttribute('tabindex')) el.setAttribute('tabindex', '0');
+            el.focus({ preventScroll: true });
+            el.dispatchEvent(new KeyboardEvent('keydown', { bubbles: true, cancelable: true, key, code: key }));
+            el.dispatchEvent(new KeyboardEvent('keyup', { bubbles: true, cancelable: true, key, code: key }));
+            break;
+        default:
+            return false;
+    }
+    return true;
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = dispatchFuzzEvent)]
+    fn dispatch_fuzz_event(selector: &str, kind: &str, pick: f64, fx: f64, fy: f64, dx: f64, dy: f64, key: &str) -> bool;
+}
+
+/// キーボード操作で送るキー（Leafletのパン・ズーム）
+const KEYS: [&str; 6] = ["ArrowUp", "ArrowDown", "ArrowLeft", "ArrowRight", "+", "-"];
+/// ドラッグの最大移動量（px）
+const MAX_DRAG_PX: f64 = 200.0;
+/// 1ティックで送るイベントの上限（タブが裏にあった後の追いつきを抑える）
+const MAX_EVENTS_PER_TICK: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FuzzKind {
+    Click,
+    Wheel,
+    Drag,
+    Key,
+}
+
+impl FuzzKind {
+    pub const ALL: [FuzzKind; 4] = [FuzzKind::Click, FuzzKind::Wheel, FuzzKind::Drag, FuzzKind::Key];
+
+    fn js_name(&self) -> &'static str {
+        match self {
+            FuzzKind::Click => "click",
+            FuzzKind::Wheel => "wheel",
+            FuzzKind::Drag => "drag",
+            FuzzKind::Key => "key",
+        }
+    }
+
+    /// 遅延を集計する操作の種類
+    pub fn interaction_kind(&self) -> InteractionKind {
+        match self {
+            FuzzKind::Click => InteractionKind::Click,
+            FuzzKind::Wheel => InteractionKind::WheelZoom,
+            FuzzKind::Drag => InteractionKind::Pan,
+            FuzzKind::Key => InteractionKind::Key,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FuzzKind::Click => "クリック",
+            FuzzKind::Wheel => "ホイール",
+            FuzzKind::Drag => "ドラッグ",
+            FuzzKind::Key => "キー",
+        }
+    }
+}
+
+/// 操作を送る要素と、その要素に送ってよい操作
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzTarget {
+    /// 遅延のタグに使う名前
+    pub name: &'static str,
+    pub selector: &'static str,
+    pub weight: f64,
+    pub kinds: &'static [FuzzKind],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FuzzConfig {
+    pub events_per_second: f64,
+    /// `FuzzKind::ALL` の順の重み
+    pub kind_weights: [f64; 4],
+    pub seed: u64,
+    /// 送り続ける時間（ms）。経過後に遅延を判定する
+    pub duration_ms: f64,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self { events_per_second: 10.0, kind_weights: [3.0, 2.0, 2.0, 1.0], seed: 1, duration_ms: 30_000.0 }
+    }
+}
+
+/// 1件の合成入力
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzAction {
+    /// `FuzzTarget` の添字
+    pub target: usize,
+    pub kind: FuzzKind,
+    /// セレクタに一致した要素のうちどれに送るか（0〜1）
+    pub element: f64,
+    /// 要素内の位置（0〜1）
+    pub x: f64,
+    pub y: f64,
+    /// ドラッグの移動量、またはホイールの量（px）
+    pub dx: f64,
+    pub dy: f64,
+    pub key: &'static str,
+}
+
+pub struct InputFuzzer {
+    config: FuzzConfig,
+    targets: Vec<FuzzTarget>,
+    rng: SimRng,
+    /// 発生率から積算した、まだ送っていないイベント数（端数）
+    pending: f64,
+}
+
+impl InputFuzzer {
+    pub fn new(config: FuzzConfig, targets: Vec<FuzzTarget>) -> Result<Self, String> {
+        if config.events_per_second.is_nan() || config.events_per_second < 0.0 {
+            return Err("events_per_second must not be negative".to_string());
+        }
+        if config.kind_weights.iter().any(|w| w.is_nan() || *w < 0.0) {
+            return Err("kind weights must not be negative".to_string());
+        }
+        if config.duration_ms.is_nan() || config.duration_ms <= 0.0 {
+            return Err("duration_ms must be positive".to_string());
+        }
+        // 各ターゲットに、重みが正の操作が1つ以上ある
+        let usable = |target: &FuzzTarget| {
+            target.weight > 0.0 && target.kinds.iter().any(|kind| config.kind_weights[kind_index(*kind)] > 0.0)
+        };
+        if !targets.iter().any(usable) {
+            return Err("no target accepts an enabled input kind".to_string());
+        }
+        let targets = targets.into_iter().map(|t| if usable(&t) { t } else { FuzzTarget { weight: 0.0, ..t } }).collect();
+        Ok(Self { rng: SimRng::new(config.seed), config, targets, pending: 0.0 })
+    }
+
+    pub fn config(&self) -> &FuzzConfig {
+        &self.config
+    }
+
+    /// `elapsed_ms` の間に送るイベント数
+    pub fn due(&mut self, elapsed_ms: f64) -> usize {
+        self.pending += self.config.events_per_second * elapsed_ms / 1000.0;
+        let due = self.pending.floor();
+        self.pending -= due;
+        (due as usize).min(MAX_EVENTS_PER_TICK)
+    }
+
+    pub fn next_action(&mut self) -> FuzzAction {
+        let target = pick_weighted(&mut self.rng, self.targets.iter().map(|t| t.weight));
+        let kinds = self.targets[target].kinds;
+        let kind = kinds[pick_weighted(&mut self.rng, kinds.iter().map(|k| self.config.kind_weights[kind_index(*k)]))];
+        let element = self.rng.next_f64();
+        let x = self.rng.range(0.1, 0.9);
+        let y = self.rng.range(0.1, 0.9);
+        let (dx, dy) = match kind {
+            FuzzKind::Drag => (self.rng.range(-MAX_DRAG_PX, MAX_DRAG_PX), self.rng.range(-MAX_DRAG_PX, MAX_DRAG_PX)),
+            FuzzKind::Wheel => (0.0, if self.rng.next_f64() < 0.5 { -100.0 } else { 100.0 }),
+            _ => (0.0, 0.0),
+        };
+        let key = KEYS[(self.rng.next_f64() * KEYS.len() as f64) as usize % KEYS.len()];
+        FuzzAction { target, kind, element, x, y, dx, dy, key }
+    }
+
+    /// 測定値のタグ（操作の種類と送り先）
+    pub fn tag(&self, action: &FuzzAction) -> LatencyTag {
+        LatencyTag::new(action.kind.interaction_kind(), self.targets[action.target].name)
+    }
+
+    /// DOMへ送る。対象の要素がなければ `false`
+    pub fn dispatch(&self, action: &FuzzAction) -> bool {
+        dispatch_fuzz_event(
+            self.targets[action.target].selector,
+            action.kind.js_name(),
+            action.element,
+            action.x,
+            action.y,
+            action.dx,
+            action.dy,
+            action.key,
+        )
+    }
+}
+
+fn kind_index(kind: FuzzKind) -> usize {
+    FuzzKind::ALL.iter().position(|k| *k == kind).unwrap_or(0)
+}
+
+/// 重みに従って添字を選ぶ（正の重みが1つ以上あること）
+fn pick_weighted(rng: &mut SimRng, weights: impl Iterator<Item = f64> + Clone) -> usize {
+    let total: f64 = weights.clone().sum();
+    let mut target = rng.next_f64() * total;
+    let mut last_positive = 0;
+    for (index, weight) in weights.enumerate() {
+        if weight > 0.0 {
+            if target < weight {
+                return index;
+            }
+            last_positive = index;
+        }
+        target -= weight;
+    }
+    last_positive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: FuzzTarget = FuzzTarget { name: "map", selector: ".map", weight: 3.0, kinds: &FuzzKind::ALL };
+    const BUTTON: FuzzTarget = FuzzTarget { name: "button", selector: ".button", weight: 1.0, kinds: &[FuzzKind::Click] };
+
+    #[test]
+    fn actions_are_deterministic_and_respect_target_kinds() {
+        let config = FuzzConfig { seed: 42, ..Default::default() };
+        let run = || {
+            let mut fuzzer = InputFuzzer::new(config.clone(), vec![MAP, BUTTON]).unwrap();
+            (0..500).map(|_| fuzzer.next_action()).collect::<Vec<_>>()
+        };
+        let actions = run();
+        assert_eq!(actions, run());
+
+        assert!(actions.iter().filter(|a| a.target == 1).all(|a| a.kind == FuzzKind::Click));
+        for kind in FuzzKind::ALL {
+            assert!(actions.iter().any(|a| a.kind == kind), "{:?} never chosen", kind);
+        }
+        let to_button = actions.iter().filter(|a| a.target == 1).count() as f64 / actions.len() as f64;
+        assert!((to_button - 0.25).abs() < 0.06, "share = {}", to_button);
+        assert!(actions.iter().all(|a| (0.1..=0.9).contains(&a.x) && a.dx.abs() <= MAX_DRAG_PX && (0.0..1.0).contains(&a.element)));
+
+        let fuzzer = InputFuzzer::new(config.clone(), vec![MAP, BUTTON]).unwrap();
+        let drag = actions.iter().find(|a| a.kind == FuzzKind::Drag).unwrap();
+        assert_eq!(fuzzer.tag(drag), LatencyTag::new(InteractionKind::Pan, "map"));
+    }
+
+    #[test]
+    fn rate_is_accumulated_and_unusable_targets_are_skipped() {
+        // クリックを無効にするとボタンは対象外になる
+        let config = FuzzConfig { events_per_second: 25.0, kind_weights: [0.0, 1.0, 1.0, 0.0], seed: 7, ..Default::default() };
+        let mut fuzzer = InputFuzzer::new(config, vec![MAP, BUTTON]).unwrap();
+        assert_eq!(fuzzer.targets[1].weight, 0.0);
+        assert!((0..200).all(|_| fuzzer.next_action().target == 0));
+
+        // 16msごとに1秒分で25件
+        let total: usize = (0..62).map(|_| fuzzer.due(16.0)).sum::<usize>() + fuzzer.due(8.0);
+        assert_eq!(total, 25);
+        assert_eq!(fuzzer.due(10_000.0), MAX_EVENTS_PER_TICK);
+
+        let only_clicks = FuzzConfig { kind_weights: [1.0, 0.0, 0.0, 0.0], ..Default::default() };
+        assert!(InputFuzzer::new(only_clicks, vec![FuzzTarget { kinds: &[FuzzKind::Wheel], ..MAP }]).is_err());
+    }
+}
@@ -1,15 +1,146 @@
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 use web_sys::{window, Performance};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::rc::Rc;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::closure::Closure;
+use crate::utils::live_handles::{self, HandleKind};
+use crate::utils::histogram::LogHistogram;
+use crate::utils::storage::{load_item, save_item};
+
+/// セッションをまたいだ累計の分布の保存先（localStorage）
+const CUMULATIVE_STORAGE_KEY: &str = "input-latency-cumulative";
+
+/// 過去のセッションの累計
+pub fn load_cumulative() -> Option<LogHistogram> {
+    load_item(CUMULATIVE_STORAGE_KEY).and_then(|json| serde_json::from_str(&json).ok())
+}
+
+/// 操作の種類。種類ごとに遅延の目標が異なる
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum InteractionKind {
+    Click,
+    Pan,
+    WheelZoom,
+    Slider,
+    Key,
+}
+
+impl InteractionKind {
+    const ALL: [InteractionKind; 5] = [
+        InteractionKind::Click,
+        InteractionKind::Pan,
+        InteractionKind::WheelZoom,
+        InteractionKind::Slider,
+        InteractionKind::Key,
+    ];
+
+    /// `name()` の逆変換
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InteractionKind::Click => "click",
+            InteractionKind::Pan => "pan",
+            InteractionKind::WheelZoom => "wheel-zoom",
+            InteractionKind::Slider => "slider",
+            InteractionKind::Key => "key",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InteractionKind::Click => "クリック",
+            InteractionKind::Pan => "ドラッグ/パン",
+            InteractionKind::WheelZoom => "ホイールズーム",
+            InteractionKind::Slider => "スライダー",
+            InteractionKind::Key => "キー",
+        }
+    }
+
+    /// P95の既定の目標（ms）。追従が見える連続操作ほど短い
+    pub fn default_target_ms(&self) -> f64 {
+        match self {
+            InteractionKind::Click => 200.0,
+            InteractionKind::Pan => 100.0,
+            InteractionKind::WheelZoom => 150.0,
+            InteractionKind::Slider => 100.0,
+            InteractionKind::Key => 200.0,
+        }
+    }
+}
+
+/// 測定値のタグ（操作の種類と対象のUI）
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LatencyTag {
+    pub kind: InteractionKind,
+    pub target: String,
+}
+
+impl LatencyTag {
+    pub fn new(kind: InteractionKind, target: impl Into<String>) -> Self {
+        Self { kind, target: target.into() }
+    }
+}
+
+impl std::fmt::Display for LatencyTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.kind.name(), self.target)
+    }
+}
+
+/// タグごとの統計と目標
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaggedLatencyStats {
+    pub tag: LatencyTag,
+    pub stats: LatencyStats,
+    pub target_ms: f64,
+}
+
+impl TaggedLatencyStats {
+    pub fn meets_target(&self) -> bool {
+        self.stats.meets_target(self.target_ms)
+    }
+}
+
+/// 全体とタグごとの測定値の分布（件数によらずメモリは一定）
+#[derive(Debug, Default)]
+struct Samples {
+    all: LogHistogram,
+    tagged: BTreeMap<LatencyTag, LogHistogram>,
+}
+
+impl Samples {
+    fn record(&mut self, tag: Option<&LatencyTag>, latency: f64) {
+        self.all.record(latency);
+        if let Some(tag) = tag {
+            self.tagged.entry(tag.clone()).or_default().record(latency);
+        }
+    }
+
+    fn tagged_stats(&self, targets: &BTreeMap<LatencyTag, f64>) -> Vec<TaggedLatencyStats> {
+        self.tagged
+            .iter()
+            .map(|(tag, histogram)| TaggedLatencyStats {
+                tag: tag.clone(),
+                stats: LatencyStats::from_histogram(histogram),
+                target_ms: targets.get(tag).copied().unwrap_or_else(|| tag.kind.default_target_ms()),
+            })
+            .collect()
+    }
+}
 
 /// 入力遅延測定用の構造体
 pub struct InputLatencyMeasurer {
     performance: Performance,
-    measurements: Rc<RefCell<Vec<f64>>>,
+    measurements: Rc<RefCell<Samples>>,
     last_input_time: Rc<RefCell<f64>>,
+    /// タグごとの目標の上書き（なければ種類の既定値）
+    targets: RefCell<BTreeMap<LatencyTag, f64>>,
 }
 
 impl InputLatencyMeasurer {
@@ -19,8 +150,9 @@ impl InputLatencyMeasurer {
         
         Self {
             performance,
-            measurements: Rc::new(RefCell::new(Vec::new())),
+            measurements: Rc::new(RefCell::new(Samples::default())),
             last_input_time: Rc::new(RefCell::new(0.0)),
+            targets: RefCell::new(BTreeMap::new()),
         }
     }
     
@@ -37,62 +169,54 @@ impl InputLatencyMeasurer {
         
         if start > 0.0 {
             let latency = now - start;
-            self.measurements.borrow_mut().push(latency);
-            
-            // 最大1000件まで保持
-            if self.measurements.borrow().len() > 1000 {
-                self.measurements.borrow_mut().remove(0);
-            }
+            self.measurements.borrow_mut().record(None, latency);
         }
     }
     
     /// 統計情報を取得
     pub fn get_stats(&self) -> LatencyStats {
-        let measurements = self.measurements.borrow();
-        
-        if measurements.is_empty() {
-            return LatencyStats::default();
+        LatencyStats::from_histogram(&self.measurements.borrow().all)
+    }
+    
+    /// 全測定値の分布（別セッションの集計と足し合わせられる）
+    pub fn histogram(&self) -> LogHistogram {
+        self.measurements.borrow().all.clone()
+    }
+    
+    /// 今回の測定値を累計に足して保存する
+    pub fn save_to_cumulative(&self) {
+        let mut total = load_cumulative().unwrap_or_default();
+        if total.merge(&self.measurements.borrow().all).is_err() {
+            return;
         }
-        
-        let mut sorted = measurements.clone();
-        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        
-        let avg = measurements.iter().sum::<f64>() / measurements.len() as f64;
-        let min = sorted[0];
-        let max = sorted[sorted.len() - 1];
-        let p50 = percentile(&sorted, 0.5);
-        let p95 = percentile(&sorted, 0.95);
-        let p99 = percentile(&sorted, 0.99);
-        
-        LatencyStats {
-            count: measurements.len(),
-            avg,
-            min,
-            max,
-            p50,
-            p95,
-            p99,
+        if let Ok(json) = serde_json::to_string(&total) {
+            save_item(CUMULATIVE_STORAGE_KEY, &json);
         }
     }
     
+    /// タグごとの統計（種類・対象の順）
+    pub fn get_tagged_stats(&self) -> Vec<TaggedLatencyStats> {
+        self.measurements.borrow().tagged_stats(&self.targets.borrow())
+    }
+    
+    /// タグの目標を上書き
+    pub fn set_target(&self, tag: LatencyTag, target_ms: f64) {
+        self.targets.borrow_mut().insert(tag, target_ms);
+    }
+    
     /// 測定をリセット
     pub fn reset(&self) {
-        self.measurements.borrow_mut().clear();
+        *self.measurements.borrow_mut() = Samples::default();
         *self.last_input_time.borrow_mut() = 0.0;
     }
     
-    /// 直接測定値を追加
-    pub fn add_measurement(&self, latency: f64) {
-        self.measurements.borrow_mut().push(latency);
-        
-        // 最大1000件まで保持
-        if self.measurements.borrow().len() > 1000 {
-            self.measurements.borrow_mut().remove(0);
-        }
+    /// タグ付きで測定値を追加（全体の統計にも含める）
+    pub fn add_tagged_measurement(&self, tag: &LatencyTag, latency: f64) {
+        self.measurements.borrow_mut().record(Some(tag), latency);
     }
     
-    /// クリック開始時刻を記録し、次のフレームで遅延を測定するクロージャを返す
-    pub fn measure_with_raf<F>(&self, callback: F) -> impl FnOnce()
+    /// 入力開始時刻を記録し、次のフレームで遅延を `tag` 付きで測定するクロージャを返す
+    pub fn measure_with_raf<F>(&self, tag: LatencyTag, callback: F) -> impl FnOnce()
     where
         F: FnOnce(f64) + 'static,
     {
@@ -102,28 +226,27 @@ impl InputLatencyMeasurer {
         
         move || {
             let window = window().expect("Window should exist");
-            let closure = Closure::once(move || {
+            // 呼ばれた時点でクロージャは解放される
+            let closure = Closure::once_into_js(move || {
+                live_handles::release(HandleKind::PendingClosure);
                 let end_time = performance.now();
                 let latency = end_time - start_time;
                 
                 // 測定値を記録
-                measurements.borrow_mut().push(latency);
-                if measurements.borrow().len() > 1000 {
-                    measurements.borrow_mut().remove(0);
-                }
+                measurements.borrow_mut().record(Some(&tag), latency);
                 
                 // コールバックを実行
                 callback(latency);
             });
             
-            window.request_animation_frame(closure.as_ref().unchecked_ref())
+            window.request_animation_frame(closure.unchecked_ref())
                 .expect("Failed to request animation frame");
-            closure.forget();
+            live_handles::acquire(HandleKind::PendingClosure);
         }
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LatencyStats {
     pub count: usize,
     pub avg: f64,
@@ -135,6 +258,47 @@ pub struct LatencyStats {
 }
 
 impl LatencyStats {
+    /// 測定値（順不同）から統計を計算
+    pub fn from_samples(measurements: &[f64]) -> Self {
+        if measurements.is_empty() {
+            return Self::default();
+        }
+        
+        let mut sorted = measurements.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        
+        let avg = measurements.iter().sum::<f64>() / measurements.len() as f64;
+        let min = sorted[0];
+        let max = sorted[sorted.len() - 1];
+        let p50 = percentile(&sorted, 0.5);
+        let p95 = percentile(&sorted, 0.95);
+        let p99 = percentile(&sorted, 0.99);
+        
+        Self {
+            count: measurements.len(),
+            avg,
+            min,
+            max,
+            p50,
+            p95,
+            p99,
+        }
+    }
+    
+    /// ヒストグラムから統計を計算（パーセンタイルの相対誤差1%未満）
+    pub fn from_histogram(histogram: &LogHistogram) -> Self {
+        let summary = histogram.summary();
+        Self {
+            count: summary.count as usize,
+            avg: summary.mean,
+            min: summary.min,
+            max: summary.max,
+            p50: summary.p50,
+            p95: summary.p95,
+            p99: summary.p99,
+        }
+    }
+    
     /// 95パーセンタイルが目標値以下かチェック
     pub fn meets_target(&self, target_ms: f64) -> bool {
         self.p95 <= target_ms
@@ -161,11 +325,10 @@ impl LatencyStats {
     }
 }
 
-/// パーセンタイル計算
+/// nearest-rank法のパーセンタイル（全体のp割に達する順位の値）
 fn percentile(sorted_data: &[f64], p: f64) -> f64 {
-    let idx = (sorted_data.len() as f64 * p) as usize;
-    let idx = idx.min(sorted_data.len() - 1);
-    sorted_data[idx]
+    let rank = ((sorted_data.len() as f64 * p).ceil() as usize).max(1);
+    sorted_data[rank.min(sorted_data.len()) - 1]
 }
 
 /// JavaScriptからアクセス可能な測定インターフェース
@@ -211,4 +374,55 @@ impl LatencyMeasurer {
     pub fn reset(&self) {
         self.inner.reset();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagged_samples_are_reported_per_tag_with_their_own_target() {
+        let pan = LatencyTag::new(InteractionKind::Pan, "map");
+        let button = LatencyTag::new(InteractionKind::Click, "start-button");
+        let mut samples = Samples::default();
+        for i in 0..20 {
+            samples.record(Some(&pan), 120.0 + i as f64);
+            samples.record(Some(&button), 30.0);
+        }
+        samples.record(None, 500.0);
+
+        // 全体には両方とタグなしが混ざる
+        assert_eq!(LatencyStats::from_histogram(&samples.all).count, 41);
+
+        let mut targets = BTreeMap::new();
+        targets.insert(button.clone(), 20.0);
+        let stats = samples.tagged_stats(&targets);
+        assert_eq!(stats.iter().map(|s| s.tag.clone()).collect::<Vec<_>>(), [button, pan]);
+        assert_eq!(stats[0].target_ms, 20.0);
+        assert!(!stats[0].meets_target());
+        // パンは種類の既定値（100ms）で判定
+        assert_eq!(stats[1].target_ms, InteractionKind::Pan.default_target_ms());
+        assert_eq!(stats[1].stats.count, 20);
+        assert!(!stats[1].meets_target());
+        assert_eq!(stats[1].tag.to_string(), "pan/map");
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        // 1〜100msの100件: P50は50件目、P95は95件目
+        let samples: Vec<f64> = (1..=100).rev().map(|i| i as f64).collect();
+        let exact = LatencyStats::from_samples(&samples);
+        assert_eq!((exact.p50, exact.p95, exact.p99), (50.0, 95.0, 99.0));
+        assert_eq!(LatencyStats::from_samples(&[7.0]).p99, 7.0);
+
+        // ヒストグラム経由でも1%以内
+        let mut histogram = LogHistogram::default();
+        samples.iter().for_each(|&s| histogram.record(s));
+        let approx = LatencyStats::from_histogram(&histogram);
+        assert_eq!(approx.count, 100);
+        assert_eq!((approx.min, approx.max), (1.0, 100.0));
+        for (a, e) in [(approx.p50, 50.0), (approx.p95, 95.0), (approx.p99, 99.0)] {
+            assert!((a - e).abs() / e < 0.01, "{} vs {}", a, e);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
+use crate::utils::live_handles::{self, HandleKind};
 
 /// 軽量なInterval実装（glooの代替）
 pub struct Interval {
@@ -21,6 +22,7 @@ impl Interval {
                 millis as i32,
             )
             .expect("failed to set interval");
+        live_handles::acquire(HandleKind::Interval);
         
         Self {
             _closure: closure,
@@ -31,10 +33,22 @@ impl Interval {
     // Intervalを明示的に停止するメソッドを追加
     pub fn stop(&mut self) {
         if let Some(window) = web_sys::window() {
+            if self.interval_id != -1 {
+                live_handles::release(HandleKind::Interval);
+            }
             window.clear_interval_with_handle(self.interval_id);
             self.interval_id = -1; // 無効化
         }
     }
+    
+    /// 自身のコールバック内から停止する
+    ///
+    /// 実行中のクロージャを破棄しないよう、`slot` に保持したまま停止だけ行う。
+    pub fn stop_in_callback(slot: &mut Option<Interval>) {
+        if let Some(interval) = slot.as_mut() {
+            interval.stop();
+        }
+    }
 }
 
 impl Drop for Interval {
@@ -42,6 +56,7 @@ impl Drop for Interval {
         if self.interval_id != -1 {
             if let Some(window) = web_sys::window() {
                 window.clear_interval_with_handle(self.interval_id);
+                live_handles::release(HandleKind::Interval);
                 web_sys::console::log_1(&format!("Interval {} cleared on drop", self.interval_id).into());
             }
         }
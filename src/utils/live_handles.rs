@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicIsize, Ordering};
+#[cfg(any(test, feature = "chaos-testing"))]
+use serde::{Deserialize, Serialize};
+
+/// 解放漏れを検出するために数えるハンドルの種類
+///
+/// WASMはシングルスレッドなので `Relaxed` で十分。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleKind {
+    /// `Interval`（停止またはドロップで解放）
+    Interval,
+    /// `FrameLoop`（ドロップで解放）
+    FrameLoop,
+    /// `Closure::once_into_js` で渡したワンショットのコールバック（呼ばれた時点で解放。呼ばれなければ漏れとして残る）
+    PendingClosure,
+}
+
+static INTERVALS: AtomicIsize = AtomicIsize::new(0);
+static FRAME_LOOPS: AtomicIsize = AtomicIsize::new(0);
+static PENDING_CLOSURES: AtomicIsize = AtomicIsize::new(0);
+
+fn counter(kind: HandleKind) -> &'static AtomicIsize {
+    match kind {
+        HandleKind::Interval => &INTERVALS,
+        HandleKind::FrameLoop => &FRAME_LOOPS,
+        HandleKind::PendingClosure => &PENDING_CLOSURES,
+    }
+}
+
+#[inline]
+pub fn acquire(kind: HandleKind) {
+    counter(kind).fetch_add(1, Ordering::Relaxed);
+}
+
+#[inline]
+pub fn release(kind: HandleKind) {
+    counter(kind).fetch_sub(1, Ordering::Relaxed);
+}
+
+/// 生存中のハンドル数
+#[cfg(any(test, feature = "chaos-testing"))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LiveHandles {
+    pub intervals: isize,
+    pub frame_loops: isize,
+    pub pending_closures: isize,
+}
+
+#[cfg(feature = "chaos-testing")]
+pub fn snapshot() -> LiveHandles {
+    LiveHandles {
+        intervals: INTERVALS.load(Ordering::Relaxed),
+        frame_loops: FRAME_LOOPS.load(Ordering::Relaxed),
+        pending_closures: PENDING_CLOSURES.load(Ordering::Relaxed),
+    }
+}
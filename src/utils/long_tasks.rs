@@ -0,0 +1,345 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use crate::utils::clock::now_ms;
+use crate::utils::span_hook::{self, SpanSink};
+
+/// メインスレッドをブロックした処理の区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PipelinePhase {
+    MarkerRebuild,
+    Animate,
+    TileLoad,
+    Render,
+    Unknown,
+}
+
+impl PipelinePhase {
+    pub const ALL: [PipelinePhase; 5] = [
+        PipelinePhase::MarkerRebuild,
+        PipelinePhase::Animate,
+        PipelinePhase::TileLoad,
+        PipelinePhase::Render,
+        PipelinePhase::Unknown,
+    ];
+
+    /// 区間の名前（JS側の `window.__benchPhase(id, ...)` の番号は `span_hook` で名前に変換される）
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "marker-rebuild" => PipelinePhase::MarkerRebuild,
+            "animate" => PipelinePhase::Animate,
+            "tile-load" => PipelinePhase::TileLoad,
+            "render" => PipelinePhase::Render,
+            _ => PipelinePhase::Unknown,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelinePhase::MarkerRebuild => "marker-rebuild",
+            PipelinePhase::Animate => "animate",
+            PipelinePhase::TileLoad => "tile-load",
+            PipelinePhase::Render => "render",
+            PipelinePhase::Unknown => "unknown",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PipelinePhase::MarkerRebuild => "マーカー再構築",
+            PipelinePhase::Animate => "アニメーション",
+            PipelinePhase::TileLoad => "タイル読み込み",
+            PipelinePhase::Render => "Dioxus再描画",
+            PipelinePhase::Unknown => "不明",
+        }
+    }
+
+    pub fn color(&self) -> &'static str {
+        match self {
+            PipelinePhase::MarkerRebuild => "#E91E63",
+            PipelinePhase::Animate => "#2196F3",
+            PipelinePhase::TileLoad => "#4CAF50",
+            PipelinePhase::Render => "#9C27B0",
+            PipelinePhase::Unknown => "#9E9E9E",
+        }
+    }
+}
+
+/// 記録された処理区間
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseSpan {
+    pub name: &'static str,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// 長時間タスクと重なりが最大の区間を原因とみなす
+///
+/// タイル読み込みは通信待ちを含む長い区間なので、他の区間と重ならない場合のみ採用する。
+/// 区間がない場合はLoAFのスクリプト情報（呼び出し元の種類）から推定する
+pub fn attribute(start: f64, end: f64, spans: &[PhaseSpan], invoker: &str) -> PipelinePhase {
+    let mut overlaps: Vec<(PipelinePhase, f64)> = Vec::new();
+    for span in spans {
+        let overlap = end.min(span.end) - start.max(span.start);
+        if overlap <= 0.0 {
+            continue;
+        }
+        let phase = PipelinePhase::from_name(span.name);
+        match overlaps.iter_mut().find(|(p, _)| *p == phase) {
+            Some((_, total)) => *total += overlap,
+            None => overlaps.push((phase, overlap)),
+        }
+    }
+
+    let executing = overlaps
+        .iter()
+        .filter(|(phase, _)| *phase != PipelinePhase::TileLoad)
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    if let Some((phase, _)) = executing {
+        return *phase;
+    }
+    if !overlaps.is_empty() {
+        return PipelinePhase::TileLoad;
+    }
+
+    if invoker.contains("FrameRequestCallback") {
+        PipelinePhase::Animate
+    } else if invoker.contains("TimerHandler") {
+        // マーカー更新はsetTimeout経由で実行される
+        PipelinePhase::MarkerRebuild
+    } else {
+        PipelinePhase::Unknown
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LongTaskKind {
+    /// Long Tasks API（50ms超のタスク）
+    Task,
+    /// Long Animation Frames API（50ms超のフレーム）
+    AnimationFrame,
+}
+
+impl LongTaskKind {
+    pub const ALL: [LongTaskKind; 2] = [LongTaskKind::Task, LongTaskKind::AnimationFrame];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LongTaskKind::Task => "Long Task",
+            LongTaskKind::AnimationFrame => "Long Animation Frame",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LongTaskEntry {
+    pub kind: LongTaskKind,
+    pub start_ms: f64,
+    pub duration_ms: f64,
+    /// 50msを超えた分（LoAFは `blockingDuration`）
+    pub blocking_ms: f64,
+    pub phase: PipelinePhase,
+}
+
+/// 区分ごとの件数とブロック時間の合計
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseSummary {
+    pub phase: PipelinePhase,
+    pub count: u32,
+    pub blocking_ms: f64,
+    pub longest_ms: f64,
+}
+
+/// 指定した種類のエントリを区分ごとに集計する
+///
+/// 同じ処理が Long Task と LoAF の両方で報告されるため、種類をまたいで合計しない
+pub fn summarize(entries: &[LongTaskEntry], kind: LongTaskKind) -> Vec<PhaseSummary> {
+    PipelinePhase::ALL
+        .iter()
+        .map(|&phase| {
+            let matching = entries.iter().filter(|e| e.kind == kind && e.phase == phase);
+            PhaseSummary {
+                phase,
+                count: matching.clone().count() as u32,
+                blocking_ms: matching.clone().map(|e| e.blocking_ms).sum(),
+                longest_ms: matching.map(|e| e.duration_ms).fold(0.0, f64::max),
+            }
+        })
+        .filter(|s| s.count > 0)
+        .collect()
+}
+
+/// 原因推定のために保持する直近の区間数
+const PHASE_LOG_LIMIT: usize = 4096;
+
+/// (種類, 開始, 継続時間, ブロック時間, 呼び出し元)
+type LongTaskCallback = Closure<dyn FnMut(String, f64, f64, f64, String)>;
+
+#[wasm_bindgen(inline_js = r#"
+export function observeLongTasks(callback) {
+    const observers = [];
+    const observe = (type, handler) => {
+        if (!PerformanceObserver.supportedEntryTypes || !PerformanceObserver.supportedEntryTypes.includes(type)) {
+            return;
+        }
+        const observer = new PerformanceObserver((list) => list.getEntries().forEach(handler));
+        observer.observe({ type, buffered: false });
+        observers.push(observer);
+    };
+
+    observe('longtask', (entry) => {
+        callback('task', entry.startTime, entry.duration, Math.max(0, entry.duration - 50), '');
+    });
+    observe('long-animation-frame', (entry) => {
+        const invoker = (entry.scripts || []).map((s) => s.invokerType + ':' + s.invoker).join(' ');
+        callback('frame', entry.startTime, entry.duration, entry.blockingDuration || 0, invoker);
+    });
+
+    return () => observers.forEach((o) => o.disconnect());
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = observeLongTasks)]
+    fn observe_long_tasks(callback: &LongTaskCallback) -> js_sys::Function;
+}
+
+/// Long Tasks / Long Animation Frames の監視。ドロップ時に解除する
+pub struct LongTaskMonitor {
+    _callback: LongTaskCallback,
+    _sink: SpanSink,
+    disconnect: js_sys::Function,
+}
+
+impl LongTaskMonitor {
+    pub fn start(mut on_entry: impl FnMut(LongTaskEntry) + 'static) -> Self {
+        // マップのJSコードや `PhaseScope` から届く区間を保持
+        let log = Rc::new(RefCell::new(VecDeque::<PhaseSpan>::with_capacity(PHASE_LOG_LIMIT + 1)));
+        let sink_log = log.clone();
+        let sink = SpanSink::add(move |name, start, end| {
+            let mut log = sink_log.borrow_mut();
+            log.push_back(PhaseSpan { name, start, end });
+            if log.len() > PHASE_LOG_LIMIT {
+                log.pop_front();
+            }
+        });
+
+        let callback = Closure::new(
+            move |kind: String, start: f64, duration: f64, blocking: f64, invoker: String| {
+                let end = start + duration;
+                let spans: Vec<PhaseSpan> = log
+                    .borrow()
+                    .iter()
+                    .filter(|span| span.end > start && span.start < end)
+                    .cloned()
+                    .collect();
+                on_entry(LongTaskEntry {
+                    kind: if kind == "frame" { LongTaskKind::AnimationFrame } else { LongTaskKind::Task },
+                    start_ms: start,
+                    duration_ms: duration,
+                    blocking_ms: blocking,
+                    phase: attribute(start, end, &spans, &invoker),
+                });
+            },
+        );
+        let disconnect = observe_long_tasks(&callback);
+
+        Self {
+            _callback: callback,
+            _sink: sink,
+            disconnect,
+        }
+    }
+}
+
+impl Drop for LongTaskMonitor {
+    fn drop(&mut self) {
+        let _ = self.disconnect.call0(&JsValue::NULL);
+    }
+}
+
+/// スコープの開始から終了までを処理区間として記録する（監視中のみ）
+pub struct PhaseScope {
+    phase: PipelinePhase,
+    start: Option<f64>,
+}
+
+impl PhaseScope {
+    pub fn new(phase: PipelinePhase) -> Self {
+        Self {
+            phase,
+            start: span_hook::is_active().then(now_ms),
+        }
+    }
+}
+
+impl Drop for PhaseScope {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            span_hook::emit(self.phase.name(), start, now_ms());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(name: &'static str, start: f64, end: f64) -> PhaseSpan {
+        PhaseSpan { name, start, end }
+    }
+
+    #[test]
+    fn attributes_to_phase_with_largest_overlap() {
+        let spans = vec![
+            span("animate", 100.0, 110.0),
+            span("marker-rebuild", 105.0, 180.0),
+            span("animate", 170.0, 200.0),
+        ];
+        assert_eq!(attribute(100.0, 190.0, &spans, ""), PipelinePhase::MarkerRebuild);
+        assert_eq!(attribute(185.0, 240.0, &spans, ""), PipelinePhase::Animate);
+
+        // タイル読み込み中でも実行中の区間があればそちらを優先
+        let with_tiles = vec![span("tile-load", 0.0, 1000.0), span("animate", 400.0, 420.0)];
+        assert_eq!(attribute(390.0, 460.0, &with_tiles, ""), PipelinePhase::Animate);
+        assert_eq!(attribute(500.0, 560.0, &with_tiles, ""), PipelinePhase::TileLoad);
+
+        // 区間がない場合はLoAFの呼び出し元から推定
+        assert_eq!(attribute(300.0, 360.0, &spans, "user-callback:FrameRequestCallback"), PipelinePhase::Animate);
+        assert_eq!(attribute(300.0, 360.0, &[], ""), PipelinePhase::Unknown);
+    }
+
+    #[test]
+    fn summarizes_per_phase() {
+        let entry = |phase, duration_ms: f64| LongTaskEntry {
+            kind: LongTaskKind::Task,
+            start_ms: 0.0,
+            duration_ms,
+            blocking_ms: duration_ms - 50.0,
+            phase,
+        };
+        // 同じ処理がLoAFとしても報告される
+        let frame = LongTaskEntry {
+            kind: LongTaskKind::AnimationFrame,
+            ..entry(PipelinePhase::Animate, 130.0)
+        };
+        let entries = [
+            entry(PipelinePhase::Animate, 60.0),
+            entry(PipelinePhase::Animate, 120.0),
+            entry(PipelinePhase::TileLoad, 55.0),
+            frame,
+        ];
+        let summary = summarize(&entries, LongTaskKind::Task);
+
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].phase, PipelinePhase::Animate);
+        assert_eq!(summary[0].count, 2);
+        assert_eq!(summary[0].blocking_ms, 80.0);
+        assert_eq!(summary[0].longest_ms, 120.0);
+
+        let frames = summarize(&entries, LongTaskKind::AnimationFrame);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].blocking_ms, 80.0);
+    }
+}
@@ -0,0 +1,152 @@
+//! マップ（Leaflet）への実際の入力の遅延測定と遅延の注入
+//!
+//! Leafletはホイールやキー入力の伝播を止めるため、Dioxusのイベントでは受け取れない。
+//! windowにキャプチャフェーズのリスナーを付け、入力の発生時刻（`event.timeStamp`）から
+//! 次のフレームまでを操作の種類ごとに通知する。
+//!
+//! 遅延を注入する間は、入力をLeafletのハンドラに届く前に止め、複製を発生順のまま遅らせて送り直す。
+//! そのときの遅延は送り直した時刻から測り、注入した遅延は別に通知する。
+
+use wasm_bindgen::prelude::*;
+use crate::utils::input_latency::{InteractionKind, LatencyTag};
+
+/// (種類, 入力の発生時刻, 次のフレームの時刻, 注入した遅延)
+type MapInputCallback = Closure<dyn FnMut(String, f64, f64, f64)>;
+/// 次の入力に注入する遅延（ms, 0以下なら遅らせない）
+type InputDelayCallback = Closure<dyn FnMut() -> f64>;
+
+#[wasm_bindgen(inline_js = r#"
+// Leafletが使う入力のうち遅延の対象にするもの（タッチは複製できないため対象外）
+const TYPES = ['pointerdown', 'pointermove', 'pointerup', 'pointercancel', 'mousedown', 'mousemove', 'mouseup',
+    'click', 'dblclick', 'contextmenu', 'wheel', 'keydown', 'keyup'];
+// ドラッグ中はマップの外に出た移動・離す操作も対象にする
+const DRAG_TYPES = new Set(['pointermove', 'pointerup', 'pointercancel', 'mousemove', 'mouseup']);
+
+export function observeMapInput(selector, inputDelay, callback) {
+    let active = true;
+    let dragging = false;
+    // 遅らせた入力を発生順に送り直すための、最後の送信予定時刻
+    let releaseAt = 0;
+    const timers = new Set();
+    const replayed = new WeakSet();
+    const pending = new Set();
+    const kindOf = (e) => {
+        switch (e.type) {
+            case 'pointermove': return (e.buttons & 1) ? 'pan' : null;
+            case 'wheel': return 'wheel-zoom';
+            case 'keydown': return 'key';
+            case 'click': return 'click';
+            default: return null;
+        }
+    };
+    const measure = (kind, start, injected) => {
+        // 連続する入力はフレームごとに最初の1件だけ測る
+        if (!kind || pending.has(kind)) return;
+        pending.add(kind);
+        requestAnimationFrame(() => {
+            pending.delete(kind);
+            if (active) callback(kind, start, performance.now(), injected);
+        });
+    };
+    const listener = (e) => {
+        // 合成イベント（入力ファジング）は送り元で測る。送り直した複製はそのまま通す
+        if (!e.isTrusted || replayed.has(e)) return;
+        const inside = e.target instanceof Element && e.target.closest(selector) !== null;
+        if (e.type === 'pointerdown' && inside) dragging = true;
+        const captured = inside || (dragging && DRAG_TYPES.has(e.type));
+        if (e.type === 'pointerup' || e.type === 'pointercancel') dragging = false;
+        if (!captured) return;
+
+        const now = performance.now();
+        const delay = Math.max(inputDelay(), 0);
+        if (delay === 0 && releaseAt <= now) {
+            measure(kindOf(e), e.timeStamp, 0);
+            return;
+        }
+        e.stopImmediatePropagation();
+        if (e.cancelable && (e.type === 'wheel' || e.type === 'keydown')) e.preventDefault();
+        releaseAt = Math.max(releaseAt, now + delay);
+        const target = e.target;
+        const copy = new e.constructor(e.type, e);
+        replayed.add(copy);
+        const timer = setTimeout(() => {
+            timers.delete(timer);
+            if (!active) return;
+            const start = performance.now();
+            target.dispatchEvent(copy);
+            measure(kindOf(e), start, start - e.timeStamp);
+        }, releaseAt - now);
+        timers.add(timer);
+    };
+    TYPES.forEach((type) => window.addEventListener(type, listener, { capture: true, passive: false }));
+    return () => {
+        active = false;
+        timers.forEach((timer) => clearTimeout(timer));
+        timers.clear();
+        TYPES.forEach((type) => window.removeEventListener(type, listener, { capture: true }));
+    };
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = observeMapInput)]
+    fn observe_map_input(selector: &str, input_delay: &InputDelayCallback, callback: &MapInputCallback) -> js_sys::Function;
+}
+
+/// マップへの1件の入力の測定
+#[derive(Clone, Debug)]
+pub struct MapInput {
+    pub tag: LatencyTag,
+    /// 測定の開始時刻（遅らせた入力は送り直した時刻, ms）
+    pub start_ms: f64,
+    /// 開始から次のフレームまで（ms）
+    pub latency_ms: f64,
+    /// 注入した遅延（ms, 遅らせていなければ0）
+    pub injected_delay_ms: f64,
+}
+
+/// マップへの入力の監視。ドロップ時に解除し、遅らせている入力は破棄する
+pub struct MapInputMonitor {
+    _input_delay: InputDelayCallback,
+    _callback: MapInputCallback,
+    disconnect: js_sys::Function,
+}
+
+impl MapInputMonitor {
+    /// `selector` に一致する要素内への入力を通知する。タグの対象は `target`
+    pub fn start(selector: &str, target: &'static str, on_input: impl FnMut(MapInput) + 'static) -> Self {
+        Self::with_input_delay(selector, target, || 0.0, on_input)
+    }
+
+    /// 入力ごとに `input_delay` の遅延（ms）を注入してマップに届ける
+    pub fn with_input_delay(
+        selector: &str,
+        target: &'static str,
+        input_delay: impl FnMut() -> f64 + 'static,
+        mut on_input: impl FnMut(MapInput) + 'static,
+    ) -> Self {
+        let input_delay: InputDelayCallback = Closure::new(input_delay);
+        let callback: MapInputCallback = Closure::new(move |kind: String, start: f64, end: f64, injected: f64| {
+            if let Some(kind) = InteractionKind::from_name(&kind) {
+                on_input(MapInput {
+                    tag: LatencyTag::new(kind, target),
+                    start_ms: start,
+                    latency_ms: (end - start).max(0.0),
+                    injected_delay_ms: injected.max(0.0),
+                });
+            }
+        });
+        let disconnect = observe_map_input(selector, &input_delay, &callback);
+
+        Self {
+            _input_delay: input_delay,
+            _callback: callback,
+            disconnect,
+        }
+    }
+}
+
+impl Drop for MapInputMonitor {
+    fn drop(&mut self) {
+        let _ = self.disconnect.call0(&JsValue::NULL);
+    }
+}
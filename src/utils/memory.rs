@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// ある時点のメモリ使用量（MB）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemorySample {
+    /// 計測時刻（performance.now()基準）
+    pub timestamp_ms: f64,
+    /// JSヒープ使用量（`performance.memory` 対応ブラウザのみ）
+    pub js_heap_mb: Option<f64>,
+    /// WASMリニアメモリのサイズ
+    pub wasm_memory_mb: f64,
+    /// アロケータが把握している使用中メモリ（計測用アロケータ有効時のみ）
+    pub allocator_mb: Option<f64>,
+}
+
+impl MemorySample {
+    /// 現在のメモリ使用量を取得
+    pub fn capture() -> Self {
+        let performance = web_sys::window().and_then(|w| w.performance());
+        let timestamp_ms = performance.as_ref().map(|p| p.now()).unwrap_or(0.0);
+
+        Self {
+            timestamp_ms,
+            js_heap_mb: performance.as_ref().and_then(js_heap_used_bytes).map(|b| b / BYTES_PER_MB),
+            wasm_memory_mb: wasm_memory_bytes() / BYTES_PER_MB,
+            allocator_mb: crate::utils::alloc_tracker::current_stats()
+                .map(|stats| stats.live_bytes as f64 / BYTES_PER_MB),
+        }
+    }
+
+    /// JSヒープとWASMメモリの合計
+    pub fn total_mb(&self) -> f64 {
+        self.js_heap_mb.unwrap_or(0.0) + self.wasm_memory_mb
+    }
+}
+
+/// Chrome系の非標準API `performance.memory.usedJSHeapSize`
+fn js_heap_used_bytes(performance: &web_sys::Performance) -> Option<f64> {
+    let memory = js_sys::Reflect::get(performance, &"memory".into()).ok()?;
+    if memory.is_undefined() || memory.is_null() {
+        return None;
+    }
+    js_sys::Reflect::get(&memory, &"usedJSHeapSize".into()).ok()?.as_f64()
+}
+
+fn wasm_memory_bytes() -> f64 {
+    wasm_bindgen::memory()
+        .dyn_into::<js_sys::WebAssembly::Memory>()
+        .ok()
+        .and_then(|memory| memory.buffer().dyn_into::<js_sys::ArrayBuffer>().ok())
+        .map(|buffer| buffer.byte_length() as f64)
+        .unwrap_or(0.0)
+}
@@ -1,4 +1,34 @@
+// ネイティブベンチマークと共有するモジュールはライブラリ側（`src/lib.rs`）で定義する
+pub use leaflet_webgl_hybrid_poc::utils::{alloc_tracker, benchmark_export, chaos_intensity, frame_stats, histogram};
+#[cfg(feature = "chaos-testing")]
+pub use leaflet_webgl_hybrid_poc::utils::chaos_scenario;
+
 pub mod fps_counter;
+pub mod adaptive_render;
 pub mod interval;
+pub mod clock;
 pub mod input_latency;
-pub mod performance_metrics;
\ No newline at end of file
+pub mod input_fuzzer;
+pub mod map_input;
+pub mod event_timing;
+pub mod memory;
+pub mod live_handles;
+pub mod performance_metrics;
+pub mod performance_monitor;
+pub mod benchmark_metrics;
+pub mod statistics;
+pub mod multi_run;
+pub mod seeded_random;
+#[cfg(any(test, feature = "chaos-testing"))]
+pub mod chaos_impact;
+pub mod chaos_report;
+#[cfg(any(test, feature = "chaos-testing"))]
+pub mod soak;
+#[cfg(any(test, feature = "chaos-testing"))]
+pub mod tile_faults;
+pub mod chaos_effects;
+pub mod storage;
+pub mod telemetry;
+pub mod span_hook;
+pub mod long_tasks;
+pub mod profiler;
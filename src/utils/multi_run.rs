@@ -0,0 +1,391 @@
+//! 同じ構成を複数回（シードを変えて）計測し、構成間の差を統計的に比較する
+//!
+//! 構成は1回ずつ交互に実行し、時間経過による端末の状態変化（発熱など）が特定の構成に偏らないようにする。
+//! 同じ回の構成には同じシードを使うため、各構成は同じシーン群で比較される。
+
+use serde::{Deserialize, Serialize};
+use crate::hooks::RenderMode;
+use crate::utils::benchmark_metrics::{BenchmarkMetrics, ScoringConfig};
+use crate::utils::frame_stats::FrameTimeHistogram;
+use crate::utils::statistics::{welch_t_test, Summary, WelchTest};
+
+/// 信頼区間の信頼水準
+pub const CONFIDENCE: f64 = 0.95;
+/// 有意水準
+pub const ALPHA: f64 = 0.05;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunConfig {
+    pub mode: RenderMode,
+    pub object_count: i32,
+}
+
+impl RunConfig {
+    pub fn label(&self) -> String {
+        format!("{} × {}", self.mode.as_str(), self.object_count)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunPlan {
+    pub configs: Vec<RunConfig>,
+    pub runs_per_config: u32,
+    pub base_seed: u64,
+    /// 構成切り替え後、計測を始めるまでの待ち時間（マーカー再構築を含む）
+    pub warmup_ms: f64,
+    pub measure_ms: f64,
+}
+
+/// 実行順の1回分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledRun {
+    pub config_index: usize,
+    pub run_index: u32,
+    pub seed: u64,
+}
+
+impl RunPlan {
+    /// 回ごとに全構成を交互に並べる
+    pub fn schedule(&self) -> Vec<ScheduledRun> {
+        (0..self.runs_per_config)
+            .flat_map(|run_index| {
+                (0..self.configs.len()).map(move |config_index| ScheduledRun {
+                    config_index,
+                    run_index,
+                    seed: self.base_seed + run_index as u64,
+                })
+            })
+            .collect()
+    }
+}
+
+/// 1回分の計測（1秒ごとのFPSとフレーム時間の分布）
+#[derive(Debug, Clone, Default)]
+pub struct RunRecorder {
+    metrics: BenchmarkMetrics,
+    histogram: FrameTimeHistogram,
+    second_start: Option<f64>,
+    second_frames: u32,
+}
+
+impl RunRecorder {
+    pub fn on_frame(&mut self, frame_ms: f64, timestamp_ms: f64) {
+        self.histogram.record(frame_ms, timestamp_ms);
+        let start = *self.second_start.get_or_insert(timestamp_ms - frame_ms);
+        self.second_frames += 1;
+
+        let elapsed = timestamp_ms - start;
+        if elapsed >= 1000.0 {
+            self.metrics.update_fps(self.second_frames as f64 * 1000.0 / elapsed);
+            self.second_start = Some(timestamp_ms);
+            self.second_frames = 0;
+        }
+    }
+
+    pub fn record_memory(&mut self, total_mb: f64) {
+        self.metrics.record_memory(total_mb);
+    }
+
+    pub fn finish(mut self) -> BenchmarkMetrics {
+        self.metrics.frame_stats = self.histogram.stats();
+        self.metrics
+    }
+}
+
+/// 1回分の結果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RunSample {
+    pub seed: u64,
+    pub avg_fps: f64,
+    pub p95_ms: f64,
+}
+
+/// 構成ごとの要約
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigSummary {
+    pub config: RunConfig,
+    pub fps: Summary,
+    pub p95_ms: Summary,
+}
+
+/// 基準構成（先頭）との比較
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub baseline: usize,
+    pub candidate: usize,
+    /// 平均FPSの差（候補 - 基準）
+    pub fps: Option<WelchTest>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiRunResults {
+    pub plan: RunPlan,
+    /// 構成ごとの結果（実行順）
+    pub samples: Vec<Vec<RunSample>>,
+}
+
+impl MultiRunResults {
+    pub fn new(plan: RunPlan) -> Self {
+        let samples = vec![Vec::new(); plan.configs.len()];
+        Self { plan, samples }
+    }
+
+    pub fn push(&mut self, run: &ScheduledRun, metrics: &BenchmarkMetrics) {
+        self.samples[run.config_index].push(RunSample {
+            seed: run.seed,
+            avg_fps: metrics.avg_fps,
+            p95_ms: metrics.frame_stats.p95_ms,
+        });
+    }
+
+    pub fn completed_runs(&self) -> usize {
+        self.samples.iter().map(Vec::len).sum()
+    }
+
+    pub fn summaries(&self) -> Vec<ConfigSummary> {
+        self.plan
+            .configs
+            .iter()
+            .zip(&self.samples)
+            .map(|(config, samples)| {
+                let fps: Vec<f64> = samples.iter().map(|s| s.avg_fps).collect();
+                let p95: Vec<f64> = samples.iter().map(|s| s.p95_ms).collect();
+                ConfigSummary {
+                    config: config.clone(),
+                    fps: Summary::from_samples(&fps, CONFIDENCE),
+                    p95_ms: Summary::from_samples(&p95, CONFIDENCE),
+                }
+            })
+            .collect()
+    }
+
+    /// 先頭の構成を基準に他の構成を比較
+    pub fn comparisons(&self) -> Vec<Comparison> {
+        let summaries = self.summaries();
+        (1..summaries.len())
+            .map(|candidate| Comparison {
+                baseline: 0,
+                candidate,
+                fps: welch_t_test(&summaries[candidate].fps, &summaries[0].fps),
+            })
+            .collect()
+    }
+
+    /// レポート（docs/reports に貼り付けられるMarkdown）
+    pub fn to_markdown(&self, scoring: &ScoringConfig) -> String {
+        let summaries = self.summaries();
+        let mut out = String::new();
+
+        out.push_str(&format!(
+            "各構成 {} 回（シード {}〜{}、計測 {:.0}秒、ウォームアップ {:.0}秒）。目標 {:.0}fps（{}）。\n\n",
+            self.plan.runs_per_config,
+            self.plan.base_seed,
+            self.plan.base_seed + self.plan.runs_per_config.saturating_sub(1) as u64,
+            self.plan.measure_ms / 1000.0,
+            self.plan.warmup_ms / 1000.0,
+            scoring.target_fps(),
+            scoring.device_class.label(),
+        ));
+
+        out.push_str("| 構成 | n | 平均FPS | 標準偏差 | 95%信頼区間 | P95フレーム時間 | 評価 |\n");
+        out.push_str("|---|---|---|---|---|---|---|\n");
+        for s in &summaries {
+            out.push_str(&format!(
+                "| {} | {} | {:.1} | {:.2} | {:.1} – {:.1} | {:.1}ms | {} |\n",
+                s.config.label(),
+                s.fps.n,
+                s.fps.mean,
+                s.fps.stddev,
+                s.fps.ci_low,
+                s.fps.ci_high,
+                s.p95_ms.mean,
+                scoring.score(s.fps.mean).text(),
+            ));
+        }
+
+        let comparisons = self.comparisons();
+        if !comparisons.is_empty() {
+            out.push_str(&format!("\n基準: {}（Welchのt検定、α = {}）\n\n", summaries[0].config.label(), ALPHA));
+            out.push_str("| 構成 | 平均FPSの差 | t | 自由度 | p値 | 判定 |\n");
+            out.push_str("|---|---|---|---|---|---|\n");
+            for c in comparisons {
+                let label = summaries[c.candidate].config.label();
+                match c.fps {
+                    Some(test) => out.push_str(&format!(
+                        "| {} | {:+.1} | {:.2} | {:.1} | {:.4} | {} |\n",
+                        label,
+                        test.mean_diff,
+                        test.t,
+                        test.df,
+                        test.p_value,
+                        if test.is_significant(ALPHA) { "有意差あり" } else { "有意差なし" },
+                    )),
+                    None => out.push_str(&format!("| {} | - | - | - | - | 標本不足 |\n", label)),
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// 実行の進行状況
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunnerPhase {
+    Idle,
+    Warmup { index: usize, until_ms: f64 },
+    Measuring { index: usize, until_ms: f64 },
+    Finished,
+}
+
+/// `start` / `tick` が返すイベント
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunnerEvent {
+    /// 次の構成を画面に反映する
+    Apply(ScheduledRun),
+    /// 1回分の計測が終わった
+    Completed(ScheduledRun, BenchmarkMetrics),
+    Finished,
+}
+
+/// 計画に沿ってウォームアップ・計測を繰り返す（時刻は呼び出し側から渡す）
+#[derive(Debug, Clone)]
+pub struct MultiRunner {
+    schedule: Vec<ScheduledRun>,
+    phase: RunnerPhase,
+    recorder: RunRecorder,
+    pub results: MultiRunResults,
+}
+
+impl MultiRunner {
+    pub fn new(plan: RunPlan) -> Self {
+        Self {
+            schedule: plan.schedule(),
+            phase: RunnerPhase::Idle,
+            recorder: RunRecorder::default(),
+            results: MultiRunResults::new(plan),
+        }
+    }
+
+    pub fn phase(&self) -> RunnerPhase {
+        self.phase
+    }
+
+    pub fn total_runs(&self) -> usize {
+        self.schedule.len()
+    }
+
+    pub fn start(&mut self, now_ms: f64) -> RunnerEvent {
+        self.enter(0, now_ms)
+    }
+
+    fn enter(&mut self, index: usize, now_ms: f64) -> RunnerEvent {
+        match self.schedule.get(index) {
+            Some(&run) => {
+                self.phase = RunnerPhase::Warmup { index, until_ms: now_ms + self.results.plan.warmup_ms };
+                RunnerEvent::Apply(run)
+            }
+            None => {
+                self.phase = RunnerPhase::Finished;
+                RunnerEvent::Finished
+            }
+        }
+    }
+
+    pub fn on_frame(&mut self, frame_ms: f64, timestamp_ms: f64) {
+        if matches!(self.phase, RunnerPhase::Measuring { .. }) {
+            self.recorder.on_frame(frame_ms, timestamp_ms);
+        }
+    }
+
+    pub fn record_memory(&mut self, total_mb: f64) {
+        if matches!(self.phase, RunnerPhase::Measuring { .. }) {
+            self.recorder.record_memory(total_mb);
+        }
+    }
+
+    /// 定期的に呼び、ウォームアップ・計測の終了を判定する
+    pub fn tick(&mut self, now_ms: f64) -> Vec<RunnerEvent> {
+        match self.phase {
+            RunnerPhase::Warmup { index, until_ms } if now_ms >= until_ms => {
+                self.recorder = RunRecorder::default();
+                self.phase = RunnerPhase::Measuring { index, until_ms: now_ms + self.results.plan.measure_ms };
+                Vec::new()
+            }
+            RunnerPhase::Measuring { index, until_ms } if now_ms >= until_ms => {
+                let run = self.schedule[index];
+                let metrics = std::mem::take(&mut self.recorder).finish();
+                self.results.push(&run, &metrics);
+                vec![RunnerEvent::Completed(run, metrics), self.enter(index + 1, now_ms)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> RunPlan {
+        RunPlan {
+            configs: vec![
+                RunConfig { mode: RenderMode::DOM, object_count: 1000 },
+                RunConfig { mode: RenderMode::Canvas, object_count: 1000 },
+            ],
+            runs_per_config: 3,
+            base_seed: 42,
+            warmup_ms: 1000.0,
+            measure_ms: 2000.0,
+        }
+    }
+
+    #[test]
+    fn schedule_interleaves_configs_with_shared_seeds() {
+        let schedule = plan().schedule();
+        assert_eq!(schedule.len(), 6);
+        assert_eq!(
+            schedule.iter().map(|r| (r.config_index, r.seed)).collect::<Vec<_>>(),
+            vec![(0, 42), (1, 42), (0, 43), (1, 43), (0, 44), (1, 44)]
+        );
+    }
+
+    #[test]
+    fn runner_measures_each_scheduled_run() {
+        let mut runner = MultiRunner::new(plan());
+        let mut now = 0.0;
+        let mut applied = vec![];
+        if let RunnerEvent::Apply(run) = runner.start(now) {
+            applied.push(run);
+        }
+
+        // DOMは30fps、Canvasは60fps相当のフレームを流す
+        loop {
+            // 2構成を交互に実行するため、偶数番目がDOM
+            let frame_ms = match runner.phase() {
+                RunnerPhase::Warmup { index, .. } | RunnerPhase::Measuring { index, .. } if index % 2 == 0 => 1000.0 / 30.0,
+                _ => 1000.0 / 60.0,
+            };
+            now += frame_ms;
+            runner.on_frame(frame_ms, now);
+            let events = runner.tick(now);
+            for event in &events {
+                if let RunnerEvent::Apply(run) = event {
+                    applied.push(*run);
+                }
+            }
+            if events.contains(&RunnerEvent::Finished) {
+                break;
+            }
+        }
+
+        assert_eq!(applied.len(), 6);
+        assert_eq!(runner.results.completed_runs(), 6);
+        let summaries = runner.results.summaries();
+        assert!((summaries[0].fps.mean - 30.0).abs() < 1.0, "{:?}", summaries[0].fps);
+        assert!((summaries[1].fps.mean - 60.0).abs() < 1.0, "{:?}", summaries[1].fps);
+
+        let report = runner.results.to_markdown(&ScoringConfig::default());
+        assert!(report.contains("| Canvas × 1000 | 3 |"), "{report}");
+    }
+}
@@ -1,65 +1,156 @@
 use wasm_bindgen::prelude::*;
-use web_sys::Performance;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use web_sys::{Performance, PerformanceEntry, PerformanceNavigationTiming, PerformanceResourceTiming};
 use serde::{Serialize, Deserialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::utils::event_timing::InpCandidates;
+use crate::utils::storage::{load_item, save_item};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+const LOAD_HISTORY_STORAGE_KEY: &str = "load-metrics-history";
+/// `main` の呼び出し時刻に付けるUser Timingのマーク（DevToolsのタイミングに出る）
+#[cfg(target_arch = "wasm32")]
+const MAIN_START_MARK: &str = "wasm-main-start";
+/// WASMのコンパイル開始・完了とインスタンス化完了のマーク（下のJSで付ける）
+#[cfg(target_arch = "wasm32")]
+const COMPILE_START_MARK: &str = "wasm-compile-start";
+#[cfg(target_arch = "wasm32")]
+const COMPILE_END_MARK: &str = "wasm-compile-end";
+#[cfg(target_arch = "wasm32")]
+const INSTANTIATE_END_MARK: &str = "wasm-instantiate-end";
+/// localStorageに保持するロード履歴の件数
+const LOAD_HISTORY_LIMIT: usize = 30;
+
+/// 初回ロードの各フェーズ（いずれもナビゲーション開始からのms）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct LoadMetrics {
-    pub navigation_start: f64,
+    /// ナビゲーション開始時刻（UNIXエポックms、履歴のキーとして使う）
+    pub time_origin: f64,
+    /// Time To First Byte（responseStart）
+    pub ttfb: f64,
+    pub dom_interactive: f64,
     pub dom_content_loaded: f64,
-    pub load_complete: f64,
+    /// loadイベント完了（未完了の間は `None`）
+    pub load_complete: Option<f64>,
+    pub first_contentful_paint: Option<f64>,
+    pub largest_contentful_paint: Option<f64>,
+    pub cumulative_layout_shift: f64,
+    /// Interaction to Next Paint（操作が発生するまでは `None`）
+    pub interaction_to_next_paint: Option<f64>,
+    /// WASMファイルの取得開始
     pub wasm_init_start: f64,
+    pub wasm_fetch_time: Option<f64>,
+    /// 取得完了（コンパイル開始の方が遅ければその時刻）からコンパイル完了まで。
+    /// ストリーミングコンパイルで取得中に進んだ分は含まない
+    pub wasm_compile_time: Option<f64>,
+    /// コンパイル完了からインスタンス化完了まで
+    pub wasm_instantiate_time: Option<f64>,
+    /// Rustの `main` が呼ばれた時刻
     pub wasm_init_complete: f64,
-    pub first_contentful_paint: f64,
+    /// ナビゲーション開始からアプリ起動（`main`）まで
     pub total_load_time: f64,
 }
 
 impl LoadMetrics {
+    /// Navigation Timing Level 2 と Resource Timing から初期値を取得
+    ///
+    /// `main` の先頭で呼び出すことで、その時刻をWASM初期化完了とみなしてマークを付ける
+    #[cfg(target_arch = "wasm32")]
     pub fn new() -> Option<Self> {
         let window = web_sys::window()?;
         let performance = window.performance()?;
-        let navigation_timing = performance.timing();
-        
-        // Navigation Timing API values
-        let navigation_start = navigation_timing.navigation_start() as f64;
-        let dom_content_loaded = navigation_timing.dom_content_loaded_event_end() as f64;
-        let load_complete = navigation_timing.load_event_end() as f64;
-        
-        // First Contentful Paint from Paint Timing API
-        let first_contentful_paint = get_first_contentful_paint(&performance).unwrap_or(0.0);
-        
-        // Calculate total load time
-        let total_load_time = if load_complete > 0.0 {
-            load_complete - navigation_start
-        } else {
-            performance.now()
+        let now = performance.now();
+        let _ = performance.mark(MAIN_START_MARK);
+
+        let mut metrics = LoadMetrics {
+            time_origin: performance.time_origin(),
+            wasm_init_complete: now,
+            total_load_time: now,
+            ..Default::default()
         };
-        
-        Some(LoadMetrics {
-            navigation_start,
-            dom_content_loaded,
-            load_complete,
-            wasm_init_start: 0.0, // Will be set when WASM starts
-            wasm_init_complete: performance.now(),
-            first_contentful_paint,
-            total_load_time,
-        })
-    }
-    
+
+        if let Some(navigation) = navigation_timing(&performance) {
+            metrics.ttfb = navigation.response_start();
+            metrics.dom_interactive = navigation.dom_interactive();
+            metrics.dom_content_loaded = navigation.dom_content_loaded_event_end();
+            let load_end = navigation.load_event_end();
+            if load_end > 0.0 {
+                metrics.load_complete = Some(load_end);
+            }
+        }
+
+        metrics.first_contentful_paint = first_contentful_paint(&performance);
+
+        // WASMの取得はResource Timingから、コンパイルとインスタンス化はローダーで付けたマークから算出する
+        let mut fetch_end = None;
+        if let Some(resource) = wasm_resource_timing(&performance) {
+            metrics.wasm_init_start = resource.start_time();
+            if resource.response_end() > 0.0 {
+                fetch_end = Some(resource.response_end());
+                metrics.wasm_fetch_time = Some(resource.response_end() - resource.start_time());
+            }
+        }
+        let compile_start = mark_time(&performance, COMPILE_START_MARK);
+        let compile_end = mark_time(&performance, COMPILE_END_MARK);
+        if let (Some(start), Some(end)) = (compile_start, compile_end) {
+            let start = fetch_end.map_or(start, |fetch_end| start.max(fetch_end));
+            metrics.wasm_compile_time = Some((end - start).max(0.0));
+        }
+        if let (Some(compile_end), Some(instantiate_end)) = (compile_end, mark_time(&performance, INSTANTIATE_END_MARK)) {
+            metrics.wasm_instantiate_time = Some(instantiate_end - compile_end);
+        }
+
+        Some(metrics)
+    }
+
+    /// WASMの取得開始からmain呼び出しまで
+    pub fn wasm_init_time(&self) -> f64 {
+        self.wasm_init_complete - self.wasm_init_start
+    }
+
     pub fn get_formatted_report(&self) -> String {
+        let opt = |value: Option<f64>| match value {
+            Some(v) => format!("{:.0}ms", v),
+            None => "計測中...".to_string(),
+        };
+
         format!(
             r#"=== 初回ロード性能レポート ===
+TTFB: {:.0}ms {}
+First Contentful Paint: {} {}
+Largest Contentful Paint: {} {}
+Cumulative Layout Shift: {:.3} {}
+Interaction to Next Paint: {} {}
+DOM Interactive: {:.0}ms
 DOM Content Loaded: {:.0}ms
-First Contentful Paint: {:.0}ms
-Page Load Complete: {:.0}ms
+Page Load Complete: {}
+WASM Fetch: {}
+WASM Compile: {}
+WASM Instantiate: {}
 WASM Init Time: {:.0}ms
 Total Load Time: {:.0}ms
 
 4G回線での目標: < 3000ms
 現在のステータス: {}"#,
-            self.dom_content_loaded - self.navigation_start,
-            self.first_contentful_paint,
-            self.load_complete - self.navigation_start,
-            self.wasm_init_complete - self.wasm_init_start,
+            self.ttfb,
+            rating(Some(self.ttfb), 800.0, 1800.0),
+            opt(self.first_contentful_paint),
+            rating(self.first_contentful_paint, 1800.0, 3000.0),
+            opt(self.largest_contentful_paint),
+            rating(self.largest_contentful_paint, 2500.0, 4000.0),
+            self.cumulative_layout_shift,
+            rating(Some(self.cumulative_layout_shift), 0.1, 0.25),
+            opt(self.interaction_to_next_paint),
+            rating(self.interaction_to_next_paint, 200.0, 500.0),
+            self.dom_interactive,
+            self.dom_content_loaded,
+            opt(self.load_complete),
+            opt(self.wasm_fetch_time),
+            opt(self.wasm_compile_time),
+            opt(self.wasm_instantiate_time),
+            self.wasm_init_time(),
             self.total_load_time,
             if self.total_load_time < 3000.0 {
                 "✅ 目標達成"
@@ -68,33 +159,392 @@ Total Load Time: {:.0}ms
             }
         )
     }
+
+    /// PerformanceObserverからの通知を反映
+    fn apply(&mut self, entry: &VitalEntry, cls: &mut ClsAccumulator, inp: &mut InpCandidates) {
+        match *entry {
+            VitalEntry::FirstContentfulPaint(time) => {
+                self.first_contentful_paint = Some(time);
+            }
+            VitalEntry::LargestContentfulPaint(time) => {
+                self.largest_contentful_paint = Some(time);
+            }
+            VitalEntry::LayoutShift { value, start_time } => {
+                cls.record(value, start_time);
+                self.cumulative_layout_shift = cls.value();
+            }
+            VitalEntry::Interaction { interaction_id, duration } => {
+                // interactionIdが0のエントリは操作に紐付かないため除外
+                if interaction_id == 0 {
+                    return;
+                }
+                inp.record(interaction_id, duration);
+                self.interaction_to_next_paint = inp.value();
+            }
+            VitalEntry::LoadComplete(time) => {
+                self.load_complete = Some(time);
+            }
+        }
+    }
 }
 
-fn get_first_contentful_paint(_performance: &Performance) -> Option<f64> {
-    // JavaScript経由でPerformance Paint Timing APIにアクセス
-    let js_code = r#"
-        const entries = performance.getEntriesByType('paint');
-        const fcp = entries.find(entry => entry.name === 'first-contentful-paint');
-        return fcp ? fcp.startTime : 0;
-    "#;
-    
-    let result = js_sys::eval(js_code).ok()?;
-    result.as_f64()
+/// Core Web Vitalsの評価（good / needs improvement / poor）
+fn rating(value: Option<f64>, good: f64, poor: f64) -> &'static str {
+    match value {
+        Some(v) if v <= good => "✅",
+        Some(v) if v <= poor => "⚠️",
+        Some(_) => "❌",
+        None => "",
+    }
 }
 
-// グローバルなロード時間を保存
-#[wasm_bindgen(inline_js = r#"
-let globalLoadMetrics = null;
+#[cfg(target_arch = "wasm32")]
+fn navigation_timing(performance: &Performance) -> Option<PerformanceNavigationTiming> {
+    performance
+        .get_entries_by_type("navigation")
+        .get(0)
+        .dyn_into::<PerformanceNavigationTiming>()
+        .ok()
+}
 
-export function saveLoadMetrics(metrics) {
-    globalLoadMetrics = metrics;
+#[cfg(target_arch = "wasm32")]
+fn wasm_resource_timing(performance: &Performance) -> Option<PerformanceResourceTiming> {
+    performance
+        .get_entries_by_type("resource")
+        .iter()
+        .filter_map(|entry| entry.dyn_into::<PerformanceResourceTiming>().ok())
+        .find(|entry| entry.name().ends_with(".wasm"))
 }
 
-export function getLoadMetrics() {
-    return globalLoadMetrics;
+/// User Timingのマークの時刻（付いていなければ `None`）
+#[cfg(target_arch = "wasm32")]
+fn mark_time(performance: &Performance, name: &str) -> Option<f64> {
+    performance
+        .get_entries_by_name_with_entry_type(name, "mark")
+        .get(0)
+        .dyn_into::<PerformanceEntry>()
+        .ok()
+        .map(|entry| entry.start_time())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn first_contentful_paint(performance: &Performance) -> Option<f64> {
+    performance
+        .get_entries_by_name_with_entry_type("first-contentful-paint", "paint")
+        .get(0)
+        .dyn_into::<web_sys::PerformanceEntry>()
+        .ok()
+        .map(|entry| entry.start_time())
+}
+
+/// 過去のロード計測を古い順に取得
+pub fn load_history() -> Vec<LoadMetrics> {
+    load_item(LOAD_HISTORY_STORAGE_KEY)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// 今回のロード計測を履歴に保存（同じナビゲーションの記録は上書き）
+pub fn save_to_history(metrics: &LoadMetrics) {
+    let mut history = load_history();
+    merge_into_history(&mut history, metrics.clone(), LOAD_HISTORY_LIMIT);
+    if let Ok(json) = serde_json::to_string(&history) {
+        save_item(LOAD_HISTORY_STORAGE_KEY, &json);
+    }
+}
+
+/// 保存済みの履歴に今回の計測を反映したもの（表示用。保存はしない）
+pub fn history_with(stored: &[LoadMetrics], current: Option<&LoadMetrics>) -> Vec<LoadMetrics> {
+    let mut history = stored.to_vec();
+    if let Some(current) = current {
+        merge_into_history(&mut history, current.clone(), LOAD_HISTORY_LIMIT);
+    }
+    history
+}
+
+fn merge_into_history(history: &mut Vec<LoadMetrics>, metrics: LoadMetrics, limit: usize) {
+    match history.iter_mut().find(|m| m.time_origin == metrics.time_origin) {
+        Some(existing) => *existing = metrics,
+        None => history.push(metrics),
+    }
+    if history.len() > limit {
+        history.drain(0..history.len() - limit);
+    }
+}
+
+/// PerformanceObserverから届くエントリ
+#[derive(Debug, Clone, PartialEq)]
+enum VitalEntry {
+    FirstContentfulPaint(f64),
+    LargestContentfulPaint(f64),
+    LayoutShift { value: f64, start_time: f64 },
+    Interaction { interaction_id: u64, duration: f64 },
+    LoadComplete(f64),
+}
+
+impl VitalEntry {
+    fn from_js(kind: &str, value: f64, time: f64, id: f64) -> Option<Self> {
+        match kind {
+            "fcp" => Some(Self::FirstContentfulPaint(time)),
+            "lcp" => Some(Self::LargestContentfulPaint(time)),
+            "layout-shift" => Some(Self::LayoutShift { value, start_time: time }),
+            "event" => Some(Self::Interaction { interaction_id: id as u64, duration: value }),
+            "load" => Some(Self::LoadComplete(time)),
+            _ => None,
+        }
+    }
+}
+
+/// CLSのセッションウィンドウ集計（間隔1秒以内・最大5秒のウィンドウの最大値）
+#[derive(Debug, Clone, Default)]
+pub struct ClsAccumulator {
+    window_value: f64,
+    window_start: f64,
+    last_shift: f64,
+    max_value: f64,
+}
+
+impl ClsAccumulator {
+    const GAP_MS: f64 = 1000.0;
+    const MAX_WINDOW_MS: f64 = 5000.0;
+
+    pub fn record(&mut self, value: f64, start_time: f64) {
+        let continues_window = self.window_value > 0.0
+            && start_time - self.last_shift < Self::GAP_MS
+            && start_time - self.window_start < Self::MAX_WINDOW_MS;
+
+        if continues_window {
+            self.window_value += value;
+        } else {
+            self.window_value = value;
+            self.window_start = start_time;
+        }
+        self.last_shift = start_time;
+        self.max_value = self.max_value.max(self.window_value);
+    }
+
+    pub fn value(&self) -> f64 {
+        self.max_value
+    }
+}
+
+/// Web Vitalsの監視。ドロップ時にPerformanceObserverを切断する
+pub struct WebVitalsObserver {
+    disconnect: js_sys::Function,
+    _callback: Closure<dyn FnMut(String, f64, f64, f64)>,
+}
+
+impl WebVitalsObserver {
+    /// `initial` に届いたエントリを反映し、更新のたびに `on_update` を呼ぶ
+    pub fn start(initial: LoadMetrics, mut on_update: impl FnMut(LoadMetrics) + 'static) -> Self {
+        let state = Rc::new(RefCell::new((
+            initial,
+            ClsAccumulator::default(),
+            InpCandidates::default(),
+        )));
+
+        let callback = Closure::new(move |kind: String, value: f64, time: f64, id: f64| {
+            let Some(entry) = VitalEntry::from_js(&kind, value, time, id) else {
+                return;
+            };
+            let updated = {
+                let mut guard = state.borrow_mut();
+                let (metrics, cls, inp) = &mut *guard;
+                metrics.apply(&entry, cls, inp);
+                metrics.clone()
+            };
+            on_update(updated);
+        });
+
+        let disconnect = observeWebVitals(&callback);
+
+        Self {
+            disconnect,
+            _callback: callback,
+        }
+    }
+
+    pub fn disconnect(&self) {
+        let _ = self.disconnect.call0(&JsValue::NULL);
+    }
+}
+
+impl Drop for WebVitalsObserver {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+// WASMのコンパイル・インスタンス化の計測とWeb Vitalsの監視
+//
+// このスニペットはwasm-bindgenのグルーコードからWASMの初期化前に読み込まれるため、
+// ここで WebAssembly.instantiateStreaming を compileStreaming と instantiate に分けてマークを付ける
+// （ストリーミングコンパイルはそのまま使う）。計測するのはアプリ本体の初期化の1回だけ。
+#[wasm_bindgen(inline_js = r#"
+if (typeof WebAssembly !== 'undefined' && WebAssembly.instantiateStreaming && WebAssembly.compileStreaming) {
+    const instantiateStreaming = WebAssembly.instantiateStreaming;
+    WebAssembly.instantiateStreaming = async function(source, imports) {
+        WebAssembly.instantiateStreaming = instantiateStreaming;
+        performance.mark('wasm-compile-start');
+        const module = await WebAssembly.compileStreaming(source);
+        performance.mark('wasm-compile-end');
+        const instance = await WebAssembly.instantiate(module, imports);
+        performance.mark('wasm-instantiate-end');
+        return { module, instance };
+    };
+}
+
+export function observeWebVitals(callback) {
+    const observers = [];
+    const observe = (type, handler, options = {}) => {
+        try {
+            const observer = new PerformanceObserver((list) => {
+                list.getEntries().forEach(handler);
+            });
+            observer.observe({ type, buffered: true, ...options });
+            observers.push(observer);
+        } catch (e) {
+            // 未対応ブラウザでは該当指標を計測しない
+            console.log('PerformanceObserver not supported for', type);
+        }
+    };
+
+    observe('paint', (entry) => {
+        if (entry.name === 'first-contentful-paint') {
+            callback('fcp', 0, entry.startTime, 0);
+        }
+    });
+    observe('largest-contentful-paint', (entry) => {
+        callback('lcp', 0, entry.renderTime || entry.loadTime || entry.startTime, 0);
+    });
+    observe('layout-shift', (entry) => {
+        if (!entry.hadRecentInput) {
+            callback('layout-shift', entry.value, entry.startTime, 0);
+        }
+    });
+    observe('event', (entry) => {
+        if (entry.interactionId) {
+            callback('event', entry.duration, entry.startTime, entry.interactionId);
+        }
+    }, { durationThreshold: 16 });
+
+    const reportLoad = () => {
+        const nav = performance.getEntriesByType('navigation')[0];
+        callback('load', 0, nav && nav.loadEventEnd > 0 ? nav.loadEventEnd : performance.now(), 0);
+    };
+    if (document.readyState === 'complete') {
+        setTimeout(reportLoad, 0);
+    } else {
+        // loadEventEndが確定するのはloadハンドラの後
+        window.addEventListener('load', () => setTimeout(reportLoad, 0), { once: true });
+    }
+
+    return () => observers.forEach((observer) => observer.disconnect());
 }
 "#)]
 extern "C" {
-    pub fn saveLoadMetrics(metrics: JsValue);
-    pub fn getLoadMetrics() -> JsValue;
-}
\ No newline at end of file
+    fn observeWebVitals(callback: &Closure<dyn FnMut(String, f64, f64, f64)>) -> js_sys::Function;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_metrics_round_trip_through_serde() {
+        let metrics = LoadMetrics {
+            time_origin: 1_749_859_200_000.0,
+            ttfb: 120.0,
+            dom_content_loaded: 450.0,
+            load_complete: Some(800.0),
+            first_contentful_paint: Some(380.0),
+            largest_contentful_paint: None,
+            wasm_fetch_time: Some(90.0),
+            wasm_init_complete: 700.0,
+            total_load_time: 700.0,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&metrics).unwrap();
+        assert_eq!(serde_json::from_str::<LoadMetrics>(&json).unwrap(), metrics);
+    }
+
+    #[test]
+    fn history_replaces_same_navigation_and_keeps_limit() {
+        let entry = |time_origin: f64, total: f64| LoadMetrics {
+            time_origin,
+            total_load_time: total,
+            ..Default::default()
+        };
+        let mut history = Vec::new();
+
+        merge_into_history(&mut history, entry(1.0, 500.0), 2);
+        merge_into_history(&mut history, entry(1.0, 650.0), 2);
+        assert_eq!(history, vec![entry(1.0, 650.0)]);
+
+        merge_into_history(&mut history, entry(2.0, 400.0), 2);
+        merge_into_history(&mut history, entry(3.0, 300.0), 2);
+        assert_eq!(history, vec![entry(2.0, 400.0), entry(3.0, 300.0)]);
+    }
+
+    #[test]
+    fn cls_uses_largest_session_window() {
+        let mut cls = ClsAccumulator::default();
+        // 1つ目のウィンドウ: 0.05 + 0.05
+        cls.record(0.05, 100.0);
+        cls.record(0.05, 500.0);
+        // 1秒以上空いたので新しいウィンドウ: 0.2
+        cls.record(0.2, 3000.0);
+        // 間隔1秒以内なら同じウィンドウに加算（0.2 + 0.01 * 5）
+        for start in [3900.0, 4800.0, 5700.0, 6600.0, 7500.0] {
+            cls.record(0.01, start);
+        }
+        // 開始から5秒を超えたら新しいウィンドウ
+        cls.record(0.01, 8400.0);
+
+        assert!((cls.value() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn inp_ignores_entries_without_interaction() {
+        let mut metrics = LoadMetrics::default();
+        let mut cls = ClsAccumulator::default();
+        let mut inp = InpCandidates::default();
+
+        // 同じ操作の複数エントリは最大値に集約し、interactionIdが0のエントリは数えない
+        for (duration, id) in [(40.0, 1.0), (120.0, 1.0), (80.0, 2.0), (900.0, 0.0)] {
+            let entry = VitalEntry::from_js("event", duration, 0.0, id).unwrap();
+            metrics.apply(&entry, &mut cls, &mut inp);
+        }
+        assert_eq!(metrics.interaction_to_next_paint, Some(120.0));
+        assert_eq!(inp.count(), 2);
+    }
+
+    #[test]
+    fn load_metrics_apply_vital_entries() {
+        let mut metrics = LoadMetrics::default();
+        let mut cls = ClsAccumulator::default();
+        let mut inp = InpCandidates::default();
+
+        for entry in [
+            VitalEntry::from_js("fcp", 0.0, 350.0, 0.0),
+            VitalEntry::from_js("lcp", 0.0, 900.0, 0.0),
+            VitalEntry::from_js("layout-shift", 0.02, 1000.0, 0.0),
+            VitalEntry::from_js("event", 64.0, 2000.0, 7.0),
+            VitalEntry::from_js("load", 0.0, 1200.0, 0.0),
+            VitalEntry::from_js("unknown", 0.0, 0.0, 0.0),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            metrics.apply(&entry, &mut cls, &mut inp);
+        }
+
+        assert_eq!(metrics.first_contentful_paint, Some(350.0));
+        assert_eq!(metrics.largest_contentful_paint, Some(900.0));
+        assert!((metrics.cumulative_layout_shift - 0.02).abs() < 1e-9);
+        assert_eq!(metrics.interaction_to_next_paint, Some(64.0));
+        assert_eq!(metrics.load_complete, Some(1200.0));
+    }
+}
@@ -0,0 +1,122 @@
+//! カオス実行中の実測パフォーマンス（FPS・落ちたフレーム数・メモリ）
+
+use serde::{Deserialize, Serialize};
+use crate::utils::frame_stats::{FrameTimeHistogram, FrameTimeStats, FRAME_BUDGET_60FPS_MS};
+
+/// 現在のFPSを求める区間（ms）
+const CURRENT_WINDOW_MS: f64 = 1000.0;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    /// 直近のメモリ使用量（MB）
+    pub memory_usage: f64,
+    /// 計測開始からの平均FPS
+    pub fps_average: f64,
+    /// 60fpsの垂直同期に間に合わなかったフレーム数
+    pub dropped_frames: usize,
+    /// 直近1秒のFPS
+    pub fps_current: f64,
+    pub peak_memory_mb: f64,
+    pub frame_stats: FrameTimeStats,
+    pub duration_ms: f64,
+}
+
+/// フレーム時間とメモリを集計する
+#[derive(Debug, Clone, Default)]
+pub struct PerformanceMonitor {
+    histogram: FrameTimeHistogram,
+    dropped_frames: usize,
+    started_at: Option<f64>,
+    last_timestamp: f64,
+    /// 直近区間の (開始時刻, フレーム数)
+    window: Option<(f64, u32)>,
+    fps_current: f64,
+    memory_mb: f64,
+    peak_memory_mb: f64,
+}
+
+impl PerformanceMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_frame(&mut self, frame_ms: f64, timestamp: f64) {
+        self.histogram.record(frame_ms, timestamp);
+        // 予算の何フレーム分かかったか（1.5倍未満なら間に合ったとみなす）
+        let intervals = (frame_ms / FRAME_BUDGET_60FPS_MS).round() as usize;
+        self.dropped_frames += intervals.saturating_sub(1);
+
+        self.started_at.get_or_insert(timestamp - frame_ms);
+        self.last_timestamp = timestamp;
+
+        let (start, frames) = self.window.get_or_insert((timestamp - frame_ms, 0));
+        *frames += 1;
+        let elapsed = timestamp - *start;
+        if elapsed >= CURRENT_WINDOW_MS {
+            self.fps_current = *frames as f64 * 1000.0 / elapsed;
+            self.window = Some((timestamp, 0));
+        }
+    }
+
+    pub fn record_memory(&mut self, total_mb: f64) {
+        self.memory_mb = total_mb;
+        self.peak_memory_mb = self.peak_memory_mb.max(total_mb);
+    }
+
+    pub fn report(&self) -> PerformanceReport {
+        let frame_stats = self.histogram.stats();
+        PerformanceReport {
+            memory_usage: self.memory_mb,
+            fps_average: if frame_stats.avg_ms > 0.0 { 1000.0 / frame_stats.avg_ms } else { 0.0 },
+            dropped_frames: self.dropped_frames,
+            fps_current: self.fps_current,
+            peak_memory_mb: self.peak_memory_mb,
+            duration_ms: self.started_at.map(|start| self.last_timestamp - start).unwrap_or(0.0),
+            frame_stats,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_missed_vsyncs_as_dropped_frames() {
+        let mut monitor = PerformanceMonitor::new();
+        let mut now = 0.0;
+        for frame_ms in [16.7, 16.7, 50.0, 16.7, 33.4, 20.0] {
+            now += frame_ms;
+            monitor.on_frame(frame_ms, now);
+        }
+
+        let report = monitor.report();
+        // 50ms → 2フレーム落ち、33.4ms → 1フレーム落ち、20msは間に合った扱い
+        assert_eq!(report.dropped_frames, 3);
+        assert_eq!(report.frame_stats.frame_count, 6);
+        assert!((report.fps_average - 6000.0 / now).abs() < 1e-9);
+        assert!((report.duration_ms - now).abs() < 1e-9);
+    }
+
+    #[test]
+    fn current_fps_and_memory_peak() {
+        let mut monitor = PerformanceMonitor::new();
+        for i in 1..=120 {
+            monitor.on_frame(1000.0 / 60.0, i as f64 * 1000.0 / 60.0);
+        }
+        monitor.record_memory(120.0);
+        monitor.record_memory(80.0);
+
+        let report = monitor.report();
+        assert!((report.fps_current - 60.0).abs() < 0.5, "fps = {}", report.fps_current);
+        assert_eq!(report.memory_usage, 80.0);
+        assert_eq!(report.peak_memory_mb, 120.0);
+
+        monitor.reset();
+        assert_eq!(monitor.report(), PerformanceReport::default());
+    }
+}
@@ -0,0 +1,252 @@
+//! フレーム単位の簡易プロファイラ
+//!
+//! `profile_scope!("animate")` でスコープの開始・終了時刻をリングバッファに記録する。
+//! `profiling` フィーチャー無効時はマクロが空に展開され、実行時コストはない。
+
+/// スコープの終わりまでを1区間として記録する
+#[cfg(feature = "profiling")]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _profile_scope = $crate::utils::profiler::ScopeGuard::new($name);
+    };
+}
+
+#[cfg(not(feature = "profiling"))]
+macro_rules! profile_scope {
+    ($name:expr) => {};
+}
+
+pub(crate) use profile_scope;
+
+/// 記録された1区間
+#[cfg(any(test, feature = "profiling"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileSpan {
+    pub name: &'static str,
+    pub start_ms: f64,
+    pub end_ms: f64,
+    /// 入れ子の深さ（JSコードから届く区間は0）
+    pub depth: u8,
+}
+
+#[cfg(any(test, feature = "profiling"))]
+impl ProfileSpan {
+    pub fn duration_ms(&self) -> f64 {
+        self.end_ms - self.start_ms
+    }
+}
+
+/// 固定長のリングバッファ（満杯時は古いものから上書き）
+#[cfg(any(test, feature = "profiling"))]
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    items: Vec<T>,
+    capacity: usize,
+    next: usize,
+}
+
+#[cfg(any(test, feature = "profiling"))]
+impl<T: Copy> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+            capacity: capacity.max(1),
+            next: 0,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.items.len() < self.capacity {
+            self.items.push(item);
+        } else {
+            self.items[self.next] = item;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.next = 0;
+    }
+
+    /// 古い順
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let split = if self.items.len() < self.capacity { 0 } else { self.next };
+        self.items[split..].iter().chain(self.items[..split].iter())
+    }
+}
+
+/// Chrome trace event形式（chrome://tracing, Perfetto で読み込める）のJSON
+#[cfg(any(test, feature = "profiling"))]
+pub fn to_chrome_trace<'a>(spans: impl Iterator<Item = &'a ProfileSpan>, frame_starts: impl Iterator<Item = &'a f64>) -> String {
+    let mut events: Vec<serde_json::Value> = spans
+        .map(|span| {
+            serde_json::json!({
+                "name": span.name,
+                "cat": if span.depth == 0 { "js" } else { "wasm" },
+                "ph": "X",
+                "ts": span.start_ms * 1000.0,
+                "dur": span.duration_ms() * 1000.0,
+                "pid": 1,
+                "tid": 1,
+            })
+        })
+        .collect();
+
+    events.extend(frame_starts.map(|&start| {
+        serde_json::json!({
+            "name": "frame",
+            "cat": "frame",
+            "ph": "i",
+            "s": "g",
+            "ts": start * 1000.0,
+            "pid": 1,
+            "tid": 1,
+        })
+    }));
+
+    serde_json::json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+    })
+    .to_string()
+}
+
+#[cfg(feature = "profiling")]
+pub use runtime::*;
+
+#[cfg(feature = "profiling")]
+mod runtime {
+    use super::{to_chrome_trace, ProfileSpan, RingBuffer};
+    use crate::utils::clock::now_ms;
+    use crate::utils::fps_counter::FrameLoop;
+    use crate::utils::span_hook::SpanSink;
+    use std::cell::RefCell;
+
+    const SPAN_CAPACITY: usize = 8192;
+    const FRAME_CAPACITY: usize = 600;
+
+    struct Profiler {
+        spans: RingBuffer<ProfileSpan>,
+        frame_starts: RingBuffer<f64>,
+        depth: u8,
+    }
+
+    thread_local! {
+        static PROFILER: RefCell<Profiler> = RefCell::new(Profiler {
+            spans: RingBuffer::new(SPAN_CAPACITY),
+            frame_starts: RingBuffer::new(FRAME_CAPACITY),
+            depth: 0,
+        });
+    }
+
+    /// `profile_scope!` が生成するガード
+    pub struct ScopeGuard {
+        name: &'static str,
+        start_ms: f64,
+        depth: u8,
+    }
+
+    impl ScopeGuard {
+        pub fn new(name: &'static str) -> Self {
+            let depth = PROFILER.with(|p| {
+                let mut p = p.borrow_mut();
+                p.depth = p.depth.saturating_add(1);
+                p.depth
+            });
+            Self { name, start_ms: now_ms(), depth }
+        }
+    }
+
+    impl Drop for ScopeGuard {
+        fn drop(&mut self) {
+            let end_ms = now_ms();
+            PROFILER.with(|p| {
+                let mut p = p.borrow_mut();
+                p.depth = p.depth.saturating_sub(1);
+                p.spans.push(ProfileSpan {
+                    name: self.name,
+                    start_ms: self.start_ms,
+                    end_ms,
+                    depth: self.depth,
+                });
+            });
+        }
+    }
+
+    /// 直近の区間とフレーム開始時刻（古い順）
+    pub fn snapshot() -> (Vec<ProfileSpan>, Vec<f64>) {
+        PROFILER.with(|p| {
+            let p = p.borrow();
+            (p.spans.iter().copied().collect(), p.frame_starts.iter().copied().collect())
+        })
+    }
+
+    pub fn clear() {
+        PROFILER.with(|p| {
+            let mut p = p.borrow_mut();
+            p.spans.clear();
+            p.frame_starts.clear();
+        });
+    }
+
+    pub fn export_chrome_trace() -> String {
+        PROFILER.with(|p| {
+            let p = p.borrow();
+            to_chrome_trace(p.spans.iter(), p.frame_starts.iter())
+        })
+    }
+
+    /// フレーム境界とJSコードの区間の記録。ドロップ時に停止する
+    pub struct ProfilerSession {
+        _frames: FrameLoop,
+        _sink: SpanSink,
+    }
+
+    impl ProfilerSession {
+        pub fn start() -> Self {
+            let frames = FrameLoop::start(|_frame_ms, timestamp| {
+                PROFILER.with(|p| p.borrow_mut().frame_starts.push(timestamp));
+            });
+
+            let sink = SpanSink::add(|name, start_ms, end_ms| {
+                PROFILER.with(|p| {
+                    p.borrow_mut().spans.push(ProfileSpan { name, start_ms, end_ms, depth: 0 });
+                });
+            });
+
+            Self { _frames: frames, _sink: sink }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_keeps_latest_items_in_order() {
+        let mut ring = RingBuffer::new(3);
+        for i in 0..5 {
+            ring.push(i);
+        }
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        ring.clear();
+        assert_eq!(ring.iter().count(), 0);
+    }
+
+    #[test]
+    fn exports_chrome_trace_events_in_microseconds() {
+        let spans = [ProfileSpan { name: "animate", start_ms: 1.5, end_ms: 4.0, depth: 1 }];
+        let trace: serde_json::Value = serde_json::from_str(&to_chrome_trace(spans.iter(), [1.0].iter())).unwrap();
+
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["name"], "animate");
+        assert_eq!(events[0]["ph"], "X");
+        assert_eq!(events[0]["ts"], 1500.0);
+        assert_eq!(events[0]["dur"], 2500.0);
+        assert_eq!(events[1]["ph"], "i");
+    }
+}
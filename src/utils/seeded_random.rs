@@ -0,0 +1,20 @@
+/// マーカー配置用の乱数関数 `rand` を定義するJSコード
+///
+/// シード指定時は `sim::rng::SimRng` と同じMulberry32を使い、同じシードならネイティブ版と同じ系列になる。
+/// 未指定時は `Math.random` をそのまま使う
+pub fn random_source_js(seed: Option<u64>) -> String {
+    match seed {
+        Some(seed) => {
+            // SimRng::new と同じく上位ビットを畳み込んでから32bitにする
+            let state = (seed ^ (seed >> 32)) as u32;
+            format!(
+                "const rand = (() => {{ let a = {state}; return () => {{ \
+                 a = (a + 0x6D2B79F5) | 0; let t = a; \
+                 t = Math.imul(t ^ (t >>> 15), t | 1); \
+                 t ^= t + Math.imul(t ^ (t >>> 7), t | 61); \
+                 return ((t ^ (t >>> 14)) >>> 0) / 4294967296; }}; }})();"
+            )
+        }
+        None => "const rand = Math.random;".to_string(),
+    }
+}
@@ -0,0 +1,273 @@
+//! 長時間のカオス実行（ソークテスト）でのリーク検出
+//!
+//! メモリと生存中のハンドル・DOMノード数を一定間隔でサンプリングし、ウォームアップ後の推移が
+//! 単調に増え続けていて、かつ増加量がしきい値を超えた指標をリークとみなす。
+
+
+use serde::{Deserialize, Serialize};
+use crate::utils::live_handles::LiveHandles;
+
+const MS_PER_HOUR: f64 = 3_600_000.0;
+/// 単調増加の判定で推移を分割する区間数
+const SEGMENTS: usize = 6;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoakConfig {
+    pub duration_ms: f64,
+    pub sample_interval_ms: f64,
+    /// 判定から除く開始直後の期間（実行時間の1/10が上限）
+    pub warmup_ms: f64,
+    /// 許容するメモリ増加率（MB/時）
+    pub max_memory_mb_per_hour: f64,
+    /// 許容するハンドル数の増加（実行全体）
+    pub max_handle_growth: f64,
+    /// 許容するDOMノード数の増加（実行全体）
+    pub max_dom_growth: f64,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            duration_ms: MS_PER_HOUR,
+            sample_interval_ms: 5000.0,
+            warmup_ms: 60_000.0,
+            max_memory_mb_per_hour: 10.0,
+            max_handle_growth: 5.0,
+            max_dom_growth: 200.0,
+        }
+    }
+}
+
+impl SoakConfig {
+    pub fn with_duration_ms(duration_ms: f64) -> Self {
+        Self { duration_ms, ..Self::default() }
+    }
+
+    fn effective_warmup_ms(&self) -> f64 {
+        self.warmup_ms.min(self.duration_ms / 10.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SoakSample {
+    /// 開始からの時刻（ms）
+    pub at_ms: f64,
+    pub memory_mb: f64,
+    pub dom_nodes: usize,
+    pub handles: LiveHandles,
+}
+
+impl SoakSample {
+    /// (指標名, 値, 増加のしきい値の種類)
+    fn metrics(&self) -> [(&'static str, f64, Limit); 5] {
+        [
+            ("memory_mb", self.memory_mb, Limit::MemoryRate),
+            ("dom_nodes", self.dom_nodes as f64, Limit::DomGrowth),
+            ("intervals", self.handles.intervals as f64, Limit::HandleGrowth),
+            ("frame_loops", self.handles.frame_loops as f64, Limit::HandleGrowth),
+            ("pending_closures", self.handles.pending_closures as f64, Limit::HandleGrowth),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Limit {
+    MemoryRate,
+    HandleGrowth,
+    DomGrowth,
+}
+
+/// 1つの指標の推移
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricTrend {
+    pub metric: String,
+    pub first: f64,
+    pub last: f64,
+    /// 最小二乗法による増加率（1時間あたり）
+    pub slope_per_hour: f64,
+    /// 回帰直線上の判定期間全体での増加量
+    pub growth: f64,
+    /// 区間ごとの最小値が一度も下がらずに増えている
+    pub monotonic: bool,
+    pub leaking: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoakVerdict {
+    pub passed: bool,
+    pub duration_ms: f64,
+    pub samples: usize,
+    /// メモリの増加率の推定（MB/時）
+    pub leak_rate_mb_per_hour: f64,
+    pub trends: Vec<MetricTrend>,
+}
+
+impl SoakVerdict {
+    /// コンソールや画面に出す1行の判定
+    pub fn summary(&self) -> String {
+        let leaking: Vec<String> = self
+            .trends
+            .iter()
+            .filter(|t| t.leaking)
+            .map(|t| format!("{} {:+.1}", t.metric, t.growth))
+            .collect();
+        format!(
+            "{}: {:.1}分 / {}サンプル / メモリ {:+.2}MB/時{}",
+            if self.passed { "PASS" } else { "FAIL" },
+            self.duration_ms / 60_000.0,
+            self.samples,
+            self.leak_rate_mb_per_hour,
+            if leaking.is_empty() { String::new() } else { format!(" / リークの疑い: {}", leaking.join(", ")) }
+        )
+    }
+}
+
+/// サンプルを集めて判定する
+#[derive(Debug, Clone)]
+pub struct SoakMonitor {
+    config: SoakConfig,
+    samples: Vec<SoakSample>,
+}
+
+impl SoakMonitor {
+    pub fn new(config: SoakConfig) -> Self {
+        Self { config, samples: Vec::new() }
+    }
+
+    pub fn record(&mut self, sample: SoakSample) {
+        self.samples.push(sample);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.samples.last().is_some_and(|s| s.at_ms >= self.config.duration_ms)
+    }
+
+    pub fn verdict(&self) -> SoakVerdict {
+        let warmup = self.config.effective_warmup_ms();
+        let judged: Vec<&SoakSample> = self.samples.iter().filter(|s| s.at_ms >= warmup).collect();
+
+        let trends: Vec<MetricTrend> = SoakSample::default()
+            .metrics()
+            .iter()
+            .enumerate()
+            .map(|(index, (metric, _, limit))| {
+                let points: Vec<(f64, f64)> = judged.iter().map(|s| (s.at_ms, s.metrics()[index].1)).collect();
+                self.trend(metric, &points, *limit)
+            })
+            .collect();
+
+        SoakVerdict {
+            passed: !trends.iter().any(|t| t.leaking),
+            duration_ms: self.samples.last().map(|s| s.at_ms).unwrap_or(0.0),
+            samples: self.samples.len(),
+            leak_rate_mb_per_hour: trends[0].slope_per_hour,
+            trends,
+        }
+    }
+
+    fn trend(&self, metric: &str, points: &[(f64, f64)], limit: Limit) -> MetricTrend {
+        let slope_per_hour = slope(points) * MS_PER_HOUR;
+        let span_ms = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => last.0 - first.0,
+            _ => 0.0,
+        };
+        let growth = slope_per_hour * span_ms / MS_PER_HOUR;
+        let monotonic = is_monotonic_growth(points);
+        let exceeded = match limit {
+            Limit::MemoryRate => slope_per_hour > self.config.max_memory_mb_per_hour,
+            Limit::HandleGrowth => growth > self.config.max_handle_growth,
+            Limit::DomGrowth => growth > self.config.max_dom_growth,
+        };
+        MetricTrend {
+            metric: metric.to_string(),
+            first: points.first().map(|p| p.1).unwrap_or(0.0),
+            last: points.last().map(|p| p.1).unwrap_or(0.0),
+            slope_per_hour,
+            growth,
+            monotonic,
+            leaking: monotonic && exceeded,
+        }
+    }
+}
+
+/// 最小二乗法の傾き（値/ms）
+fn slope(points: &[(f64, f64)]) -> f64 {
+    if points.len() < 2 {
+        return 0.0;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for &(x, y) in points {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance += (x - mean_x) * (x - mean_x);
+    }
+    if variance > 0.0 { covariance / variance } else { 0.0 }
+}
+
+/// 推移を区間に分け、各区間の最小値（GC直後の保持量に相当）が下がらずに増えているか
+fn is_monotonic_growth(points: &[(f64, f64)]) -> bool {
+    if points.len() < SEGMENTS {
+        return false;
+    }
+    let chunk = points.len().div_ceil(SEGMENTS);
+    let minima: Vec<f64> = points
+        .chunks(chunk)
+        .map(|c| c.iter().map(|p| p.1).fold(f64::INFINITY, f64::min))
+        .collect();
+    minima.windows(2).all(|w| w[1] >= w[0]) && minima.last() > minima.first()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(config: SoakConfig, sample: impl Fn(f64) -> SoakSample) -> SoakMonitor {
+        let step = config.sample_interval_ms;
+        let mut monitor = SoakMonitor::new(config);
+        let mut at_ms = 0.0;
+        while !monitor.is_finished() {
+            monitor.record(SoakSample { at_ms, ..sample(at_ms) });
+            at_ms += step;
+        }
+        monitor
+    }
+
+    #[test]
+    fn steady_growth_of_memory_and_closures_fails() {
+        // 1時間でメモリが30MB、未実行のクロージャが20個増える
+        let monitor = run(SoakConfig::default(), |at_ms| {
+            let hours = at_ms / MS_PER_HOUR;
+            SoakSample {
+                memory_mb: 100.0 + 30.0 * hours + if ((at_ms / 5000.0) as u64).is_multiple_of(3) { 4.0 } else { 0.0 },
+                dom_nodes: 800,
+                handles: LiveHandles { intervals: 3, frame_loops: 2, pending_closures: (20.0 * hours) as isize },
+                ..Default::default()
+            }
+        });
+
+        let verdict = monitor.verdict();
+        assert!(!verdict.passed);
+        assert!((verdict.leak_rate_mb_per_hour - 30.0).abs() < 1.0, "rate = {}", verdict.leak_rate_mb_per_hour);
+        let leaking: Vec<_> = verdict.trends.iter().filter(|t| t.leaking).map(|t| t.metric.as_str()).collect();
+        assert_eq!(leaking, vec!["memory_mb", "pending_closures"]);
+        assert!(verdict.summary().starts_with("FAIL"));
+    }
+
+    #[test]
+    fn sawtooth_without_retained_growth_passes() {
+        // GCで毎分元に戻るメモリと、増減するDOMノード
+        let monitor = run(SoakConfig::with_duration_ms(10.0 * 60_000.0), |at_ms| SoakSample {
+            memory_mb: 100.0 + (at_ms % 60_000.0) / 1000.0,
+            dom_nodes: 800 + ((at_ms / 5000.0) as usize % 4) * 50,
+            handles: LiveHandles { intervals: 3, frame_loops: 2, pending_closures: 0 },
+            ..Default::default()
+        });
+
+        let verdict = monitor.verdict();
+        assert!(verdict.passed, "{}", verdict.summary());
+        assert!(verdict.trends.iter().all(|t| !t.monotonic));
+        assert_eq!(verdict.samples, 121);
+    }
+}
@@ -0,0 +1,87 @@
+use wasm_bindgen::prelude::*;
+use std::cell::RefCell;
+
+/// 処理区間の通知先 `(名前, 開始, 終了)`
+type SinkFn = Box<dyn Fn(&'static str, f64, f64)>;
+
+/// `window.__benchPhase` に設定するクロージャ
+type JsHook = Closure<dyn Fn(u32, f64, f64)>;
+
+/// JSコードから届く区間番号に対応する名前（毎フレーム呼ばれるため文字列は受け渡さない）
+const JS_SPAN_NAMES: [&str; 3] = ["animate", "marker-rebuild", "tile-load"];
+
+/// マップのJSコードが呼ぶグローバル関数名
+const GLOBAL_HOOK: &str = "__benchPhase";
+
+thread_local! {
+    static SINKS: RefCell<Vec<(u32, SinkFn)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: RefCell<u32> = const { RefCell::new(0) };
+    static JS_HOOK: RefCell<Option<JsHook>> = const { RefCell::new(None) };
+}
+
+/// 登録中の全通知先に区間を渡す（通知先がなければ何もしない）
+///
+/// 通知先の中で `SpanSink` を追加・解除してはならない
+pub fn emit(name: &'static str, start: f64, end: f64) {
+    SINKS.with(|s| {
+        for (_, sink) in s.borrow().iter() {
+            sink(name, start, end);
+        }
+    });
+}
+
+pub fn is_active() -> bool {
+    SINKS.with(|s| !s.borrow().is_empty())
+}
+
+/// 区間の通知先。ドロップ時に登録を解除する
+///
+/// 最初の登録時に `window.__benchPhase` を設定し、最後の解除時に削除する。
+/// JS側は `if (window.__benchPhase)` で確認してから呼ぶため、未登録時のコストはない
+pub struct SpanSink {
+    id: u32,
+}
+
+impl SpanSink {
+    pub fn add(sink: impl Fn(&'static str, f64, f64) + 'static) -> Self {
+        let id = NEXT_ID.with(|n| {
+            let mut n = n.borrow_mut();
+            *n += 1;
+            *n
+        });
+        SINKS.with(|s| s.borrow_mut().push((id, Box::new(sink))));
+
+        JS_HOOK.with(|hook| {
+            let mut hook = hook.borrow_mut();
+            if hook.is_none() {
+                let closure = JsHook::new(|phase: u32, start: f64, end: f64| {
+                    let name = JS_SPAN_NAMES.get(phase as usize).copied().unwrap_or("js");
+                    emit(name, start, end);
+                });
+                if let Some(window) = web_sys::window() {
+                    let _ = js_sys::Reflect::set(&window, &GLOBAL_HOOK.into(), closure.as_ref());
+                }
+                *hook = Some(closure);
+            }
+        });
+
+        Self { id }
+    }
+}
+
+impl Drop for SpanSink {
+    fn drop(&mut self) {
+        let empty = SINKS.with(|s| {
+            let mut sinks = s.borrow_mut();
+            sinks.retain(|(id, _)| *id != self.id);
+            sinks.is_empty()
+        });
+
+        if empty {
+            if let Some(window) = web_sys::window() {
+                let _ = js_sys::Reflect::delete_property(&window, &GLOBAL_HOOK.into());
+            }
+            JS_HOOK.with(|hook| hook.borrow_mut().take());
+        }
+    }
+}
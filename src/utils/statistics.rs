@@ -0,0 +1,248 @@
+//! 複数回計測の要約統計と有意差検定
+//!
+//! 信頼区間はt分布、2群の比較は等分散を仮定しないWelchのt検定を使う。
+
+use serde::{Deserialize, Serialize};
+
+/// 標本の要約
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub n: usize,
+    pub mean: f64,
+    /// 不偏標準偏差（n-1で割る）
+    pub stddev: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+    /// 信頼水準（0.95など）
+    pub confidence: f64,
+}
+
+impl Summary {
+    pub fn from_samples(samples: &[f64], confidence: f64) -> Self {
+        let n = samples.len();
+        if n == 0 {
+            return Self { confidence, ..Default::default() };
+        }
+
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        if n == 1 {
+            return Self { n, mean, stddev: 0.0, ci_low: mean, ci_high: mean, confidence };
+        }
+
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        let stddev = variance.sqrt();
+        let half_width = t_critical((n - 1) as f64, confidence) * stddev / (n as f64).sqrt();
+
+        Self {
+            n,
+            mean,
+            stddev,
+            ci_low: mean - half_width,
+            ci_high: mean + half_width,
+            confidence,
+        }
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.stddev * self.stddev
+    }
+
+    /// 変動係数（%）
+    pub fn cv_percent(&self) -> f64 {
+        if self.mean == 0.0 {
+            0.0
+        } else {
+            self.stddev / self.mean.abs() * 100.0
+        }
+    }
+}
+
+/// Welchのt検定の結果（a - b）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WelchTest {
+    pub mean_diff: f64,
+    pub t: f64,
+    /// Welch–Satterthwaiteの自由度
+    pub df: f64,
+    /// 両側p値
+    pub p_value: f64,
+}
+
+impl WelchTest {
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+/// 2群の平均の差の検定。どちらかの標本が2件未満なら `None`
+pub fn welch_t_test(a: &Summary, b: &Summary) -> Option<WelchTest> {
+    if a.n < 2 || b.n < 2 {
+        return None;
+    }
+
+    let va = a.variance() / a.n as f64;
+    let vb = b.variance() / b.n as f64;
+    let se2 = va + vb;
+    let mean_diff = a.mean - b.mean;
+
+    // 両群とも分散0（完全に同じ値）の場合
+    if se2 == 0.0 {
+        let p_value = if mean_diff == 0.0 { 1.0 } else { 0.0 };
+        let t = if mean_diff == 0.0 { 0.0 } else { mean_diff.signum() * f64::INFINITY };
+        return Some(WelchTest { mean_diff, t, df: (a.n + b.n - 2) as f64, p_value });
+    }
+
+    let t = mean_diff / se2.sqrt();
+    let df = se2 * se2 / (va * va / (a.n - 1) as f64 + vb * vb / (b.n - 1) as f64);
+    Some(WelchTest { mean_diff, t, df, p_value: two_tailed_p(t, df) })
+}
+
+/// t分布の両側p値
+pub fn two_tailed_p(t: f64, df: f64) -> f64 {
+    if !t.is_finite() {
+        return 0.0;
+    }
+    let x = df / (df + t * t);
+    regularized_incomplete_beta(x, df / 2.0, 0.5).clamp(0.0, 1.0)
+}
+
+/// 両側信頼区間に使うtの臨界値（二分法）
+pub fn t_critical(df: f64, confidence: f64) -> f64 {
+    let alpha = 1.0 - confidence;
+    let (mut low, mut high) = (0.0, 1000.0);
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if two_tailed_p(mid, df) > alpha {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// ln Γ(x)（Lanczos近似）
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    let mut y = x;
+    for c in COEFFS {
+        y += 1.0;
+        series += c / y;
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// 正則化不完全ベータ関数 I_x(a, b)
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // 連分数の収束が速い側で評価する
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// 不完全ベータ関数の連分数（Lentz法）
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 200;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        // 偶数項
+        let aa = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        // 奇数項
+        let aa = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confidence_interval_uses_t_distribution() {
+        assert!((t_critical(10.0, 0.95) - 2.228).abs() < 1e-3);
+        assert!((t_critical(1000.0, 0.95) - 1.962).abs() < 1e-3);
+
+        let summary = Summary::from_samples(&[58.0, 60.0, 59.0, 61.0, 57.0], 0.95);
+        assert_eq!(summary.n, 5);
+        assert_eq!(summary.mean, 59.0);
+        assert!((summary.stddev - 2.5f64.sqrt()).abs() < 1e-12);
+        // t(4) = 2.776
+        assert!((summary.ci_high - summary.mean - 2.776 * summary.stddev / 5f64.sqrt()).abs() < 1e-2);
+    }
+
+    #[test]
+    fn welch_test_matches_reference_values() {
+        // 英語版WikipediaのWelchのt検定の例1
+        let a = [27.5, 21.0, 19.0, 23.6, 17.0, 17.9, 16.9, 20.1, 21.9, 22.6, 23.1, 19.6, 19.0, 21.7, 21.4];
+        let b = [27.1, 22.0, 20.8, 23.4, 23.4, 23.5, 25.8, 22.0, 24.8, 20.2, 21.9, 22.1, 22.9, 20.5, 24.4];
+        let test = welch_t_test(&Summary::from_samples(&a, 0.95), &Summary::from_samples(&b, 0.95)).unwrap();
+
+        assert!((test.t - -2.46).abs() < 0.01, "t = {}", test.t);
+        assert!((test.df - 24.99).abs() < 0.05, "df = {}", test.df);
+        assert!((test.p_value - 0.021).abs() < 0.001, "p = {}", test.p_value);
+        assert!(test.is_significant(0.05));
+
+        let same = Summary::from_samples(&[60.0, 60.0, 60.0], 0.95);
+        assert_eq!(welch_t_test(&same, &same).unwrap().p_value, 1.0);
+    }
+}
@@ -0,0 +1,53 @@
+use wasm_bindgen::prelude::*;
+
+// ファイルダウンロードとlocalStorageアクセスのヘルパー
+#[wasm_bindgen(inline_js = r#"
+export function downloadText(filename, mimeType, text) {
+    const blob = new Blob([text], { type: mimeType });
+    const url = URL.createObjectURL(blob);
+    const anchor = document.createElement('a');
+    anchor.href = url;
+    anchor.download = filename;
+    document.body.appendChild(anchor);
+    anchor.click();
+    anchor.remove();
+    setTimeout(() => URL.revokeObjectURL(url), 0);
+}
+
+export function loadItem(key) {
+    try {
+        return window.localStorage.getItem(key);
+    } catch (e) {
+        // プライベートモードなどでlocalStorageが使えない場合
+        return null;
+    }
+}
+
+export function saveItem(key, value) {
+    try {
+        window.localStorage.setItem(key, value);
+        return true;
+    } catch (e) {
+        console.log('localStorage write failed:', e);
+        return false;
+    }
+}
+
+export function removeItem(key) {
+    try {
+        window.localStorage.removeItem(key);
+    } catch (e) {
+        // 無視
+    }
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = downloadText)]
+    pub fn download_text(filename: &str, mime_type: &str, text: &str);
+    #[wasm_bindgen(js_name = loadItem)]
+    pub fn load_item(key: &str) -> Option<String>;
+    #[wasm_bindgen(js_name = saveItem)]
+    pub fn save_item(key: &str, value: &str) -> bool;
+    #[wasm_bindgen(js_name = removeItem)]
+    pub fn remove_item(key: &str);
+}
@@ -0,0 +1,455 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::types::PerformanceMetrics;
+use crate::utils::input_latency::LatencyStats;
+use crate::utils::histogram::LogHistogram;
+use crate::utils::performance_metrics::LoadMetrics;
+use crate::utils::storage::{load_item, remove_item, save_item};
+
+const CONFIG_STORAGE_KEY: &str = "telemetry-config";
+const QUEUE_STORAGE_KEY: &str = "telemetry-queue";
+/// オフライン時に保持する最大件数（超えた分は古い順に破棄）
+const MAX_QUEUED_RECORDS: usize = 500;
+/// 再送間隔の初期値と上限（ms）
+const RETRY_BASE_MS: f64 = 2_000.0;
+const RETRY_MAX_MS: f64 = 5.0 * 60.0 * 1000.0;
+
+/// テレメトリ送信の設定（オプトイン、localStorageに保存）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub batch_size: usize,
+    pub flush_interval_ms: u32,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:8787/collect".to_string(),
+            batch_size: 20,
+            flush_interval_ms: 10_000,
+        }
+    }
+}
+
+impl TelemetryConfig {
+    pub fn load() -> Self {
+        load_item(CONFIG_STORAGE_KEY)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string(self) {
+            save_item(CONFIG_STORAGE_KEY, &json);
+        }
+    }
+}
+
+/// 送信するデータの種類
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum TelemetryPayload {
+    Performance(PerformanceMetrics),
+    Load(LoadMetrics),
+    Latency {
+        component: String,
+        stats: LatencyStats,
+        /// 受信側でセッションをまたいで足し合わせるための分布
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        histogram: Option<LogHistogram>,
+    },
+}
+
+/// 1件分の送信レコード
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryRecord {
+    pub session_id: String,
+    /// 記録時刻（UNIXエポックms）
+    pub recorded_at: f64,
+    pub route: String,
+    pub user_agent: String,
+    pub build_profile: String,
+    #[serde(flatten)]
+    pub payload: TelemetryPayload,
+}
+
+/// 送信待ちレコードのキューと再送制御
+#[derive(Debug, Clone, Default)]
+pub struct TelemetryQueue {
+    records: Vec<TelemetryRecord>,
+    failed_attempts: u32,
+    next_attempt_at: f64,
+}
+
+impl TelemetryQueue {
+    pub fn with_records(records: Vec<TelemetryRecord>) -> Self {
+        let mut queue = Self::default();
+        for record in records {
+            queue.push(record);
+        }
+        queue
+    }
+
+    pub fn push(&mut self, record: TelemetryRecord) {
+        self.records.push(record);
+        self.drop_oldest();
+    }
+
+    /// 上限を超えた分を古い順に捨てる（追加時も再送待ちへの戻し時も同じ方針）
+    fn drop_oldest(&mut self) {
+        if self.records.len() > MAX_QUEUED_RECORDS {
+            self.records.drain(0..self.records.len() - MAX_QUEUED_RECORDS);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn records(&self) -> &[TelemetryRecord] {
+        &self.records
+    }
+
+    /// 再送待ち中でなければ送信してよい
+    pub fn can_send(&self, now: f64) -> bool {
+        !self.records.is_empty() && now >= self.next_attempt_at
+    }
+
+    /// 先頭から最大 `size` 件を取り出す
+    pub fn take_batch(&mut self, size: usize) -> Vec<TelemetryRecord> {
+        let size = size.max(1).min(self.records.len());
+        self.records.drain(0..size).collect()
+    }
+
+    pub fn on_success(&mut self) {
+        self.failed_attempts = 0;
+        self.next_attempt_at = 0.0;
+    }
+
+    /// 送信失敗時はバッチを先頭に戻し、指数バックオフで次回時刻を決める
+    pub fn on_failure(&mut self, batch: Vec<TelemetryRecord>, now: f64) {
+        let mut restored = batch;
+        restored.append(&mut self.records);
+        self.records = restored;
+        self.drop_oldest();
+
+        self.failed_attempts += 1;
+        self.next_attempt_at = now + Self::backoff_ms(self.failed_attempts);
+    }
+
+    pub fn backoff_ms(failed_attempts: u32) -> f64 {
+        let exponent = failed_attempts.saturating_sub(1).min(16) as i32;
+        (RETRY_BASE_MS * 2f64.powi(exponent)).min(RETRY_MAX_MS)
+    }
+}
+
+#[wasm_bindgen(inline_js = r#"
+export function sendTelemetry(endpoint, body, onDone) {
+    if (!navigator.onLine) {
+        onDone(false);
+        return;
+    }
+    fetch(endpoint, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body,
+        keepalive: true,
+        mode: 'cors',
+    })
+        .then((response) => onDone(response.ok))
+        .catch(() => onDone(false));
+}
+
+export function beaconTelemetry(endpoint, body) {
+    if (!navigator.sendBeacon) {
+        return false;
+    }
+    // text/plainはCORSプリフライト不要なためページ離脱時でも届きやすい
+    return navigator.sendBeacon(endpoint, new Blob([body], { type: 'text/plain' }));
+}
+
+export function onTelemetryLifecycle(onHidden, onOnline) {
+    const visibility = () => {
+        if (document.visibilityState === 'hidden') {
+            onHidden();
+        }
+    };
+    document.addEventListener('visibilitychange', visibility);
+    window.addEventListener('online', onOnline);
+    return () => {
+        document.removeEventListener('visibilitychange', visibility);
+        window.removeEventListener('online', onOnline);
+    };
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = sendTelemetry)]
+    fn send_telemetry(endpoint: &str, body: &str, on_done: &js_sys::Function);
+    #[wasm_bindgen(js_name = beaconTelemetry)]
+    fn beacon_telemetry(endpoint: &str, body: &str) -> bool;
+    #[wasm_bindgen(js_name = onTelemetryLifecycle)]
+    fn on_telemetry_lifecycle(on_hidden: &Closure<dyn FnMut()>, on_online: &Closure<dyn FnMut()>) -> js_sys::Function;
+}
+
+struct TelemetryState {
+    config: TelemetryConfig,
+    queue: TelemetryQueue,
+    session_id: String,
+    in_flight: bool,
+}
+
+/// テレメトリの送信窓口（Dioxusコンテキストで共有する）
+#[derive(Clone)]
+pub struct Telemetry {
+    state: Rc<RefCell<TelemetryState>>,
+}
+
+impl Telemetry {
+    /// 保存済みの設定とオフラインキューを読み込む
+    pub fn new() -> Self {
+        let records = load_item(QUEUE_STORAGE_KEY)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Self {
+            state: Rc::new(RefCell::new(TelemetryState {
+                config: TelemetryConfig::load(),
+                queue: TelemetryQueue::with_records(records),
+                session_id: format!("{:016x}", (js_sys::Math::random() * u64::MAX as f64) as u64),
+                in_flight: false,
+            })),
+        }
+    }
+
+    pub fn config(&self) -> TelemetryConfig {
+        self.state.borrow().config.clone()
+    }
+
+    pub fn set_config(&self, config: TelemetryConfig) {
+        config.save();
+        let mut state = self.state.borrow_mut();
+        if !config.enabled {
+            // オプトアウト時は未送信のデータも破棄する
+            state.queue = TelemetryQueue::default();
+            remove_item(QUEUE_STORAGE_KEY);
+        }
+        state.config = config;
+    }
+
+    pub fn pending(&self) -> usize {
+        self.state.borrow().queue.len()
+    }
+
+    /// レコードをキューに追加（無効時は何もしない）
+    pub fn report(&self, payload: TelemetryPayload) {
+        let mut state = self.state.borrow_mut();
+        if !state.config.enabled {
+            return;
+        }
+
+        let window = web_sys::window();
+        let record = TelemetryRecord {
+            session_id: state.session_id.clone(),
+            recorded_at: js_sys::Date::now(),
+            route: window
+                .as_ref()
+                .and_then(|w| w.location().pathname().ok())
+                .unwrap_or_default(),
+            user_agent: window
+                .as_ref()
+                .and_then(|w| w.navigator().user_agent().ok())
+                .unwrap_or_default(),
+            build_profile: crate::utils::benchmark_export::BenchmarkRun::current_build_profile(),
+            payload,
+        };
+        state.queue.push(record);
+        persist_queue(&state.queue);
+
+        let should_flush = state.queue.len() >= state.config.batch_size;
+        drop(state);
+        if should_flush {
+            self.flush();
+        }
+    }
+
+    /// 1バッチ分をfetchで送信。失敗時はバックオフ後に再送する
+    pub fn flush(&self) {
+        let now = js_sys::Date::now();
+        let (endpoint, batch) = {
+            let mut state = self.state.borrow_mut();
+            if !state.config.enabled || state.in_flight || !state.queue.can_send(now) {
+                return;
+            }
+            let size = state.config.batch_size;
+            state.in_flight = true;
+            (state.config.endpoint.clone(), state.queue.take_batch(size))
+        };
+
+        let body = serde_json::to_string(&batch).unwrap_or_default();
+        let state = self.state.clone();
+        let on_done = Closure::once_into_js(move |ok: bool| {
+            let mut state = state.borrow_mut();
+            state.in_flight = false;
+            if ok {
+                state.queue.on_success();
+            } else {
+                state.queue.on_failure(batch, js_sys::Date::now());
+            }
+            persist_queue(&state.queue);
+        });
+
+        send_telemetry(&endpoint, &body, on_done.unchecked_ref());
+    }
+
+    /// ページ離脱時にsendBeaconで残りを送る（送れなかった分は次回起動時に再送）
+    ///
+    /// fetchで送信中のバッチはキューから取り出し済みなので、送信中でも残りをすべて送る
+    /// （送信中のバッチは `keepalive` のfetchがページ離脱後も送り切る）
+    pub fn flush_with_beacon(&self) {
+        let mut state = self.state.borrow_mut();
+        if !state.config.enabled || state.queue.is_empty() {
+            return;
+        }
+        let body = serde_json::to_string(state.queue.records()).unwrap_or_default();
+        if beacon_telemetry(&state.config.endpoint, &body) {
+            state.queue = TelemetryQueue::default();
+        }
+        persist_queue(&state.queue);
+    }
+
+    /// 定期送信とページ離脱・オンライン復帰の監視を開始
+    ///
+    /// `on_hidden` はビーコン送信の直前に呼ばれ、最終値のレコードを追加できる
+    pub fn start(&self, on_hidden: impl FnMut() + 'static) -> TelemetryLifecycle {
+        let interval_ms = self.state.borrow().config.flush_interval_ms;
+        let telemetry = self.clone();
+        let interval = crate::utils::interval::Interval::new(interval_ms, move || telemetry.flush());
+
+        let telemetry = self.clone();
+        let mut on_hidden = on_hidden;
+        let hidden = Closure::new(move || {
+            on_hidden();
+            telemetry.flush_with_beacon();
+        });
+        let telemetry = self.clone();
+        let online = Closure::new(move || {
+            telemetry.state.borrow_mut().queue.on_success();
+            telemetry.flush();
+        });
+        let remove_listeners = on_telemetry_lifecycle(&hidden, &online);
+
+        TelemetryLifecycle {
+            _interval: interval,
+            _hidden: hidden,
+            _online: online,
+            remove_listeners,
+        }
+    }
+}
+
+/// `Telemetry::start` で登録した監視。ドロップ時に解除する
+pub struct TelemetryLifecycle {
+    _interval: crate::utils::interval::Interval,
+    _hidden: Closure<dyn FnMut()>,
+    _online: Closure<dyn FnMut()>,
+    remove_listeners: js_sys::Function,
+}
+
+impl Drop for TelemetryLifecycle {
+    fn drop(&mut self) {
+        let _ = self.remove_listeners.call0(&JsValue::NULL);
+    }
+}
+
+fn persist_queue(queue: &TelemetryQueue) {
+    if queue.is_empty() {
+        remove_item(QUEUE_STORAGE_KEY);
+    } else if let Ok(json) = serde_json::to_string(queue.records()) {
+        save_item(QUEUE_STORAGE_KEY, &json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: u32) -> TelemetryRecord {
+        TelemetryRecord {
+            session_id: "session".to_string(),
+            recorded_at: id as f64,
+            route: "/map/canvas".to_string(),
+            user_agent: "test".to_string(),
+            build_profile: "debug+wee_alloc".to_string(),
+            payload: TelemetryPayload::Performance(PerformanceMetrics {
+                fps: 60.0,
+                memory_usage: Some(32.0),
+                dropped_frames: id,
+                timestamp: 0.0,
+            }),
+        }
+    }
+
+    #[test]
+    fn failed_batches_are_requeued_in_order_with_backoff() {
+        let mut queue = TelemetryQueue::with_records((0..5).map(record).collect());
+
+        let batch = queue.take_batch(3);
+        assert_eq!(queue.len(), 2);
+
+        queue.on_failure(batch, 1_000.0);
+        let order: Vec<f64> = queue.records().iter().map(|r| r.recorded_at).collect();
+        assert_eq!(order, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert!(!queue.can_send(1_000.0 + RETRY_BASE_MS - 1.0));
+        assert!(queue.can_send(1_000.0 + RETRY_BASE_MS));
+
+        let batch = queue.take_batch(3);
+        queue.on_failure(batch, 10_000.0);
+        assert!(!queue.can_send(10_000.0 + RETRY_BASE_MS));
+        assert!(queue.can_send(10_000.0 + RETRY_BASE_MS * 2.0));
+
+        queue.take_batch(5);
+        queue.on_success();
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(TelemetryQueue::backoff_ms(1), RETRY_BASE_MS);
+        assert_eq!(TelemetryQueue::backoff_ms(2), RETRY_BASE_MS * 2.0);
+        assert_eq!(TelemetryQueue::backoff_ms(40), RETRY_MAX_MS);
+    }
+
+    #[test]
+    fn queue_drops_oldest_when_full() {
+        let mut queue = TelemetryQueue::with_records((0..MAX_QUEUED_RECORDS as u32 + 10).map(record).collect());
+        assert_eq!(queue.len(), MAX_QUEUED_RECORDS);
+        assert_eq!(queue.records()[0].recorded_at, 10.0);
+
+        // 失敗したバッチを戻して溢れた場合も古いもの（戻したバッチの先頭）から捨てる
+        let batch = queue.take_batch(5);
+        let newest = MAX_QUEUED_RECORDS as u32 + 10;
+        queue.push(record(newest));
+        queue.push(record(newest + 1));
+        queue.on_failure(batch, 0.0);
+        assert_eq!(queue.len(), MAX_QUEUED_RECORDS);
+        assert_eq!(queue.records()[0].recorded_at, 12.0);
+        assert_eq!(queue.records().last().unwrap().recorded_at, (newest + 1) as f64);
+    }
+
+    #[test]
+    fn records_serialize_with_kind_tag() {
+        let json = serde_json::to_value(record(1)).unwrap();
+        assert_eq!(json["kind"], "performance");
+        assert_eq!(json["data"]["dropped_frames"], 1);
+        assert_eq!(serde_json::from_value::<TelemetryRecord>(json).unwrap(), record(1));
+    }
+}
@@ -0,0 +1,412 @@
+//! タイル読み込みへの障害注入
+//!
+//! マップのタイルレイヤーを、設定に応じて遅延・通信エラー・429/503・途中で切れた画像・壊れた画像を
+//! 返すレイヤーに差し替える。失敗したタイルは指数バックオフで再試行し、諦めたら代替タイルを表示する。
+//! 確率の累積と再試行間隔はRust側で計算してJSへ渡す。
+
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(inline_js = r#"
+let faults = null;
+const emptyStats = () => ({
+    requests: 0, loaded: 0, retries: 0, fallbacks: 0,
+    injected_errors: 0, injected_429: 0, injected_503: 0,
+    truncated: 0, corrupted: 0, decode_errors: 0, total_latency_ms: 0,
+});
+let counters = emptyStats();
+
+const sleep = (ms) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// ネットワークを使わずに座標入りのタイルを生成する
+function localTile(coords) {
+    const canvas = document.createElement('canvas');
+    canvas.width = canvas.height = 256;
+    const ctx = canvas.getContext('2d');
+    ctx.fillStyle = (coords.x + coords.y) % 2 === 0 ? '#e8eef2' : '#dde6eb';
+    ctx.fillRect(0, 0, 256, 256);
+    ctx.strokeStyle = '#b0bec5';
+    ctx.strokeRect(0, 0, 256, 256);
+    ctx.fillStyle = '#607d8b';
+    ctx.font = '14px monospace';
+    ctx.fillText(`${coords.z}/${coords.x}/${coords.y}`, 8, 20);
+    return new Promise((resolve) => canvas.toBlob(resolve, 'image/png'));
+}
+
+async function fetchBytes(url, coords) {
+    if (faults.provider === 'local') {
+        return new Uint8Array(await (await localTile(coords)).arrayBuffer());
+    }
+    const response = await fetch(url, { mode: 'cors' });
+    if (!response.ok) throw { kind: 'http', status: response.status };
+    return new Uint8Array(await response.arrayBuffer());
+}
+
+// 1回分の取得。注入した障害は例外として投げる
+async function attempt(url, coords) {
+    const delay = faults.latency_ms + Math.random() * faults.latency_jitter_ms;
+    if (delay > 0) await sleep(delay);
+    const roll = Math.random();
+    const t = faults.thresholds;
+    if (roll < t.error) { counters.injected_errors++; throw { kind: 'network' }; }
+    if (roll < t.too_many_requests) { counters.injected_429++; throw { kind: 'http', status: 429, retryAfterMs: faults.retry_after_ms }; }
+    if (roll < t.unavailable) { counters.injected_503++; throw { kind: 'http', status: 503 }; }
+    let bytes = await fetchBytes(url, coords);
+    if (roll < t.truncate) {
+        counters.truncated++;
+        bytes = bytes.slice(0, Math.floor(bytes.length / 2));
+    } else if (roll < t.corrupt) {
+        counters.corrupted++;
+        bytes = bytes.slice();
+        for (let i = 0; i < 64 && bytes.length > 8; i++) {
+            bytes[8 + Math.floor(Math.random() * (bytes.length - 8))] ^= 0xff;
+        }
+    }
+    return bytes;
+}
+
+function decode(tile, bytes) {
+    return new Promise((resolve, reject) => {
+        const src = URL.createObjectURL(new Blob([bytes], { type: 'image/png' }));
+        tile.onload = () => { URL.revokeObjectURL(src); resolve(); };
+        tile.onerror = () => { URL.revokeObjectURL(src); reject({ kind: 'decode' }); };
+        tile.src = src;
+    });
+}
+
+function drawFallback(tile, coords, error) {
+    const canvas = document.createElement('canvas');
+    canvas.width = canvas.height = 256;
+    const ctx = canvas.getContext('2d');
+    ctx.fillStyle = '#f5f5f5';
+    ctx.fillRect(0, 0, 256, 256);
+    ctx.strokeStyle = '#e57373';
+    ctx.beginPath();
+    ctx.moveTo(0, 0); ctx.lineTo(256, 256); ctx.moveTo(256, 0); ctx.lineTo(0, 256);
+    ctx.stroke();
+    ctx.fillStyle = '#c62828';
+    ctx.font = '14px monospace';
+    const reason = error && error.status ? `HTTP ${error.status}` : (error ? error.kind : 'error');
+    ctx.fillText(`${coords.z}/${coords.x}/${coords.y} ${reason}`, 8, 20);
+    tile.onload = null;
+    tile.onerror = null;
+    tile.classList.add('chaos-fallback-tile');
+    tile.src = canvas.toDataURL();
+}
+
+export function installFaultyTileLayer() {
+    if (window.__tileLayerFactory) return true;
+    if (typeof L === 'undefined') return false;
+    const FaultyTileLayer = L.TileLayer.extend({
+        createTile(coords, done) {
+            if (!faults || !faults.enabled) {
+                return L.TileLayer.prototype.createTile.call(this, coords, done);
+            }
+            const tile = document.createElement('img');
+            tile.alt = '';
+            tile.setAttribute('role', 'presentation');
+            const url = this.getTileUrl(coords);
+            const config = faults;
+            counters.requests++;
+            const started = performance.now();
+            (async () => {
+                let lastError = null;
+                for (let i = 0; i <= config.retry_delays_ms.length; i++) {
+                    if (i > 0) {
+                        counters.retries++;
+                        await sleep(Math.max(config.retry_delays_ms[i - 1], (lastError && lastError.retryAfterMs) || 0));
+                    }
+                    try {
+                        await decode(tile, await attempt(url, coords));
+                        counters.loaded++;
+                        counters.total_latency_ms += performance.now() - started;
+                        done(null, tile);
+                        return;
+                    } catch (e) {
+                        lastError = e;
+                        if (e && e.kind === 'decode') counters.decode_errors++;
+                    }
+                }
+                counters.fallbacks++;
+                drawFallback(tile, coords, lastError);
+                done(null, tile);
+            })();
+            return tile;
+        },
+    });
+    window.__tileLayerFactory = (url, options) => {
+        const layer = new FaultyTileLayer(url, options);
+        window.__faultTileLayer = layer;
+        return layer;
+    };
+    return true;
+}
+
+export function uninstallFaultyTileLayer() {
+    delete window.__tileLayerFactory;
+    faults = null;
+}
+
+export function setTileFaults(json) {
+    faults = json ? JSON.parse(json) : null;
+    const layer = window.__faultTileLayer;
+    if (layer && layer._map) layer.redraw();
+}
+
+export function tileFaultStats() {
+    return JSON.stringify(counters);
+}
+
+export function resetTileFaultStats() {
+    counters = emptyStats();
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = installFaultyTileLayer)]
+    fn install_faulty_tile_layer() -> bool;
+
+    #[wasm_bindgen(js_name = uninstallFaultyTileLayer)]
+    fn uninstall_faulty_tile_layer();
+
+    #[wasm_bindgen(js_name = setTileFaults)]
+    fn set_tile_faults(json: &str);
+
+    #[wasm_bindgen(js_name = tileFaultStats)]
+    fn tile_fault_stats() -> String;
+
+    #[wasm_bindgen(js_name = resetTileFaultStats)]
+    fn reset_tile_fault_stats();
+}
+
+/// 再試行間隔の上限（ms）
+const MAX_RETRY_DELAY_MS: f64 = 8000.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileProvider {
+    /// OpenStreetMapのタイルを取得する
+    Remote,
+    /// ネットワークを使わずに生成する（障害以外の揺らぎを除くため）
+    Local,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TileFaultConfig {
+    pub enabled: bool,
+    pub provider: TileProvider,
+    /// 通信エラーの確率（0〜1、以下同様）
+    pub error_rate: f64,
+    pub too_many_requests_rate: f64,
+    pub unavailable_rate: f64,
+    /// 画像の後半を切り捨てる確率
+    pub truncate_rate: f64,
+    /// 画像のバイトを書き換える確率
+    pub corrupt_rate: f64,
+    /// 1回の取得ごとに加える遅延（ms）
+    pub latency_ms: f64,
+    pub latency_jitter_ms: f64,
+    pub max_retries: u32,
+    /// 1回目の再試行までの間隔。以降は倍々に伸ばす
+    pub retry_base_ms: f64,
+    /// 429の `Retry-After` に相当する待ち時間（ms）
+    pub retry_after_ms: f64,
+}
+
+impl Default for TileFaultConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: TileProvider::Remote,
+            error_rate: 0.1,
+            too_many_requests_rate: 0.05,
+            unavailable_rate: 0.05,
+            truncate_rate: 0.05,
+            corrupt_rate: 0.05,
+            latency_ms: 200.0,
+            latency_jitter_ms: 300.0,
+            max_retries: 3,
+            retry_base_ms: 250.0,
+            retry_after_ms: 1000.0,
+        }
+    }
+}
+
+/// JS側で1回の乱数と比べる累積確率
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct FaultThresholds {
+    pub error: f64,
+    pub too_many_requests: f64,
+    pub unavailable: f64,
+    pub truncate: f64,
+    pub corrupt: f64,
+}
+
+#[derive(Serialize)]
+struct JsTileFaults {
+    enabled: bool,
+    provider: TileProvider,
+    latency_ms: f64,
+    latency_jitter_ms: f64,
+    retry_after_ms: f64,
+    thresholds: FaultThresholds,
+    retry_delays_ms: Vec<f64>,
+}
+
+impl TileFaultConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let rates = [
+            ("error_rate", self.error_rate),
+            ("too_many_requests_rate", self.too_many_requests_rate),
+            ("unavailable_rate", self.unavailable_rate),
+            ("truncate_rate", self.truncate_rate),
+            ("corrupt_rate", self.corrupt_rate),
+        ];
+        if let Some((name, _)) = rates.iter().find(|(_, rate)| !(0.0..=1.0).contains(rate)) {
+            return Err(format!("{} must be within 0..=1", name));
+        }
+        if rates.iter().map(|(_, rate)| rate).sum::<f64>() > 1.0 {
+            return Err("fault rates must not add up to more than 1".to_string());
+        }
+        if !(self.latency_ms >= 0.0 && self.latency_jitter_ms >= 0.0 && self.retry_base_ms >= 0.0 && self.retry_after_ms >= 0.0) {
+            return Err("delays must not be negative".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn thresholds(&self) -> FaultThresholds {
+        let error = self.error_rate;
+        let too_many_requests = error + self.too_many_requests_rate;
+        let unavailable = too_many_requests + self.unavailable_rate;
+        let truncate = unavailable + self.truncate_rate;
+        FaultThresholds { error, too_many_requests, unavailable, truncate, corrupt: truncate + self.corrupt_rate }
+    }
+
+    /// n回目の再試行までの待ち時間（指数バックオフ、上限あり）
+    pub fn retry_delays_ms(&self) -> Vec<f64> {
+        (0..self.max_retries)
+            .map(|attempt| (self.retry_base_ms * 2f64.powi(attempt as i32)).min(MAX_RETRY_DELAY_MS))
+            .collect()
+    }
+
+    fn to_js_json(&self) -> String {
+        serde_json::to_string(&JsTileFaults {
+            enabled: self.enabled,
+            provider: self.provider,
+            latency_ms: self.latency_ms,
+            latency_jitter_ms: self.latency_jitter_ms,
+            retry_after_ms: self.retry_after_ms,
+            thresholds: self.thresholds(),
+            retry_delays_ms: self.retry_delays_ms(),
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// 注入した障害とタイル読み込みの結果
+#[cfg(feature = "chaos-testing")]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct TileFaultStats {
+    pub requests: u32,
+    pub loaded: u32,
+    pub retries: u32,
+    /// 再試行しても読めずに代替タイルを表示した数
+    pub fallbacks: u32,
+    pub injected_errors: u32,
+    pub injected_429: u32,
+    pub injected_503: u32,
+    pub truncated: u32,
+    pub corrupted: u32,
+    /// 画像として読めなかった数（切り捨て・破損のうち実際に失敗したもの）
+    pub decode_errors: u32,
+    pub total_latency_ms: f64,
+}
+
+#[cfg(feature = "chaos-testing")]
+impl TileFaultStats {
+    /// 読み込めたタイルの、再試行を含む平均読み込み時間
+    pub fn avg_latency_ms(&self) -> f64 {
+        if self.loaded == 0 { 0.0 } else { self.total_latency_ms / self.loaded as f64 }
+    }
+}
+
+/// マップのタイルレイヤーを障害注入付きのものに差し替える。ドロップ時に元へ戻す
+///
+/// 差し替えはこれ以降に初期化されたマップに効くため、マップより先に作ること。
+#[cfg(feature = "chaos-testing")]
+pub struct TileFaultInjector;
+
+#[cfg(feature = "chaos-testing")]
+impl TileFaultInjector {
+    pub fn new() -> Self {
+        install_faulty_tile_layer();
+        Self
+    }
+
+    /// 設定を反映し、表示中のタイルを読み込み直す
+    pub fn apply(&self, config: &TileFaultConfig) -> Result<(), String> {
+        config.validate()?;
+        if !install_faulty_tile_layer() {
+            return Err("Leaflet is not loaded".to_string());
+        }
+        set_tile_faults(&config.to_js_json());
+        Ok(())
+    }
+
+    pub fn stats(&self) -> TileFaultStats {
+        serde_json::from_str(&tile_fault_stats()).unwrap_or_default()
+    }
+
+    pub fn reset_stats(&self) {
+        reset_tile_fault_stats();
+    }
+}
+
+#[cfg(feature = "chaos-testing")]
+impl Drop for TileFaultInjector {
+    fn drop(&mut self) {
+        set_tile_faults("");
+        uninstall_faulty_tile_layer();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_accumulate_and_rates_are_validated() {
+        let config = TileFaultConfig {
+            error_rate: 0.1,
+            too_many_requests_rate: 0.2,
+            unavailable_rate: 0.1,
+            truncate_rate: 0.05,
+            corrupt_rate: 0.05,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        let t = config.thresholds();
+        assert!((t.too_many_requests - 0.3).abs() < 1e-12);
+        assert!((t.unavailable - 0.4).abs() < 1e-12);
+        assert!((t.corrupt - 0.5).abs() < 1e-12);
+
+        let too_much = TileFaultConfig { error_rate: 0.8, ..config.clone() };
+        assert!(too_much.validate().unwrap_err().contains("add up"));
+        let negative = TileFaultConfig { corrupt_rate: -0.1, ..config };
+        assert!(negative.validate().unwrap_err().contains("corrupt_rate"));
+    }
+
+    #[test]
+    fn retries_back_off_exponentially_up_to_a_cap() {
+        let config = TileFaultConfig { max_retries: 7, retry_base_ms: 250.0, ..Default::default() };
+        assert_eq!(
+            config.retry_delays_ms(),
+            vec![250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 8000.0]
+        );
+        assert!(TileFaultConfig { max_retries: 0, ..Default::default() }.retry_delays_ms().is_empty());
+
+        let json: serde_json::Value = serde_json::from_str(&config.to_js_json()).unwrap();
+        assert_eq!(json["provider"], "remote");
+        assert_eq!(json["retry_delays_ms"].as_array().unwrap().len(), 7);
+    }
+}